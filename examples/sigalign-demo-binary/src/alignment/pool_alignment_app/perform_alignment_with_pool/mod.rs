@@ -15,7 +15,7 @@ use sigalign_utils::{
     },
     sequence_manipulation::reverse_complementary::reverse_complement_of_dna_sequence_in_place,
 };
-use sigalign_impl::sequence_storage::in_memory::InMemoryBuffer;
+use sigalign_impl::sequence_storage::dynamic_sequence_storage::DynamicSequenceBuffer;
 
 pub struct ThreadPool {
     workers: Vec<Worker>,
@@ -154,7 +154,7 @@ impl Worker {
 
         let thread = thread::spawn(move || {
             let stdout = std::io::stdout();
-            let mut reference_sequence_buffer = InMemoryBuffer::new();
+            let mut reference_sequence_buffer = DynamicSequenceBuffer::new();
 
             loop {
                 let msg = job_receiver.lock().unwrap().recv();
@@ -181,6 +181,7 @@ impl Worker {
                         result.0.into_iter().for_each(|TargetAlignmentResult {
                             index: target_index,
                             alignments: anchor_results,
+                            ..
                         }| {
                             anchor_results.into_iter().for_each(|anchor_result| {
                                 string_result.push_str(&format!(
@@ -203,6 +204,7 @@ impl Worker {
                         result.0.into_iter().for_each(|TargetAlignmentResult {
                             index: target_index,
                             alignments: anchor_results,
+                            ..
                         }| {
                             anchor_results.into_iter().for_each(|anchor_result| {
                                 string_result.push_str(&format!(
@@ -240,6 +242,7 @@ fn operations_to_string(operations: &Vec<AlignmentOperations>) -> String {
             match op.operation {
                 AlignmentOperation::Match => 'M',
                 AlignmentOperation::Subst => 'S',
+                AlignmentOperation::AmbiguousMatch => 'A',
                 AlignmentOperation::Insertion => 'I',
                 AlignmentOperation::Deletion => 'D',
             },
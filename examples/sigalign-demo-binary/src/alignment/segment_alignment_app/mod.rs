@@ -280,6 +280,7 @@ fn write_alignment_result_as_tsv<D: Direction>(
     result.0.into_iter().for_each(|TargetAlignmentResult {
         index: target_index,
         alignments: anchor_results,
+        ..
     }| {
         anchor_results.into_iter().for_each(|anchor_result| {
             let _ = buf_writer.write(label).unwrap();
@@ -310,6 +311,7 @@ fn write_alignment_result_as_tsv<D: Direction>(
                     match operation {
                         AlignmentOperation::Match => b"M",
                         AlignmentOperation::Subst => b"S",
+                        AlignmentOperation::AmbiguousMatch => b"A",
                         AlignmentOperation::Insertion => b"I",
                         AlignmentOperation::Deletion => b"D",
                     }
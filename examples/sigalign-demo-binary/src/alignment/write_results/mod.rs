@@ -21,6 +21,7 @@ pub fn write_alignment_result_as_tsv<D: Direction>(
     result.0.into_iter().for_each(|TargetAlignmentResult {
         index: target_index,
         alignments: anchor_results,
+        ..
     }| {
         anchor_results.into_iter().for_each(|anchor_result| {
             let _ = buf_writer.write(query_id).unwrap();
@@ -51,7 +52,7 @@ pub fn write_alignment_result_as_tsv<D: Direction>(
                 let _ = buf_writer.write(
                     match operation {
                         AlignmentOperation::Match => b"=",
-                        AlignmentOperation::Subst => b"X",
+                        AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => b"X",
                         AlignmentOperation::Insertion => b"I",
                         AlignmentOperation::Deletion => b"D",
                     }
@@ -24,8 +24,14 @@ use sigalign_utils::{
     sequence_reader::decompress::get_gzip_decoder,
 };
 use sigalign_impl::{
-    pattern_index::dynamic_lfi::DynamicLfiOption,
-    sequence_storage::in_memory::InMemoryStorage,
+    pattern_index::{
+        dynamic_lfi::DynamicLfiOption,
+        dynamic_pattern_index::DynamicPatternIndexOption,
+    },
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        dynamic_sequence_storage::DynamicSequenceStorage,
+    },
 };
 
 const MAXIMUM_LENGTH: u32 = u32::MAX - 1;
@@ -178,7 +184,7 @@ impl ReferenceConfig {
             };
             let filled_sequence_storages = sequence_storage.fill_fasta_until_max_length(
                 readable_file,
-                self.max_length,
+                self.max_length as u64,
             )?;
             let after_fill_num_targets = sequence_storage.num_targets();
             let num_targets_in_input_file = {
@@ -284,7 +290,7 @@ fn get_reference_with_default_option(
     // Maximum: 200 MiB
     let lookup_table_max_bytes_size = u64::min(
         200 * 1024 * 1024,
-        (total_length / 8) as u64,
+        total_length / 8,
     );
     let dynamic_lfi_option = DynamicLfiOption {
         suffix_array_sampling_ratio: 1,
@@ -292,8 +298,8 @@ fn get_reference_with_default_option(
         use_safe_guard: USE_SAFE_GUARD,
     };
     let raw_reference = RawReference::new(
-        sequence_storage,
-        dynamic_lfi_option,
+        DynamicSequenceStorage::InMemory(sequence_storage),
+        DynamicPatternIndexOption::Lfi(dynamic_lfi_option),
     )?;
     let reference = Reference::from_raw(raw_reference);
     Ok(reference)
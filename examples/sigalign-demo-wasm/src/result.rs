@@ -68,6 +68,7 @@ fn operations_to_string(operations: &Vec<AlignmentOperations>) -> String {
             match ops.operation {
                 AlignmentOperation::Match => 'M',
                 AlignmentOperation::Subst => 'S',
+                AlignmentOperation::AmbiguousMatch => 'A',
                 AlignmentOperation::Insertion => 'I',
                 AlignmentOperation::Deletion => 'D',
             },
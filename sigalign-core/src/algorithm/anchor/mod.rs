@@ -1,4 +1,4 @@
-use crate::core::BufferedPatternLocator;
+use crate::core::{BufferedPatternLocator, SearchRange, stats::StatsCollector};
 use ahash::AHashMap;
 
 mod unsafe_marking;
@@ -26,36 +26,48 @@ pub type AnchorIndex = (u32, u32);
 
 impl AnchorTable {
     #[inline]
-    pub fn new_by_target_index<L: BufferedPatternLocator>(
+    pub fn new_by_target_index<L: BufferedPatternLocator, C: StatsCollector>(
         pattern_locater: &L,
         query: &[u8],
         sorted_target_indices: &[u32],
         pattern_size: u32,
+        pattern_occurrence_cap: Option<u32>,
+        stats: &mut C,
     ) -> AHashMap<u32, Self> {
         let qry_len = query.len();
         let pattern_count = qry_len / pattern_size as usize;
+        stats.add_pattern_count(pattern_count as u64);
 
         let mut anchor_table_by_target_index: AHashMap<u32, Self> = AHashMap::new();
 
+        let timer = C::start_timer();
+
+        let search_range = SearchRange::new(sorted_target_indices);
         (0..pattern_count).for_each(|pattern_index| {
             let qry_pos = pattern_index * pattern_size as usize;
             let pattern = &query[qry_pos..qry_pos+pattern_size as usize];
             
-            let pattern_locations = pattern_locater.locate(pattern, sorted_target_indices);
+            // The pattern over the occurrence cap is dropped (counted in the `AlignmentStats`).
+            let Some(pattern_locations) = pattern_locater.locate_with_stats(pattern, &search_range, pattern_occurrence_cap, stats) else {
+                return
+            };
+            if !pattern_locations.is_empty() {
+                stats.add_located_pattern();
+            }
 
-            pattern_locations.into_iter().for_each(|pattern_location| {
+            pattern_locations.iter().for_each(|pattern_location| {
                 match anchor_table_by_target_index.get_mut(&pattern_location.target_index) {
                     Some(anchor_table) => {
                         anchor_table.add_new_positions(
                             pattern_index,
-                            pattern_location.sorted_positions,
+                            &pattern_location.sorted_positions,
                         )
                     },
                     None => {
                         let mut new_pos_table = Self::new_empty(pattern_count);
                         new_pos_table.add_new_positions(
                             pattern_index,
-                            pattern_location.sorted_positions,
+                            &pattern_location.sorted_positions,
                         );
                         anchor_table_by_target_index.insert(pattern_location.target_index, new_pos_table);
                     }
@@ -63,16 +75,37 @@ impl AnchorTable {
             });
         });
 
+        stats.add_pattern_locating_time(timer);
+
+        if C::ENABLED {
+            stats.add_anchors_before_merge(Self::count_anchors_of_tables(&anchor_table_by_target_index));
+        }
+        let timer = C::start_timer();
         anchor_table_by_target_index.iter_mut().for_each(|(_, pos_table)| {
             pos_table.merge_ungapped_anchors(pattern_size);
         });
+        stats.add_anchor_merging_time(timer);
+        if C::ENABLED {
+            stats.add_anchors_after_merge(Self::count_anchors_of_tables(&anchor_table_by_target_index));
+        }
 
         anchor_table_by_target_index
     }
+    fn count_anchors_of_tables(anchor_table_by_target_index: &AHashMap<u32, Self>) -> u64 {
+        anchor_table_by_target_index.values().map(|anchor_table| {
+            anchor_table.0.iter().map(|anchors| anchors.len() as u64).sum::<u64>()
+        }).sum()
+    }
+    /// Number of anchors marked as skipped
+    pub fn count_skipped_anchors(&self) -> u64 {
+        self.0.iter().map(|anchors| {
+            anchors.iter().filter(|anchor| anchor.skipped).count() as u64
+        }).sum()
+    }
     fn add_new_positions(
         &mut self,
         pattern_index: usize,
-        sorted_target_positions: Vec<u32>,
+        sorted_target_positions: &[u32],
     ) {
         self.0[pattern_index] = Anchor::new_vec(sorted_target_positions);
     }
@@ -94,8 +127,8 @@ impl AnchorTable {
 }
 
 impl Anchor {
-    fn new_vec(sorted_target_positions: Vec<u32>) -> Vec<Self> {
-        sorted_target_positions.into_iter().map(|pos| {
+    fn new_vec(sorted_target_positions: &[u32]) -> Vec<Self> {
+        sorted_target_positions.iter().map(|&pos| {
             Self {
                 target_position: pos,
                 pattern_count: 1,
@@ -3,6 +3,7 @@ use crate::results::{
     AlignmentOperations,
     AlignmentOperation,
     AlignmentPosition,
+    Strand,
 };
 
 // Preliminary form of alignment result
@@ -53,6 +54,7 @@ impl Extension {
             length: self.length,
             position: self.alignment_position.clone(),
             operations,
+            strand: Strand::Forward,
         }
     }
 }
@@ -1,6 +1,9 @@
 use crate::{
-    core::regulators::{
-        Penalty, Cutoff,
+    core::{
+        regulators::{
+            Penalty, Cutoff,
+        },
+        stats::StatsCollector,
     },
     results::{
         AlignmentPosition, AlignmentOperations,
@@ -19,13 +22,14 @@ pub use valid_position_candidate::Vpc;
 
 // Assuming leftmost anchor
 #[inline]
-pub fn extend_anchor(
+pub fn extend_anchor<C: StatsCollector>(
     anchor_table: &AnchorTable,
     anchor_index: AnchorIndex,
     pattern_size: &u32,
     spare_penalty_calculator: &SparePenaltyCalculator,
     target: &[u8],
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     // Buffers
@@ -36,7 +40,9 @@ pub fn extend_anchor(
     operations_buffer: &mut Vec<AlignmentOperations>,
     traversed_anchor_index_buffer: &mut Vec<AnchorIndex>,
     extension_buffer: &mut Vec<Extension>,
+    stats: &mut C,
 ) {
+    stats.add_extended_anchor();
     // 1. Init
     let anchor = &anchor_table.0[anchor_index.0 as usize][anchor_index.1 as usize];
     // 1.1. Define the range of sequence to extend    
@@ -53,15 +59,20 @@ pub fn extend_anchor(
     // 2.1. Get slices to extend
     let right_target_slice = &target[right_target_start_index as usize..];
     let right_query_slice = &query[right_query_start_index as usize..];
+    let right_quality_slice = query_qualities.map(|x| &x[right_query_start_index as usize..]);
     // 2.2. Calculate the left spare penalty
     let right_spare_penalty = spare_penalty_calculator.get_right_spare_penalty(anchor_index.0);
     // 2.3. Extend the side with wave front
     right_wave_front.align_right_to_end_point(
         right_target_slice,
         right_query_slice,
+        right_quality_slice,
         penalties,
         right_spare_penalty,
     );
+    if C::ENABLED {
+        stats.add_filled_wave_front_cells(right_wave_front.count_filled_cells());
+    }
     // 2.4. Fill sorted vpc vector buffer
     right_vpc_buffer.clear();
     right_wave_front.fill_sorted_vpc_vector(
@@ -73,6 +84,7 @@ pub fn extend_anchor(
     // 3.1. Get slices to extend
     let left_target_slice = &target[..left_target_end_index as usize];
     let left_query_slice = &query[..left_query_end_index as usize];
+    let left_quality_slice = query_qualities.map(|x| &x[..left_query_end_index as usize]);
     // 3.2. Calculate the left spare penalty
     let max_scaled_penalty_delta_of_right = right_vpc_buffer[0].scaled_penalty_delta
         + (anchor_size * cutoff.maximum_scaled_penalty_per_length) as i64
@@ -85,9 +97,13 @@ pub fn extend_anchor(
     left_wave_front.align_left_to_end_point(
         left_target_slice,
         left_query_slice,
+        left_quality_slice,
         penalties,
         left_spare_penalty,
     );
+    if C::ENABLED {
+        stats.add_filled_wave_front_cells(left_wave_front.count_filled_cells());
+    }
     // 3.4. Fill sorted vpc vector buffer
     left_vpc_buffer.clear();
     left_wave_front.fill_sorted_vpc_vector(
@@ -104,7 +120,7 @@ pub fn extend_anchor(
     );
     // 4.2. Backtrace left
     let left_optimal_vpc = &left_vpc_buffer[optimal_left_vpc_index];
-    let left_back_trace_result = left_wave_front.backtrace_of_left_side(
+    let mut left_back_trace_result = left_wave_front.backtrace_of_left_side(
         left_optimal_vpc.penalty,
         *pattern_size,
         left_optimal_vpc.component_index,
@@ -112,7 +128,7 @@ pub fn extend_anchor(
         operations_buffer,
         traversed_anchor_index_buffer,
     );
-    transform_left_additive_position_to_traversed_anchor_index(
+    left_back_trace_result.traversed_anchor_range = transform_left_additive_position_to_traversed_anchor_index(
         anchor_table,
         traversed_anchor_index_buffer,
         anchor_index.0,
@@ -121,7 +137,7 @@ pub fn extend_anchor(
     );
     // 4.2. Backtrace right
     let right_optimal_vpc = &right_vpc_buffer[optimal_right_vpc_index];
-    let right_back_trace_result = right_wave_front.backtrace_of_right_side(
+    let mut right_back_trace_result = right_wave_front.backtrace_of_right_side(
         right_optimal_vpc.penalty,
         *pattern_size,
         pattern_count,
@@ -130,7 +146,7 @@ pub fn extend_anchor(
         operations_buffer,
         traversed_anchor_index_buffer,
     );
-    transform_right_additive_position_to_traversed_anchor_index(
+    right_back_trace_result.traversed_anchor_range = transform_right_additive_position_to_traversed_anchor_index(
         anchor_table,
         traversed_anchor_index_buffer,
         anchor_index.0,
@@ -14,6 +14,8 @@ use wave_front::{
     BackTraceResult,
 };
 pub use wave_front::WaveFront;
+#[cfg(feature = "switchable_match_counting")]
+pub use wave_front::use_scalar_match_counting;
 
 mod spare_penalty;
 pub use spare_penalty::SparePenaltyCalculator;
@@ -12,6 +12,8 @@ enum ComponentType {
     M,
     D,
     I,
+    D2,
+    I2,
 }
 
 impl WaveFront {
@@ -80,7 +82,7 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            penalty -= penalties.mismatch_penalty_of_index(component.mismatch_index);
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -109,17 +111,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromD => {
+                        BackTraceMarker::FromD | BackTraceMarker::FromD2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::D;
-                            // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromD {
+                                (ComponentType::D, wave_front_score.d_component_of_k(k))
+                            } else {
+                                (ComponentType::D2, wave_front_score.d2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -139,17 +143,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromI => {
+                        BackTraceMarker::FromI | BackTraceMarker::FromI2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::I;
-                            // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromI {
+                                (ComponentType::I, wave_front_score.i_component_of_k(k))
+                            } else {
+                                (ComponentType::I2, wave_front_score.i2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -172,6 +178,10 @@ impl WaveFront {
                         _ => { // START_POINT
                             // Add operation
                             //  - skip
+                            // With the free ambiguous matches, the patterns in the match runs are not always anchors.
+                            if penalties.ambiguous_match == Some(0) {
+                                traversed_anchor_index_buffer.truncate(traversed_anchor_start_index as usize);
+                            }
                             return (
                                 traversed_anchor_start_index,
                                 traversed_anchor_index_buffer.len() as u32,
@@ -180,11 +190,13 @@ impl WaveFront {
                     }
                 },
                 /* D */
-                ComponentType::D => {
+                ComponentType::D | ComponentType::D2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::D2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -202,7 +214,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_D
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -210,7 +222,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.d2_component_of_k(k)
+                            } else {
+                                wave_front_score.d_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -221,11 +237,13 @@ impl WaveFront {
                     }
                 },
                 /* I */
-                ComponentType::I => {
+                ComponentType::I | ComponentType::I2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::I2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -243,7 +261,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_I
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -251,7 +269,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.i2_component_of_k(k)
+                            } else {
+                                wave_front_score.i_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -295,7 +317,7 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            penalty -= penalties.mismatch_penalty_of_index(component.mismatch_index);
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -325,17 +347,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromD => {
+                        BackTraceMarker::FromD | BackTraceMarker::FromD2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::D;
-                            // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromD {
+                                (ComponentType::D, wave_front_score.d_component_of_k(k))
+                            } else {
+                                (ComponentType::D2, wave_front_score.d2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -356,17 +380,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromI => {
+                        BackTraceMarker::FromI | BackTraceMarker::FromI2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::I;
-                            // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromI {
+                                (ComponentType::I, wave_front_score.i_component_of_k(k))
+                            } else {
+                                (ComponentType::I2, wave_front_score.i2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -390,6 +416,10 @@ impl WaveFront {
                         _ => { // START_POINT
                             // Add operation
                             //  - skip
+                            // With the free ambiguous matches, the patterns in the match runs are not always anchors.
+                            if penalties.ambiguous_match == Some(0) {
+                                traversed_anchor_index_buffer.truncate(traversed_anchor_start_index as usize);
+                            }
                             return (
                                 traversed_anchor_start_index,
                                 traversed_anchor_index_buffer.len() as u32,
@@ -398,11 +428,13 @@ impl WaveFront {
                     }
                 },
                 /* D */
-                ComponentType::D => {
+                ComponentType::D | ComponentType::D2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::D2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -420,7 +452,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_D
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -428,7 +460,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.d2_component_of_k(k)
+                            } else {
+                                wave_front_score.d_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -439,11 +475,13 @@ impl WaveFront {
                     }
                 },
                 /* I */
-                ComponentType::I => {
+                ComponentType::I | ComponentType::I2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::I2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -461,7 +499,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_I
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -469,7 +507,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.i2_component_of_k(k)
+                            } else {
+                                wave_front_score.i_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
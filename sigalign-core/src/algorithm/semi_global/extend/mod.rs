@@ -1,6 +1,9 @@
 use crate::{
-    core::regulators::{
-        Penalty, Cutoff, PREC_SCALE,
+    core::{
+        regulators::{
+            Penalty, Cutoff, PREC_SCALE,
+        },
+        stats::StatsCollector,
     },
     results::{
         AlignmentPosition, AlignmentOperations,
@@ -30,13 +33,14 @@ pub struct SemiGlobalExtension {
 
 // Assuming leftmost anchor
 #[inline]
-pub fn extend_anchor(
+pub fn extend_anchor<C: StatsCollector>(
     anchor_table: &AnchorTable,
     anchor_index: AnchorIndex,
     pattern_size: &u32,
     spare_penalty_calculator: &SparePenaltyCalculator,
     target: &[u8],
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     // Buffers
@@ -44,7 +48,9 @@ pub fn extend_anchor(
     operations_buffer: &mut Vec<AlignmentOperations>,
     traversed_anchor_index_buffer: &mut Vec<AnchorIndex>,
     extension_buffer: &mut Vec<Extension>,
+    stats: &mut C,
 ) {
+    stats.add_extended_anchor();
     // 1. Init
     let anchor = &anchor_table.0[anchor_index.0 as usize][anchor_index.1 as usize];
     // 1.1. Define the range of sequence to extend
@@ -61,15 +67,20 @@ pub fn extend_anchor(
     // 2.1. Get slices to extend
     let right_target_slice = &target[right_target_start_index as usize..];
     let right_query_slice = &query[right_query_start_index as usize..];
+    let right_quality_slice = query_qualities.map(|x| &x[right_query_start_index as usize..]);
     // 2.2. Calculate the left spare penalty
     let right_spare_penalty = spare_penalty_calculator.get_right_spare_penalty(anchor_index.0);
     // 2.3. Extend the side with wave front
     wave_front.align_right_to_end_point(
         right_target_slice,
         right_query_slice,
+        right_quality_slice,
         penalties,
         right_spare_penalty,
     );
+    if C::ENABLED {
+        stats.add_filled_wave_front_cells(wave_front.count_filled_cells());
+    }
     // 2.4. Check if invalid
     //   - confirm invalid: early drop here
     if !wave_front.is_reached_to_sequence_end() {
@@ -82,7 +93,7 @@ pub fn extend_anchor(
             penalties,
             traversed_anchor_index_buffer,
         );
-        transform_right_additive_position_to_traversed_anchor_index(
+        let right_traversed_anchor_range = transform_right_additive_position_to_traversed_anchor_index(
             anchor_table,
             traversed_anchor_index_buffer,
             anchor_index.0,
@@ -108,14 +119,14 @@ pub fn extend_anchor(
         return;
     }
     //   - have chance to valid: proceed
-    let right_back_trace_result = wave_front.backtrace_from_the_end_of_right_side(
+    let mut right_back_trace_result = wave_front.backtrace_from_the_end_of_right_side(
         *pattern_size,
         pattern_count,
         penalties,
         operations_buffer,
         traversed_anchor_index_buffer,
     );
-    transform_right_additive_position_to_traversed_anchor_index(
+    right_back_trace_result.traversed_anchor_range = transform_right_additive_position_to_traversed_anchor_index(
         anchor_table,
         traversed_anchor_index_buffer,
         anchor_index.0,
@@ -128,6 +139,7 @@ pub fn extend_anchor(
     // 3.1. Get slices to extend
     let left_target_slice = &target[..left_target_end_index as usize];
     let left_query_slice = &query[..left_query_end_index as usize];
+    let left_quality_slice = query_qualities.map(|x| &x[..left_query_end_index as usize]);
     // 3.2. Calculate the left spare penalty
     let left_spare_penalty = {
         let max_scaled_penalty_delta_of_right = {
@@ -143,9 +155,13 @@ pub fn extend_anchor(
     wave_front.align_left_to_end_point(
         left_target_slice,
         left_query_slice,
+        left_quality_slice,
         penalties,
         left_spare_penalty,
     );
+    if C::ENABLED {
+        stats.add_filled_wave_front_cells(wave_front.count_filled_cells());
+    }
     // 3.4. Check if invalid
     //   - confirm invalid: early drop here
     if !wave_front.is_reached_to_sequence_end() {
@@ -157,7 +173,7 @@ pub fn extend_anchor(
             penalties,
             traversed_anchor_index_buffer,
         );
-        transform_left_additive_position_to_traversed_anchor_index(
+        let left_traversed_anchor_range = transform_left_additive_position_to_traversed_anchor_index(
             anchor_table,
             traversed_anchor_index_buffer,
             anchor_index.0,
@@ -182,13 +198,13 @@ pub fn extend_anchor(
         return;
     }
     //   - have chance to valid: proceed
-    let left_back_trace_result = wave_front.backtrace_from_the_end_of_left_side(
+    let mut left_back_trace_result = wave_front.backtrace_from_the_end_of_left_side(
         *pattern_size,
         penalties,
         operations_buffer,
         traversed_anchor_index_buffer,
     );
-    transform_left_additive_position_to_traversed_anchor_index(
+    left_back_trace_result.traversed_anchor_range = transform_left_additive_position_to_traversed_anchor_index(
         anchor_table,
         traversed_anchor_index_buffer,
         anchor_index.0,
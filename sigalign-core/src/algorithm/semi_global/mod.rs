@@ -3,7 +3,8 @@ use crate::{
         BufferedPatternLocator, SequenceBuffer,
         regulators::{
             Penalty, Cutoff,
-        }
+        },
+        stats::StatsCollector,
     },
     results::{
         AlignmentResult, TargetAlignmentResult, AnchorAlignmentResult,
@@ -24,12 +25,14 @@ use extend::extend_anchor;
 
 // Find all semi-global alignments
 #[inline]
-pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator>(
+pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator, C: StatsCollector>(
     pattern_locater: &L,
     sequence_buffer: &mut L::Buffer,
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     sorted_target_indices: &[u32],
     pattern_size: u32,
+    pattern_occurrence_cap: Option<u32>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     spare_penalty_calculator: &mut SparePenaltyCalculator,
@@ -38,16 +41,20 @@ pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator>(
     traversed_anchor_index_buffer: &mut Vec<AnchorIndex>,
     operations_buffer: &mut Vec<AlignmentOperations>,
     extension_buffer: &mut Vec<Extension>,
+    stats: &mut C,
 ) -> AlignmentResult {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, pattern_occurrence_cap, stats);
+
+    let timer = C::start_timer();
     let target_alignment_results: Vec<TargetAlignmentResult> = anchor_table_map.iter_mut().filter_map(|(target_index, anchor_table)| {
         pattern_locater.fill_buffer(*target_index, sequence_buffer);
         let target = sequence_buffer.buffered_sequence();
-        let anchor_alignment_results = semi_global_alignment_query_to_target(
+        let mut anchor_alignment_results = semi_global_alignment_query_to_target(
             anchor_table,
             pattern_size,
             target,
             query,
+            query_qualities,
             penalties,
             cutoff,
             spare_penalty_calculator,
@@ -55,7 +62,14 @@ pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator>(
             traversed_anchor_index_buffer,
             operations_buffer,
             extension_buffer,
+            stats,
         );
+        if C::ENABLED {
+            stats.add_skipped_anchors(anchor_table.count_skipped_anchors());
+        }
+        if penalties.ambiguous_match.is_some() {
+            anchor_alignment_results.iter_mut().for_each(|result| result.mark_ambiguous_matches(target, query));
+        }
 
         if anchor_alignment_results.is_empty() {
             None
@@ -63,18 +77,22 @@ pub fn semi_global_alignment_algorithm<L: BufferedPatternLocator>(
             Some(TargetAlignmentResult {
                 index: *target_index,
                 alignments: anchor_alignment_results,
+                cutoff_tier: 0,
             })
         }
     }).collect();
 
+    stats.add_extension_time(timer);
+
     AlignmentResult(target_alignment_results)
 }
 
-fn semi_global_alignment_query_to_target(
+fn semi_global_alignment_query_to_target<C: StatsCollector>(
     anchor_table: &mut AnchorTable,
     pattern_size: u32,
     target: &[u8],
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     // Buffers
@@ -83,6 +101,7 @@ fn semi_global_alignment_query_to_target(
     traversed_anchor_index_buffer: &mut Vec<AnchorIndex>,
     operations_buffer: &mut Vec<AlignmentOperations>,
     extension_buffer: &mut Vec<Extension>,
+    stats: &mut C,
 ) -> Vec<AnchorAlignmentResult> {
     // Initialize
     //   - Clear the buffers
@@ -111,12 +130,14 @@ fn semi_global_alignment_query_to_target(
                         spare_penalty_calculator,
                         target,
                         query,
+                        query_qualities,
                         penalties,
                         cutoff,
                         wave_front,
                         operations_buffer,
                         traversed_anchor_index_buffer,
                         extension_buffer,
+                        stats,
                     );
                     let current_anchor = &mut anchor_table.0[pattern_index][anchor_index_in_pattern];
                     current_anchor.extended = true;
@@ -142,12 +163,14 @@ fn semi_global_alignment_query_to_target(
                                 spare_penalty_calculator,
                                 target,
                                 query,
+                                query_qualities,
                                 penalties,
                                 cutoff,
                                 wave_front,
                                 operations_buffer,
                                 traversed_anchor_index_buffer,
                                 extension_buffer,
+                                stats,
                             );
                             let traversed_anchor = &mut anchor_table.0[traversed_anchor_index.0 as usize][traversed_anchor_index.1 as usize];
                             traversed_anchor.extended = true;
@@ -188,12 +211,14 @@ fn semi_global_alignment_query_to_target(
 
 // Find semi-global alignments with a limit
 #[inline]
-pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
+pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator, C: StatsCollector>(
     pattern_locater: &L,
     sequence_buffer: &mut L::Buffer,
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     sorted_target_indices: &[u32],
     pattern_size: u32,
+    pattern_occurrence_cap: Option<u32>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     spare_penalty_calculator: &mut SparePenaltyCalculator,
@@ -204,18 +229,22 @@ pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
     extension_buffer: &mut Vec<Extension>,
     // Limit of the number of alignments
     mut limit: u32,
+    stats: &mut C,
 ) -> AlignmentResult {
-    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size);
+    let mut anchor_table_map = AnchorTable::new_by_target_index(pattern_locater, query, sorted_target_indices, pattern_size, pattern_occurrence_cap, stats);
+
+    let timer = C::start_timer();
     let mut target_alignment_results: Vec<TargetAlignmentResult> = Vec::new();
 
     for (target_index, anchor_table) in anchor_table_map.iter_mut() {
         pattern_locater.fill_buffer(*target_index, sequence_buffer);
         let target = sequence_buffer.buffered_sequence();
-        let anchor_alignment_results = semi_global_alignment_query_to_target_with_limit(
+        let mut anchor_alignment_results = semi_global_alignment_query_to_target_with_limit(
             anchor_table,
             pattern_size,
             target,
             query,
+            query_qualities,
             penalties,
             cutoff,
             spare_penalty_calculator,
@@ -224,12 +253,20 @@ pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
             operations_buffer,
             extension_buffer,
             &mut limit,
+            stats,
         );
+        if C::ENABLED {
+            stats.add_skipped_anchors(anchor_table.count_skipped_anchors());
+        }
+        if penalties.ambiguous_match.is_some() {
+            anchor_alignment_results.iter_mut().for_each(|result| result.mark_ambiguous_matches(target, query));
+        }
 
         if !anchor_alignment_results.is_empty() {
             target_alignment_results.push(TargetAlignmentResult {
                 index: *target_index,
                 alignments: anchor_alignment_results,
+                cutoff_tier: 0,
             });
 
             if limit == 0 {
@@ -238,14 +275,17 @@ pub fn semi_global_alignment_algorithm_with_limit<L: BufferedPatternLocator>(
         }
     }
 
+    stats.add_extension_time(timer);
+
     AlignmentResult(target_alignment_results)
 }
 
-fn semi_global_alignment_query_to_target_with_limit(
+fn semi_global_alignment_query_to_target_with_limit<C: StatsCollector>(
     anchor_table: &mut AnchorTable,
     pattern_size: u32,
     target: &[u8],
     query: &[u8],
+    query_qualities: Option<&[u8]>,
     penalties: &Penalty,
     cutoff: &Cutoff,
     // Buffers
@@ -256,6 +296,7 @@ fn semi_global_alignment_query_to_target_with_limit(
     extension_buffer: &mut Vec<Extension>,
     // Limit of the number of alignments
     limit: &mut u32,
+    stats: &mut C,
 ) -> Vec<AnchorAlignmentResult> {
     // Initialize
     //   - Clear the buffers
@@ -284,12 +325,14 @@ fn semi_global_alignment_query_to_target_with_limit(
                         spare_penalty_calculator,
                         target,
                         query,
+                        query_qualities,
                         penalties,
                         cutoff,
                         wave_front,
                         operations_buffer,
                         traversed_anchor_index_buffer,
                         extension_buffer,
+                        stats,
                     );
                     let current_anchor = &mut anchor_table.0[pattern_index][anchor_index_in_pattern];
                     current_anchor.extended = true;
@@ -315,12 +358,14 @@ fn semi_global_alignment_query_to_target_with_limit(
                                 spare_penalty_calculator,
                                 target,
                                 query,
+                                query_qualities,
                                 penalties,
                                 cutoff,
                                 wave_front,
                                 operations_buffer,
                                 traversed_anchor_index_buffer,
                                 extension_buffer,
+                                stats,
                             );
                             let traversed_anchor = &mut anchor_table.0[traversed_anchor_index.0 as usize][traversed_anchor_index.1 as usize];
                             traversed_anchor.extended = true;
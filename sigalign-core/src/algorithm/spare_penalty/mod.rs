@@ -68,20 +68,25 @@ impl SparePenaltyCalculator {
         //   - f(x) = (a * x + b) / c
         //   - x: reversed pattern index (= last pattern index - pattern index)
         //   - all coefficient is scaled
-        let a = maximum_scaled_penalty_per_length * penalties.e * pattern_size;
+        //   - With the two-piece gap penalty, the lower envelope of the gap penalties is used
+        //     (the spare penalty can only be larger).
+        //   - The mismatch penalty is not used, so the bound is kept with any
+        //     (e.g., quality-aware) mismatch penalties.
+        let (gap_open_penalty, gap_extend_penalty) = penalties.lower_envelope_of_gap_penalties();
+        let a = maximum_scaled_penalty_per_length * gap_extend_penalty * pattern_size;
         let b = maximum_scaled_penalty_per_length * (
-            penalties.e * (3 * pattern_size - 2) - penalties.o
+            (gap_extend_penalty * (3 * pattern_size - 2)).saturating_sub(gap_open_penalty)
         );
-        let c = penalties.e * PREC_SCALE - maximum_scaled_penalty_per_length;
+        let c = gap_extend_penalty * PREC_SCALE - maximum_scaled_penalty_per_length;
 
         // (2) For left spare penalty
         //   - g(y,z) = (d * y + e * z - f) / g
         //   - y: right penalty delta
         //   - z: pattern index
         //   - all coefficient is scaled
-        let d = penalties.e;
-        let e = maximum_scaled_penalty_per_length * penalties.e * pattern_size;
-        let f = maximum_scaled_penalty_per_length * penalties.o;
+        let d = gap_extend_penalty;
+        let e = maximum_scaled_penalty_per_length * gap_extend_penalty * pattern_size;
+        let f = maximum_scaled_penalty_per_length * gap_open_penalty;
         // g is same as c
         let g = c;
 
@@ -90,7 +95,7 @@ impl SparePenaltyCalculator {
             last_pattern_index: 0,
             coefficient_for_right: (a, b, c),
             coefficient_for_left: (d, e, f, g),
-            min_penalty: penalties.o,
+            min_penalty: gap_open_penalty,
         }
     }
     #[inline]
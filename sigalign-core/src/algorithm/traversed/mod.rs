@@ -1,5 +1,8 @@
 use super::{AnchorTable, Anchor, AnchorIndex};
 
+// The pattern over the occurrence cap is not located, so it has no anchor.
+// Such traversed patterns are skipped, and the range of the kept anchors is returned.
+//  - The `index_range` must be at the end of the `traversed_anchor_index_buffer`.
 #[inline]
 pub fn transform_left_additive_position_to_traversed_anchor_index(
     anchor_table: &AnchorTable,
@@ -7,16 +10,21 @@ pub fn transform_left_additive_position_to_traversed_anchor_index(
     base_pattern_index: u32,
     base_target_position: u32,
     index_range: (u32, u32),
-) {
-    traversed_anchor_index_buffer[index_range.0 as usize..index_range.1 as usize].iter_mut().for_each(|additive_position_info| {
+) -> (u32, u32) {
+    debug_assert_eq!(index_range.1 as usize, traversed_anchor_index_buffer.len());
+    let mut kept_index = index_range.0 as usize;
+    for index in index_range.0 as usize..index_range.1 as usize {
+        let additive_position_info = traversed_anchor_index_buffer[index];
         let pattern_index = base_pattern_index - additive_position_info.0;
         let target_position = base_target_position - additive_position_info.1;
         let anchors_by_pattern = &anchor_table.0[pattern_index as usize];
-        let anchor_index_in_pattern = unsafe {
-            binary_search(anchors_by_pattern, target_position).unwrap_unchecked()
-        } as u32;
-        *additive_position_info = (pattern_index, anchor_index_in_pattern);
-    });
+        if let Ok(anchor_index_in_pattern) = binary_search(anchors_by_pattern, target_position) {
+            traversed_anchor_index_buffer[kept_index] = (pattern_index, anchor_index_in_pattern as u32);
+            kept_index += 1;
+        }
+    }
+    traversed_anchor_index_buffer.truncate(kept_index);
+    (index_range.0, kept_index as u32)
 }
 #[inline]
 pub fn transform_right_additive_position_to_traversed_anchor_index(
@@ -26,29 +34,31 @@ pub fn transform_right_additive_position_to_traversed_anchor_index(
     base_target_position: u32,
     index_range: (u32, u32),
     pattern_size: u32,
-) {
-    traversed_anchor_index_buffer[index_range.0 as usize..index_range.1 as usize].iter_mut().for_each(|additive_position_info| {
+) -> (u32, u32) {
+    debug_assert_eq!(index_range.1 as usize, traversed_anchor_index_buffer.len());
+    let mut kept_index = index_range.0 as usize;
+    for index in index_range.0 as usize..index_range.1 as usize {
+        let additive_position_info = traversed_anchor_index_buffer[index];
         let mut pattern_index = base_pattern_index + additive_position_info.0;
         let mut target_position = base_target_position + additive_position_info.1;
-        let anchor_index_in_pattern = {
-            loop {
-                let anchors_by_pattern = &anchor_table.0[pattern_index as usize];
-                match binary_search(
-                    anchors_by_pattern,
-                    target_position,
-                ) {
-                    Ok(v) => {
-                        break v as u32
-                    },
-                    Err(_) => {
-                        pattern_index -= 1;
-                        target_position -= pattern_size;
-                    },
-                }
+        // Search toward the base anchor, but never mark the base anchor itself.
+        while pattern_index > base_pattern_index {
+            let anchors_by_pattern = &anchor_table.0[pattern_index as usize];
+            match binary_search(anchors_by_pattern, target_position) {
+                Ok(anchor_index_in_pattern) => {
+                    traversed_anchor_index_buffer[kept_index] = (pattern_index, anchor_index_in_pattern as u32);
+                    kept_index += 1;
+                    break
+                },
+                Err(_) => {
+                    pattern_index -= 1;
+                    target_position -= pattern_size;
+                },
             }
-        };
-        *additive_position_info = (pattern_index, anchor_index_in_pattern);
-    });
+        }
+    }
+    traversed_anchor_index_buffer.truncate(kept_index);
+    (index_range.0, kept_index as u32)
 }
 
 #[inline(always)]
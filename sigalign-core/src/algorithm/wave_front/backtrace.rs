@@ -21,6 +21,8 @@ enum ComponentType {
     M,
     I,
     D,
+    I2,
+    D2,
 }
 
 // TODO: Backtrace can refer the other extensions of this anchor
@@ -64,7 +66,7 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            penalty -= penalties.mismatch_penalty_of_index(component.mismatch_index);
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -121,17 +123,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromD => {
+                        BackTraceMarker::FromD | BackTraceMarker::FromD2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::D;
-                            // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromD {
+                                (ComponentType::D, wave_front_score.d_component_of_k(k))
+                            } else {
+                                (ComponentType::D2, wave_front_score.d2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -158,17 +162,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromI => {
+                        BackTraceMarker::FromI | BackTraceMarker::FromI2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::I;
-                            // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromI {
+                                (ComponentType::I, wave_front_score.i_component_of_k(k))
+                            } else {
+                                (ComponentType::I2, wave_front_score.i2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -206,6 +212,10 @@ impl WaveFront {
                                 );
                             };
 
+                            // With the free ambiguous matches, the patterns in the match runs are not always anchors.
+                            if penalties.ambiguous_match == Some(0) {
+                                traversed_anchor_index_buffer.truncate(traversed_anchor_start_index as usize);
+                            }
                             let backtrace_result = BackTraceResult {
                                 // start, size
                                 operation_buffer_range: (
@@ -227,11 +237,13 @@ impl WaveFront {
                     }
                 },
                 /* I */
-                ComponentType::D => {
+                ComponentType::D | ComponentType::D2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::D2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -262,7 +274,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_I
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -270,7 +282,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.d2_component_of_k(k)
+                            } else {
+                                wave_front_score.d_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -294,11 +310,13 @@ impl WaveFront {
                     }
                 },
                 /* D */
-                ComponentType::I => {
+                ComponentType::I | ComponentType::I2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::I2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -329,7 +347,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_D
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -337,7 +355,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.i2_component_of_k(k)
+                            } else {
+                                wave_front_score.i_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -405,7 +427,7 @@ impl WaveFront {
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.x;
+                            penalty -= penalties.mismatch_penalty_of_index(component.mismatch_index);
                             // (2) Next k
                             // not change
                             // (3) Next WFS
@@ -463,17 +485,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromD => {
+                        BackTraceMarker::FromD | BackTraceMarker::FromD2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::D;
-                            // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromD {
+                                (ComponentType::D, wave_front_score.d_component_of_k(k))
+                            } else {
+                                (ComponentType::D2, wave_front_score.d2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -501,17 +525,19 @@ impl WaveFront {
                             // (9) Next fr to fr
                             fr = next_fr;
                         },
-                        BackTraceMarker::FromI => {
+                        BackTraceMarker::FromI | BackTraceMarker::FromI2 => {
                             // (1) Next penalty
                             // not change
                             // (2) Next k
                             // not change
                             // (3) Next WFS
                             // not change
-                            // (4) Component type
-                            component_type = ComponentType::I;
-                            // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            // (4) Component type & (5) Next component
+                            (component_type, component) = if component.bt == BackTraceMarker::FromI {
+                                (ComponentType::I, wave_front_score.i_component_of_k(k))
+                            } else {
+                                (ComponentType::I2, wave_front_score.i2_component_of_k(k))
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Check traversed
@@ -549,6 +575,10 @@ impl WaveFront {
                                 }
                             );
 
+                            // With the free ambiguous matches, the patterns in the match runs are not always anchors.
+                            if penalties.ambiguous_match == Some(0) {
+                                traversed_anchor_index_buffer.truncate(traversed_anchor_start_index as usize);
+                            }
                             let backtrace_result = BackTraceResult {
                                 // start, size
                                 operation_buffer_range: (
@@ -570,11 +600,13 @@ impl WaveFront {
                     }
                 },
                 /* I */
-                ComponentType::D => {
+                ComponentType::D | ComponentType::D2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::D2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -605,7 +637,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_I
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k -= 1;
                             // (3) Next WFS
@@ -613,7 +645,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.d_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.d2_component_of_k(k)
+                            } else {
+                                wave_front_score.d_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -637,11 +673,13 @@ impl WaveFront {
                     }
                 },
                 /* D */
-                ComponentType::I => {
+                ComponentType::I | ComponentType::I2 => {
+                    let is_second_piece = matches!(component_type, ComponentType::I2);
+                    let (gap_open_penalty, gap_extend_penalty) = penalties.gap_penalties_of_piece(is_second_piece);
                     match component.bt {
                         BackTraceMarker::FromM => {
                             // (1) Next penalty
-                            penalty -= penalties.o + penalties.e;
+                            penalty -= gap_open_penalty + gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -672,7 +710,7 @@ impl WaveFront {
                         },
                         _ => { // FROM_D
                             // (1) Next penalty
-                            penalty -= penalties.e;
+                            penalty -= gap_extend_penalty;
                             // (2) Next k
                             k += 1;
                             // (3) Next WFS
@@ -680,7 +718,11 @@ impl WaveFront {
                             // (4) Component type
                             // not change
                             // (5) Next component
-                            component = wave_front_score.i_component_of_k(k);
+                            component = if is_second_piece {
+                                wave_front_score.i2_component_of_k(k)
+                            } else {
+                                wave_front_score.i_component_of_k(k)
+                            };
                             // (6) Next fr
                             let next_fr = component.fr;
                             // (7) Add operation
@@ -1,7 +1,8 @@
 use crate::core::regulators::Penalty;
 use super::{
-    WaveFront, WaveEndPoint, WaveFrontScore, Components, Component, BackTraceMarker,
+    WaveFront, WaveEndPoint, WaveFrontScore, Components, GapComponents, Component, BackTraceMarker,
     MatchCounter, ForwardMatchCounter, ReverseMatchCounter,
+    IupacForwardMatchCounter, IupacReverseMatchCounter,
 };
 
 impl WaveFront {
@@ -10,26 +11,37 @@ impl WaveFront {
         &mut self,
         tgt_seq: &[u8],
         qry_seq: &[u8],
+        qry_qual: Option<&[u8]>,
         penalties: &Penalty,
         spare_penalty: u32,
     ) {
-        self.align_to_end_point::<ForwardMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty)
+        if penalties.ambiguous_match == Some(0) {
+            self.align_to_end_point::<IupacForwardMatchCounter>(tgt_seq, qry_seq, qry_qual, penalties, spare_penalty)
+        } else {
+            self.align_to_end_point::<ForwardMatchCounter>(tgt_seq, qry_seq, qry_qual, penalties, spare_penalty)
+        }
     }
     #[inline]
     pub fn align_left_to_end_point(
         &mut self,
         tgt_seq: &[u8],
         qry_seq: &[u8],
+        qry_qual: Option<&[u8]>,
         penalties: &Penalty,
         spare_penalty: u32,
     ) {
-        self.align_to_end_point::<ReverseMatchCounter>(tgt_seq, qry_seq, penalties, spare_penalty)
+        if penalties.ambiguous_match == Some(0) {
+            self.align_to_end_point::<IupacReverseMatchCounter>(tgt_seq, qry_seq, qry_qual, penalties, spare_penalty)
+        } else {
+            self.align_to_end_point::<ReverseMatchCounter>(tgt_seq, qry_seq, qry_qual, penalties, spare_penalty)
+        }
     }
     #[inline]
     fn align_to_end_point<C: MatchCounter>(
         &mut self,
         tgt_seq: &[u8],
         qry_seq: &[u8],
+        qry_qual: Option<&[u8]>,
         penalties: &Penalty,
         spare_penalty: u32,
     ) {
@@ -47,6 +59,7 @@ impl WaveFront {
             let end_point = self.fill_wave_front_scores_until_end::<C>(
                 tgt_seq,
                 qry_seq,
+                qry_qual,
                 spare_penalty,
                 penalties,
             );
@@ -58,6 +71,7 @@ impl WaveFront {
         &mut self,
         tgt_seq: &[u8],
         qry_seq: &[u8],
+        qry_qual: Option<&[u8]>,
         mut spare_penalty: u32,
         penalties: &Penalty,
     ) -> WaveEndPoint {
@@ -65,7 +79,7 @@ impl WaveFront {
             spare_penalty = (self.wave_front_scores.len() - 1) as u32;
         }
         for penalty in 1..=spare_penalty {
-            self.update_components_of_next_wave_front_score(penalty, penalties);
+            self.update_components_of_next_wave_front_score::<C>(tgt_seq, qry_seq, qry_qual, penalty, penalties);
            
             let optional_last_k = self.wave_front_scores[penalty as usize].extend_components_until_end::<C>(tgt_seq, qry_seq);
 
@@ -77,8 +91,11 @@ impl WaveFront {
         WaveEndPoint { penalty: spare_penalty as usize, k: None }
     }
     #[inline]
-    fn update_components_of_next_wave_front_score(
+    fn update_components_of_next_wave_front_score<C: MatchCounter>(
         &mut self,
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        qry_qual: Option<&[u8]>,
         penalty: u32,
         penalties: &Penalty,
     ) {
@@ -90,18 +107,25 @@ impl WaveFront {
             max_k,
             num_components,
             new_components_ptr,
+            new_second_gap_components_ptr,
         ) = {
             let next_wave_front_score = &mut self.wave_front_scores[penalty as usize];
             (
                 next_wave_front_score.max_k,
                 next_wave_front_score.components_by_k.len(),
                 next_wave_front_score.components_by_k.as_mut_ptr(),
+                next_wave_front_score.second_gap_components_by_k.as_mut_ptr(),
             )
         };
         unsafe {
             let ptr = new_components_ptr as *mut u8;
             let byte_count = num_components * std::mem::size_of::<Components>();
             std::ptr::write_bytes(ptr, 0, byte_count);
+            if penalties.second_gap.is_some() {
+                let ptr = new_second_gap_components_ptr as *mut u8;
+                let byte_count = num_components * std::mem::size_of::<GapComponents>();
+                std::ptr::write_bytes(ptr, 0, byte_count);
+            }
         }
 
         // (1) From score: s-o-e
@@ -120,6 +144,7 @@ impl WaveFront {
                                 fr: pre_m_component.fr + 1,
                                 insertion_count: pre_m_component.insertion_count,
                                 bt: BackTraceMarker::FromM,
+                                mismatch_index: 0,
                             };
                         }
                     }
@@ -134,6 +159,7 @@ impl WaveFront {
                                 fr: pre_m_component.fr,
                                 insertion_count: pre_m_component.insertion_count + 1,
                                 bt: BackTraceMarker::FromM,
+                                mismatch_index: 0,
                             };
                         }
                     }
@@ -158,6 +184,7 @@ impl WaveFront {
                                     fr: pre_d_component.fr + 1,
                                     insertion_count: pre_d_component.insertion_count,
                                     bt: BackTraceMarker::FromD,
+                                    mismatch_index: 0,
                                 };
                             }
                         };
@@ -174,6 +201,7 @@ impl WaveFront {
                                     fr: pre_i_component.fr,
                                     insertion_count: pre_i_component.insertion_count + 1,
                                     bt: BackTraceMarker::FromI,
+                                    mismatch_index: 0,
                                 };
                             };
                         }
@@ -181,57 +209,252 @@ impl WaveFront {
                 }
             }
         }
+        // (2-2) Second piece of the two-piece gap penalty
+        if let Some(second_gap) = &penalties.second_gap {
+            // From score: s-o2-e2
+            if let Some(pre_score) = penalty.checked_sub(second_gap.o + second_gap.e) {
+                let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
+                for index_of_k in 0..num_components {
+                    let k = index_of_k as i32 - max_k;
+                    let new_gap_components_of_k = unsafe { new_second_gap_components_ptr.add(index_of_k) };
+                    // 1. Update D2 from M
+                    if let Some(pre_components) = pre_wave_front_score.components_of_k_checked(k-1) {
+                        let pre_m_component = &pre_components.m;
+                        if pre_m_component.bt != BackTraceMarker::Empty {
+                            unsafe {
+                                (*new_gap_components_of_k).d = Component {
+                                    fr: pre_m_component.fr + 1,
+                                    insertion_count: pre_m_component.insertion_count,
+                                    bt: BackTraceMarker::FromM,
+                                    mismatch_index: 0,
+                                };
+                            }
+                        }
+                    }
+                    // 2. Update I2 from M
+                    if let Some(pre_components) = pre_wave_front_score.components_of_k_checked(k+1) {
+                        let pre_m_component = &pre_components.m;
+                        if pre_m_component.bt != BackTraceMarker::Empty {
+                            unsafe {
+                                (*new_gap_components_of_k).i = Component {
+                                    fr: pre_m_component.fr,
+                                    insertion_count: pre_m_component.insertion_count + 1,
+                                    bt: BackTraceMarker::FromM,
+                                    mismatch_index: 0,
+                                };
+                            }
+                        }
+                    }
+                }
+            }
+            // From score: s-e2
+            if let Some(pre_score) = penalty.checked_sub(second_gap.e) {
+                let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
+                for index_of_k in 0..num_components {
+                    let k = index_of_k as i32 - max_k;
+                    let new_gap_components_of_k = unsafe { new_second_gap_components_ptr.add(index_of_k) };
+                    // 1. Update D2 from D2
+                    if let Some(pre_gap_components) = pre_wave_front_score.second_gap_components_of_k_checked(k-1) {
+                        let pre_d_component = &pre_gap_components.d;
+                        if pre_d_component.bt != BackTraceMarker::Empty {
+                            unsafe {
+                                if (*new_gap_components_of_k).d.bt == BackTraceMarker::Empty || (*new_gap_components_of_k).d.fr < pre_d_component.fr + 1 {
+                                    (*new_gap_components_of_k).d = Component {
+                                        fr: pre_d_component.fr + 1,
+                                        insertion_count: pre_d_component.insertion_count,
+                                        bt: BackTraceMarker::FromD2,
+                                        mismatch_index: 0,
+                                    };
+                                }
+                            }
+                        }
+                    }
+                    // 2. Update I2 from I2
+                    if let Some(pre_gap_components) = pre_wave_front_score.second_gap_components_of_k_checked(k+1) {
+                        let pre_i_component = &pre_gap_components.i;
+                        if pre_i_component.bt != BackTraceMarker::Empty {
+                            unsafe {
+                                if (*new_gap_components_of_k).i.bt == BackTraceMarker::Empty || (*new_gap_components_of_k).i.fr < pre_i_component.fr {
+                                    (*new_gap_components_of_k).i = Component {
+                                        fr: pre_i_component.fr,
+                                        insertion_count: pre_i_component.insertion_count + 1,
+                                        bt: BackTraceMarker::FromI2,
+                                        mismatch_index: 0,
+                                    };
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
         // (3) From score: s-x
-        if let Some(pre_score) = penalty.checked_sub(*mismatch_penalty) {
-            let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
-            for index_of_k in 0..num_components {
-                let k = index_of_k as i32 - max_k;
-                let new_components_of_k = unsafe { new_components_ptr.add(index_of_k) };
-                // 1. Update M from M
-                let pre_component_index = (pre_wave_front_score.max_k + k) as usize;
+        match (&penalties.substitution_matrix, &penalties.quality_mismatch) {
+            (None, None) => {
+                if let Some(pre_score) = penalty.checked_sub(*mismatch_penalty) {
+                    let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
+                    for index_of_k in 0..num_components {
+                        let k = index_of_k as i32 - max_k;
+                        let new_components_of_k = unsafe { new_components_ptr.add(index_of_k) };
+                        // 1. Update M from M
+                        let pre_component_index = (pre_wave_front_score.max_k + k) as usize;
 
-                if let Some(pre_components) = pre_wave_front_score.components_by_k.get(pre_component_index) {
-                    let pre_m_component = &pre_components.m;
-                    // Update M
-                    unsafe {
-                        (*new_components_of_k).m = Component {
-                            fr: pre_m_component.fr + 1,
-                            insertion_count: pre_m_component.insertion_count,
-                            bt: BackTraceMarker::FromM,
-                        };
+                        if let Some(pre_components) = pre_wave_front_score.components_by_k.get(pre_component_index) {
+                            let pre_m_component = &pre_components.m;
+                            // Update M
+                            //  - With the second gap, the range of k can be wider than the reachable range.
+                            if pre_m_component.bt != BackTraceMarker::Empty {
+                                unsafe {
+                                    (*new_components_of_k).m = Component {
+                                        fr: pre_m_component.fr + 1,
+                                        insertion_count: pre_m_component.insertion_count,
+                                        bt: BackTraceMarker::FromM,
+                                        mismatch_index: 0,
+                                    };
+                                }
+                            }
+                        }
+                        // 2. Update M from D & I
+                        unsafe { update_m_component_from_gaps(new_components_of_k) };
                     }
                 }
-                unsafe {
-                    // 2. Update M from D
-                    if (*new_components_of_k).d.bt != BackTraceMarker::Empty && (
-                        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).d.fr >= (*new_components_of_k).m.fr
-                    ) {
-                        (*new_components_of_k).m = Component {
-                            fr: (*new_components_of_k).d.fr,
-                            insertion_count: (*new_components_of_k).d.insertion_count,
-                            bt: BackTraceMarker::FromD,
-                        };
-                    }
-                    // 3. Update M from I
-                    if (*new_components_of_k).i.bt != BackTraceMarker::Empty && (
-                        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).i.fr >= (*new_components_of_k).m.fr
-                    ) {
-                        (*new_components_of_k).m = Component {
-                            fr: (*new_components_of_k).i.fr,
-                            insertion_count: (*new_components_of_k).i.insertion_count,
-                            bt: BackTraceMarker::FromI,
-                        };
+            },
+            (substitution_matrix, quality_mismatch) => {
+                // 1. Update M from M
+                //  - The penalty of the next mismatch is determined by the bases (or the quality of query base)
+                //    at the end of the pre M component.
+                let distinct_penalties = match (substitution_matrix, quality_mismatch) {
+                    (Some(substitution_matrix), None) => &substitution_matrix.distinct_penalties,
+                    (None, Some(quality_mismatch)) => &quality_mismatch.distinct_penalties,
+                    // The regulator never has both (the qualities would be ignored)
+                    (Some(_), Some(_)) | (None, None) => unreachable!(),
+                };
+                let tgt_len = tgt_seq.len() as i32;
+                let qry_len = qry_seq.len() as i32;
+                for (mismatch_index, mismatch_penalty) in distinct_penalties.iter().enumerate() {
+                    let pre_score = match penalty.checked_sub(*mismatch_penalty) {
+                        Some(v) => v,
+                        None => break, // Penalties are sorted
+                    };
+                    let pre_wave_front_score = &self.wave_front_scores[pre_score as usize];
+                    for index_of_k in 0..num_components {
+                        let k = index_of_k as i32 - max_k;
+                        let new_components_of_k = unsafe { new_components_ptr.add(index_of_k) };
+                        if let Some(pre_components) = pre_wave_front_score.components_of_k_checked(k) {
+                            let pre_m_component = &pre_components.m;
+                            let h = pre_m_component.fr;
+                            let v = h - k;
+                            if pre_m_component.bt != BackTraceMarker::Empty && h < tgt_len && v >= 0 && v < qry_len {
+                                let penalty_of_next_mismatch = match substitution_matrix {
+                                    Some(substitution_matrix) => {
+                                        let (target_base, query_base) = C::bases_at(tgt_seq, qry_seq, v as usize, h as usize);
+                                        substitution_matrix.penalty_of_pair(target_base, query_base)
+                                    },
+                                    None => {
+                                        // Quality is at the same position of the query
+                                        let quality = qry_qual.map(|qry_qual| {
+                                            C::bases_at(tgt_seq, qry_qual, v as usize, h as usize).1
+                                        });
+                                        unsafe { quality_mismatch.as_ref().unwrap_unchecked() }.penalty_of_quality(quality)
+                                    },
+                                };
+                                if penalty_of_next_mismatch == *mismatch_penalty {
+                                    // Update M
+                                    unsafe {
+                                        if (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).m.fr < h + 1 {
+                                            (*new_components_of_k).m = Component {
+                                                fr: h + 1,
+                                                insertion_count: pre_m_component.insertion_count,
+                                                bt: BackTraceMarker::FromM,
+                                                mismatch_index: mismatch_index as u8,
+                                            };
+                                        }
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
+                // 2. Update M from D & I
+                for index_of_k in 0..num_components {
+                    unsafe { update_m_component_from_gaps(new_components_ptr.add(index_of_k)) };
+                }
+            },
+        }
+        // (4) Update M from D2 & I2
+        if penalties.second_gap.is_some() {
+            for index_of_k in 0..num_components {
+                unsafe {
+                    update_m_component_from_second_gaps(
+                        new_components_ptr.add(index_of_k),
+                        new_second_gap_components_ptr.add(index_of_k),
+                    )
+                };
             }
         }
     }
 }
 
+#[inline(always)]
+unsafe fn update_m_component_from_gaps(new_components_of_k: *mut Components) {
+    // 1. Update M from D
+    if (*new_components_of_k).d.bt != BackTraceMarker::Empty && (
+        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).d.fr >= (*new_components_of_k).m.fr
+    ) {
+        (*new_components_of_k).m = Component {
+            fr: (*new_components_of_k).d.fr,
+            insertion_count: (*new_components_of_k).d.insertion_count,
+            bt: BackTraceMarker::FromD,
+            mismatch_index: 0,
+        };
+    }
+    // 2. Update M from I
+    if (*new_components_of_k).i.bt != BackTraceMarker::Empty && (
+        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_components_of_k).i.fr >= (*new_components_of_k).m.fr
+    ) {
+        (*new_components_of_k).m = Component {
+            fr: (*new_components_of_k).i.fr,
+            insertion_count: (*new_components_of_k).i.insertion_count,
+            bt: BackTraceMarker::FromI,
+            mismatch_index: 0,
+        };
+    }
+}
+
+#[inline(always)]
+unsafe fn update_m_component_from_second_gaps(
+    new_components_of_k: *mut Components,
+    new_gap_components_of_k: *const GapComponents,
+) {
+    // 1. Update M from D2
+    if (*new_gap_components_of_k).d.bt != BackTraceMarker::Empty && (
+        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_gap_components_of_k).d.fr > (*new_components_of_k).m.fr
+    ) {
+        (*new_components_of_k).m = Component {
+            fr: (*new_gap_components_of_k).d.fr,
+            insertion_count: (*new_gap_components_of_k).d.insertion_count,
+            bt: BackTraceMarker::FromD2,
+            mismatch_index: 0,
+        };
+    }
+    // 2. Update M from I2
+    if (*new_gap_components_of_k).i.bt != BackTraceMarker::Empty && (
+        (*new_components_of_k).m.bt == BackTraceMarker::Empty || (*new_gap_components_of_k).i.fr > (*new_components_of_k).m.fr
+    ) {
+        (*new_components_of_k).m = Component {
+            fr: (*new_gap_components_of_k).i.fr,
+            insertion_count: (*new_gap_components_of_k).i.insertion_count,
+            bt: BackTraceMarker::FromI2,
+            mismatch_index: 0,
+        };
+    }
+}
+
 impl WaveFrontScore {
     #[inline]
     fn add_first_components(&mut self, first_match_count: i32) {
         self.components_by_k = vec![Components::new_start_point(first_match_count)];
+        self.second_gap_components_by_k.fill(GapComponents::default());
     }
     #[inline]
     fn extend_components_until_end<C: MatchCounter>(
@@ -0,0 +1,127 @@
+mod vectorized;
+use vectorized::{count_forward_match, count_backward_match};
+#[cfg(feature = "switchable_match_counting")]
+pub use vectorized::use_scalar_match_counting;
+use crate::core::iupac::is_compatible;
+
+pub trait MatchCounter {
+    fn count_consecutive_match(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> i32;
+    /// (target base, query base) at the position
+    fn bases_at(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> (u8, u8);
+}
+
+pub struct ForwardMatchCounter;
+impl MatchCounter for ForwardMatchCounter {
+    #[inline(always)]
+    fn count_consecutive_match(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> i32 {
+        count_forward_match(&qry_seq[v..], &tgt_seq[h..]) as i32
+    }
+    #[inline(always)]
+    fn bases_at(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> (u8, u8) {
+        (tgt_seq[h], qry_seq[v])
+    }
+}
+pub struct ReverseMatchCounter;
+impl MatchCounter for ReverseMatchCounter {
+    #[inline(always)]
+    fn count_consecutive_match(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> i32 {
+        count_backward_match(&qry_seq[..qry_seq.len()-v], &tgt_seq[..tgt_seq.len()-h]) as i32
+    }
+    #[inline(always)]
+    fn bases_at(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> (u8, u8) {
+        (tgt_seq[tgt_seq.len() - 1 - h], qry_seq[qry_seq.len() - 1 - v])
+    }
+}
+/// Counts the ambiguous matches of the IUPAC codes as the matches.
+pub struct IupacForwardMatchCounter;
+impl MatchCounter for IupacForwardMatchCounter {
+    #[inline(always)]
+    fn count_consecutive_match(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> i32 {
+        let (qry_seq, tgt_seq) = (&qry_seq[v..], &tgt_seq[h..]);
+        let len = qry_seq.len().min(tgt_seq.len());
+        let mut count = 0;
+        loop {
+            count += count_forward_match(&qry_seq[count..], &tgt_seq[count..]);
+            if count < len && is_compatible(qry_seq[count], tgt_seq[count]) {
+                count += 1;
+            } else {
+                return count as i32
+            }
+        }
+    }
+    #[inline(always)]
+    fn bases_at(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> (u8, u8) {
+        ForwardMatchCounter::bases_at(tgt_seq, qry_seq, v, h)
+    }
+}
+pub struct IupacReverseMatchCounter;
+impl MatchCounter for IupacReverseMatchCounter {
+    #[inline(always)]
+    fn count_consecutive_match(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> i32 {
+        let (qry_seq, tgt_seq) = (&qry_seq[..qry_seq.len()-v], &tgt_seq[..tgt_seq.len()-h]);
+        let len = qry_seq.len().min(tgt_seq.len());
+        let mut count = 0;
+        loop {
+            count += count_backward_match(&qry_seq[..qry_seq.len()-count], &tgt_seq[..tgt_seq.len()-count]);
+            if count < len && is_compatible(qry_seq[qry_seq.len()-1-count], tgt_seq[tgt_seq.len()-1-count]) {
+                count += 1;
+            } else {
+                return count as i32
+            }
+        }
+    }
+    #[inline(always)]
+    fn bases_at(
+        tgt_seq: &[u8],
+        qry_seq: &[u8],
+        v: usize,
+        h: usize,
+    ) -> (u8, u8) {
+        ReverseMatchCounter::bases_at(tgt_seq, qry_seq, v, h)
+    }
+}
@@ -0,0 +1,326 @@
+/*!
+Counting the consecutive matches of two sequences.
+
+- The vectorized path compares a word (8 bytes) at a time with XOR and counts the zero bytes.
+- On top of it, SIMD instructions are used if the CPU supports them:
+    - x86_64: AVX2 (detected at runtime) or SSE2 (always available)
+    - aarch64: NEON (detected at runtime)
+- The scalar path compares one byte at a time and is kept as the reference.
+    - It is used instead of the vectorized path when the `scalar_match_counting` feature is enabled.
+    - With the `switchable_match_counting` feature, it can be selected for each thread at runtime,
+      to compare the results of the two paths (only for the tests).
+*/
+#![cfg_attr(feature = "scalar_match_counting", allow(dead_code))]
+
+#[cfg(feature = "switchable_match_counting")]
+thread_local! {
+    static USE_SCALAR: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+/// Use the scalar path instead of the vectorized path in the current thread.
+///  - Only for comparing the results of the two paths. Both give the same results.
+#[cfg(feature = "switchable_match_counting")]
+pub fn use_scalar_match_counting(use_scalar: bool) {
+    USE_SCALAR.with(|v| v.set(use_scalar));
+}
+
+/// Count the matches from the start of the sequences
+#[inline(always)]
+pub fn count_forward_match(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    let len = seq_1.len().min(seq_2.len());
+    let (seq_1, seq_2) = (&seq_1[..len], &seq_2[..len]);
+    #[cfg(feature = "switchable_match_counting")]
+    if USE_SCALAR.with(|v| v.get()) {
+        return count_forward_scalar(seq_1, seq_2)
+    }
+    #[cfg(not(feature = "scalar_match_counting"))]
+    { count_forward_vectorized(seq_1, seq_2) }
+    #[cfg(feature = "scalar_match_counting")]
+    { count_forward_scalar(seq_1, seq_2) }
+}
+/// Count the matches from the end of the sequences
+#[inline(always)]
+pub fn count_backward_match(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    let len = seq_1.len().min(seq_2.len());
+    let (seq_1, seq_2) = (&seq_1[seq_1.len()-len..], &seq_2[seq_2.len()-len..]);
+    #[cfg(feature = "switchable_match_counting")]
+    if USE_SCALAR.with(|v| v.get()) {
+        return count_backward_scalar(seq_1, seq_2)
+    }
+    #[cfg(not(feature = "scalar_match_counting"))]
+    { count_backward_vectorized(seq_1, seq_2) }
+    #[cfg(feature = "scalar_match_counting")]
+    { count_backward_scalar(seq_1, seq_2) }
+}
+
+// Dispatch
+//  - From here, two sequences always have the same length.
+#[cfg(target_arch = "x86_64")]
+#[inline]
+fn count_forward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    if is_x86_feature_detected!("avx2") {
+        unsafe { x86::count_forward_avx2(seq_1, seq_2) }
+    } else {
+        unsafe { x86::count_forward_sse2(seq_1, seq_2) }
+    }
+}
+#[cfg(target_arch = "x86_64")]
+#[inline]
+fn count_backward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    if is_x86_feature_detected!("avx2") {
+        unsafe { x86::count_backward_avx2(seq_1, seq_2) }
+    } else {
+        unsafe { x86::count_backward_sse2(seq_1, seq_2) }
+    }
+}
+#[cfg(target_arch = "aarch64")]
+#[inline]
+fn count_forward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    if std::arch::is_aarch64_feature_detected!("neon") {
+        unsafe { aarch64::count_forward_neon(seq_1, seq_2) }
+    } else {
+        count_forward_word(seq_1, seq_2)
+    }
+}
+#[cfg(target_arch = "aarch64")]
+#[inline]
+fn count_backward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    if std::arch::is_aarch64_feature_detected!("neon") {
+        unsafe { aarch64::count_backward_neon(seq_1, seq_2) }
+    } else {
+        count_backward_word(seq_1, seq_2)
+    }
+}
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[inline]
+fn count_forward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    count_forward_word(seq_1, seq_2)
+}
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[inline]
+fn count_backward_vectorized(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    count_backward_word(seq_1, seq_2)
+}
+
+// Scalar
+#[inline]
+fn count_forward_scalar(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    seq_1.iter().zip(seq_2.iter()).take_while(|(v1, v2)| v1 == v2).count()
+}
+#[inline]
+fn count_backward_scalar(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    seq_1.iter().rev().zip(seq_2.iter().rev()).take_while(|(v1, v2)| v1 == v2).count()
+}
+
+// Word-at-a-time (portable)
+const WORD_SIZE: usize = std::mem::size_of::<u64>();
+
+#[inline]
+fn count_forward_word(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    let len = seq_1.len();
+    let mut count = 0;
+    while count + WORD_SIZE <= len {
+        let xor = read_word(seq_1, count) ^ read_word(seq_2, count);
+        if xor != 0 {
+            return count + (xor.trailing_zeros() / 8) as usize;
+        }
+        count += WORD_SIZE;
+    }
+    count + count_forward_scalar(&seq_1[count..], &seq_2[count..])
+}
+#[inline]
+fn count_backward_word(seq_1: &[u8], seq_2: &[u8]) -> usize {
+    let len = seq_1.len();
+    let mut count = 0;
+    while count + WORD_SIZE <= len {
+        let start = len - count - WORD_SIZE;
+        let xor = read_word(seq_1, start) ^ read_word(seq_2, start);
+        if xor != 0 {
+            return count + (xor.leading_zeros() / 8) as usize;
+        }
+        count += WORD_SIZE;
+    }
+    count + count_backward_scalar(&seq_1[..len-count], &seq_2[..len-count])
+}
+#[inline(always)]
+fn read_word(seq: &[u8], start: usize) -> u64 {
+    // Little endian: the first byte is the lowest byte.
+    u64::from_le_bytes(seq[start..start+WORD_SIZE].try_into().unwrap())
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+    use super::{count_forward_word, count_backward_word};
+
+    const AVX2_SIZE: usize = 32;
+    const SSE2_SIZE: usize = 16;
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn count_forward_avx2(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + AVX2_SIZE <= len {
+            let v1 = _mm256_loadu_si256(seq_1.as_ptr().add(count) as *const __m256i);
+            let v2 = _mm256_loadu_si256(seq_2.as_ptr().add(count) as *const __m256i);
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(v1, v2)) as u32;
+            if mask != u32::MAX {
+                return count + (!mask).trailing_zeros() as usize;
+            }
+            count += AVX2_SIZE;
+        }
+        count + count_forward_word(&seq_1[count..], &seq_2[count..])
+    }
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn count_backward_avx2(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + AVX2_SIZE <= len {
+            let start = len - count - AVX2_SIZE;
+            let v1 = _mm256_loadu_si256(seq_1.as_ptr().add(start) as *const __m256i);
+            let v2 = _mm256_loadu_si256(seq_2.as_ptr().add(start) as *const __m256i);
+            let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(v1, v2)) as u32;
+            if mask != u32::MAX {
+                return count + (!mask).leading_zeros() as usize;
+            }
+            count += AVX2_SIZE;
+        }
+        count + count_backward_word(&seq_1[..len-count], &seq_2[..len-count])
+    }
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn count_forward_sse2(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + SSE2_SIZE <= len {
+            let v1 = _mm_loadu_si128(seq_1.as_ptr().add(count) as *const __m128i);
+            let v2 = _mm_loadu_si128(seq_2.as_ptr().add(count) as *const __m128i);
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(v1, v2)) as u16;
+            if mask != u16::MAX {
+                return count + (!mask).trailing_zeros() as usize;
+            }
+            count += SSE2_SIZE;
+        }
+        count + count_forward_word(&seq_1[count..], &seq_2[count..])
+    }
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn count_backward_sse2(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + SSE2_SIZE <= len {
+            let start = len - count - SSE2_SIZE;
+            let v1 = _mm_loadu_si128(seq_1.as_ptr().add(start) as *const __m128i);
+            let v2 = _mm_loadu_si128(seq_2.as_ptr().add(start) as *const __m128i);
+            let mask = _mm_movemask_epi8(_mm_cmpeq_epi8(v1, v2)) as u16;
+            if mask != u16::MAX {
+                return count + (!mask).leading_zeros() as usize;
+            }
+            count += SSE2_SIZE;
+        }
+        count + count_backward_word(&seq_1[..len-count], &seq_2[..len-count])
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64 {
+    use std::arch::aarch64::*;
+    use super::{count_forward_word, count_backward_word};
+
+    const NEON_SIZE: usize = 16;
+
+    // NEON has no movemask: if a chunk has a mismatch, the word-at-a-time path finds it.
+    #[target_feature(enable = "neon")]
+    pub unsafe fn count_forward_neon(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + NEON_SIZE <= len {
+            let v1 = vld1q_u8(seq_1.as_ptr().add(count));
+            let v2 = vld1q_u8(seq_2.as_ptr().add(count));
+            if vminvq_u8(vceqq_u8(v1, v2)) != u8::MAX {
+                break;
+            }
+            count += NEON_SIZE;
+        }
+        count + count_forward_word(&seq_1[count..], &seq_2[count..])
+    }
+    #[target_feature(enable = "neon")]
+    pub unsafe fn count_backward_neon(seq_1: &[u8], seq_2: &[u8]) -> usize {
+        let len = seq_1.len();
+        let mut count = 0;
+        while count + NEON_SIZE <= len {
+            let start = len - count - NEON_SIZE;
+            let v1 = vld1q_u8(seq_1.as_ptr().add(start));
+            let v2 = vld1q_u8(seq_2.as_ptr().add(start));
+            if vminvq_u8(vceqq_u8(v1, v2)) != u8::MAX {
+                break;
+            }
+            count += NEON_SIZE;
+        }
+        count + count_backward_word(&seq_1[..len-count], &seq_2[..len-count])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pairs of sequences that have a mismatch at every position (or none)
+    fn sequence_pairs() -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut seed: u32 = 1;
+        let mut pairs = Vec::new();
+        for len in 0..100 {
+            let seq: Vec<u8> = (0..len).map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                b"ACGT"[((seed >> 16) % 4) as usize]
+            }).collect();
+            pairs.push((seq.clone(), seq.clone()));
+            for mismatch_position in 0..len {
+                let mut other = seq.clone();
+                other[mismatch_position] = b'N';
+                pairs.push((seq.clone(), other));
+            }
+        }
+        // Longer sequences with several mismatches
+        for len in (100..400).step_by(7) {
+            let seq: Vec<u8> = (0..len).map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                b"ACGT"[((seed >> 16) % 4) as usize]
+            }).collect();
+            let mut other = seq.clone();
+            for _ in 0..3 {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                other[(seed >> 16) as usize % len] = b'N';
+            }
+            pairs.push((seq, other));
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_vectorized_counting_is_same_as_scalar() {
+        for (seq_1, seq_2) in sequence_pairs() {
+            let forward = count_forward_scalar(&seq_1, &seq_2);
+            let backward = count_backward_scalar(&seq_1, &seq_2);
+            assert_eq!(count_forward_word(&seq_1, &seq_2), forward);
+            assert_eq!(count_backward_word(&seq_1, &seq_2), backward);
+            assert_eq!(count_forward_vectorized(&seq_1, &seq_2), forward);
+            assert_eq!(count_backward_vectorized(&seq_1, &seq_2), backward);
+            assert_eq!(count_forward_match(&seq_1, &seq_2), forward);
+            assert_eq!(count_backward_match(&seq_1, &seq_2), backward);
+            #[cfg(target_arch = "x86_64")]
+            unsafe {
+                assert_eq!(x86::count_forward_sse2(&seq_1, &seq_2), forward);
+                assert_eq!(x86::count_backward_sse2(&seq_1, &seq_2), backward);
+                if is_x86_feature_detected!("avx2") {
+                    assert_eq!(x86::count_forward_avx2(&seq_1, &seq_2), forward);
+                    assert_eq!(x86::count_backward_avx2(&seq_1, &seq_2), backward);
+                }
+            }
+        }
+    }
+    #[test]
+    fn test_sequences_of_different_lengths() {
+        let seq_1 = b"ACGTACGTACGTACGTACGTACGTACGTACGTACGTACGT";
+        let seq_2 = b"ACGTACGTACGTACGTACGTACGTACGTACGTAC";
+        assert_eq!(count_forward_match(seq_1, seq_2), seq_2.len());
+        assert_eq!(count_backward_match(&seq_1[..seq_2.len()+4], seq_2), seq_2.len());
+    }
+}
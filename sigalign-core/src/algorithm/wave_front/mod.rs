@@ -1,9 +1,13 @@
 use crate::core::regulators::Penalty;
 use super::AnchorIndex;
-use bytemuck::{Pod, Zeroable};
 
 mod match_counter;
-use match_counter::{MatchCounter, ForwardMatchCounter, ReverseMatchCounter};
+use match_counter::{
+    MatchCounter, ForwardMatchCounter, ReverseMatchCounter,
+    IupacForwardMatchCounter, IupacReverseMatchCounter,
+};
+#[cfg(feature = "switchable_match_counting")]
+pub use match_counter::use_scalar_match_counting;
 mod fill;
 mod backtrace;
 pub use backtrace::BackTraceResult;
@@ -26,6 +30,7 @@ pub struct WaveEndPoint {
 pub struct WaveFrontScore {
     pub max_k: i32,
     pub components_by_k: Vec<Components>, // (-max_k..=max_k)
+    pub second_gap_components_by_k: Vec<GapComponents>, // (-max_k..=max_k), empty if the second gap penalty is not used
 }
 
 impl WaveFront {
@@ -34,11 +39,12 @@ impl WaveFront {
         max_penalty: usize,
     ) -> Self {
         let wave_front_score_count = max_penalty + 1;
-        let gap_open_penalty = penalties.o;
-        let gap_extend_penalty = penalties.e;
+        // With two-piece gap penalties, the lower envelope bounds the maximum gap length of each score.
+        let (gap_open_penalty, gap_extend_penalty) = penalties.lower_envelope_of_gap_penalties();
+        let use_second_gap = penalties.second_gap.is_some();
 
         let mut wave_front_scores: Vec<WaveFrontScore> = Vec::with_capacity(wave_front_score_count);
-        let first_wave_front_score = WaveFrontScore::with_max_k(0);
+        let first_wave_front_score = WaveFrontScore::with_max_k(0, use_second_gap);
 
         let optional_penalty_from_one_gap = max_penalty.checked_sub((gap_open_penalty + gap_extend_penalty) as usize);
 
@@ -52,11 +58,11 @@ impl WaveFront {
                 let rem = penalty_from_one_gap as u32 % gap_extend_penalty;
                 for max_k in 1..quot+1 {
                     (0..gap_extend_penalty).for_each(|_| {
-                        wave_front_scores.push(WaveFrontScore::with_max_k(max_k));
+                        wave_front_scores.push(WaveFrontScore::with_max_k(max_k, use_second_gap));
                     });
                 };
                 (0..rem+1).for_each(|_| {
-                    wave_front_scores.push(WaveFrontScore::with_max_k(quot+1));
+                    wave_front_scores.push(WaveFrontScore::with_max_k(quot+1, use_second_gap));
                 });
             },
             None => {
@@ -76,14 +82,26 @@ impl WaveFront {
     pub fn is_reached_to_sequence_end(&self) -> bool {
         self.end_point.k.is_some()
     }
+    /// Number of cells filled until the end point
+    pub fn count_filled_cells(&self) -> u64 {
+        self.wave_front_scores[..=self.end_point.penalty].iter().map(|wave_front_score| {
+            wave_front_score.components_by_k.len() as u64
+        }).sum()
+    }
 }
 
 impl WaveFrontScore {
     // New
-    fn with_max_k(max_k: i32) -> Self {
+    fn with_max_k(max_k: i32, use_second_gap: bool) -> Self {
+        let num_components = max_k as usize * 2 + 1;
         Self {
             max_k,
-            components_by_k: vec![Components::default(); max_k as usize * 2 + 1],
+            components_by_k: vec![Components::default(); num_components],
+            second_gap_components_by_k: if use_second_gap {
+                vec![GapComponents::default(); num_components]
+            } else {
+                Vec::new()
+            },
         }
     }
     // Get
@@ -102,16 +120,32 @@ impl WaveFrontScore {
     pub fn components_of_k_checked(&self, k: i32) -> Option<&Components> {
         self.components_by_k.get((self.max_k + k) as usize)
     }
+    pub fn d2_component_of_k(&self, k: i32) -> &Component {
+        &self.second_gap_components_by_k[(self.max_k + k) as usize].d
+    }
+    pub fn i2_component_of_k(&self, k: i32) -> &Component {
+        &self.second_gap_components_by_k[(self.max_k + k) as usize].i
+    }
+    pub fn second_gap_components_of_k_checked(&self, k: i32) -> Option<&GapComponents> {
+        self.second_gap_components_by_k.get((self.max_k + k) as usize)
+    }
 }
 
 // Components
 #[repr(C)]
-#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[derive(Debug, Clone, Copy)]
 pub struct Components {
     pub m: Component,
     pub d: Component,
     pub i: Component,
 }
+/// Gap components of the second piece of the two-piece affine gap penalty
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct GapComponents {
+    pub d: Component,
+    pub i: Component,
+}
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -119,9 +153,8 @@ pub struct Component {
     pub fr: i32,
     pub insertion_count: u16,
     pub bt: BackTraceMarker,
+    pub mismatch_index: u8, // Index of the mismatch penalty in the substitution matrix (only for M from M)
 }
-unsafe impl Pod for Component {}
-unsafe impl Zeroable for Component {}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BackTraceMarker {
@@ -130,6 +163,8 @@ pub enum BackTraceMarker {
     FromM = 2,
     FromD = 3,
     FromI = 4,
+    FromD2 = 5,
+    FromI2 = 6,
 }
 impl Default for Components {
     fn default() -> Self {
@@ -140,6 +175,14 @@ impl Default for Components {
         }
     }
 }
+impl Default for GapComponents {
+    fn default() -> Self {
+        Self {
+            d: Component::empty(),
+            i: Component::empty(),
+        }
+    }
+}
 impl Components {
     fn new_start_point(first_fr: i32) -> Self {
         Self {
@@ -157,6 +200,7 @@ impl Component {
             fr: 0,
             insertion_count: 0,
             bt: BackTraceMarker::Empty,
+            mismatch_index: 0,
         }
     }
     #[inline(always)]
@@ -165,6 +209,7 @@ impl Component {
             fr: first_fr,
             insertion_count: 0,
             bt: BackTraceMarker::Start,
+            mismatch_index: 0,
         }
     }
 }
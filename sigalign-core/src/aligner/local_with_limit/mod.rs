@@ -1,5 +1,6 @@
 use crate::{
-    results::AlignmentResult,
+    core::stats::{StatsCollector, NoStats},
+    results::{AlignmentResult, AlignmentStats},
     reference::{
         Reference, PatternIndex, SequenceStorage,
     },
@@ -27,6 +28,41 @@ impl<A: AllocationStrategy> Aligner for LocalWithLimitAligner<A> {
         sequence_buffer: &mut S::Buffer,
         sorted_target_indices: &[u32],
         query: &[u8],
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, None, &mut NoStats)
+    }
+    fn alignment_with_stats<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        stats: &mut AlignmentStats,
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, None, stats)
+    }
+    fn alignment_with_qualities<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: &[u8],
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, Some(query_qualities), &mut NoStats)
+    }
+}
+
+impl<A: AllocationStrategy> LocalWithLimitAligner<A> {
+    #[inline]
+    fn alignment_with_collector<I: PatternIndex, S: SequenceStorage, C: StatsCollector> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: Option<&[u8]>,
+        stats: &mut C,
     ) -> AlignmentResult {
         self.space_manager.allocate_more_space_if_needed(query.len() as u32, &self.regulator);
 
@@ -34,8 +70,10 @@ impl<A: AllocationStrategy> Aligner for LocalWithLimitAligner<A> {
             reference,
             sequence_buffer,
             query,
+            query_qualities,
             sorted_target_indices,
             self.regulator.pattern_size,
+            self.regulator.pattern_occurrence_cap,
             &self.regulator.penalties,
             &self.regulator.cutoff,
             &mut self.space_manager.spare_penalty_calculator,
@@ -47,6 +85,7 @@ impl<A: AllocationStrategy> Aligner for LocalWithLimitAligner<A> {
             &mut self.space_manager.operations_buffer,
             &mut self.space_manager.extension_buffer,
             self.limit,
+            stats,
         );
         result.multiply_gcd(self.regulator.gcd_for_compression);
         result
@@ -1,11 +1,11 @@
-use crate::results::AlignmentResult;
+use crate::results::{AlignmentResult, AlignmentStats};
 use crate::reference::{
     Reference, PatternIndex, SequenceStorage,
 };
 
 // Internal structures for Aligner
 mod regulator;
-pub use regulator::{AlignmentRegulator, RegulatorError};
+pub use regulator::{AlignmentRegulator, RegulatorError, SubstitutionMatrix, PHRED_OFFSET};
 
 mod space_manager;
 use space_manager::{
@@ -19,6 +19,8 @@ use space_manager::{
     MultipleSemiGlobalSpaceManager,
 };
 pub use space_manager::AllocationStrategy;
+#[cfg(feature = "switchable_match_counting")]
+pub use crate::algorithm::use_scalar_match_counting;
 
 // Aligner
 mod local;
@@ -45,4 +47,30 @@ pub trait Aligner {
         sorted_target_indices: &[u32],
         query: &[u8],
     ) -> AlignmentResult;
+    /// `alignment` that accumulates the diagnostics of the alignment to the `stats`
+    ///  - By default, the diagnostics are not collected, and the `stats` is left unchanged.
+    fn alignment_with_stats<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        _stats: &mut AlignmentStats,
+    ) -> AlignmentResult {
+        self.alignment(reference, sequence_buffer, sorted_target_indices, query)
+    }
+    /// `alignment` with the qualities of the query bases (Phred+33)
+    ///  - The qualities are used only by the regulator with the quality-aware mismatch penalty.
+    ///  - The length of `query_qualities` must be the same as the `query`.
+    ///  - By default, the qualities are ignored.
+    fn alignment_with_qualities<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        _query_qualities: &[u8],
+    ) -> AlignmentResult {
+        self.alignment(reference, sequence_buffer, sorted_target_indices, query)
+    }
 }
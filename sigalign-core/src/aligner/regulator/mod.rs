@@ -1,18 +1,32 @@
 use crate::core::regulators::{
-    Penalty, PREC_SCALE, Cutoff, MinPenaltyForPattern,
+    Penalty, GapPenalty, QualityMismatchPenalty, PREC_SCALE, Cutoff, MinPenaltyForPattern,
 };
+pub use crate::core::regulators::{SubstitutionMatrix, PHRED_OFFSET};
 use crate::results::{
     AlignmentResult, AnchorAlignmentResult, TargetAlignmentResult,
 };
 use thiserror::Error;
 use num::integer::{div_ceil, gcd};
 
+mod substitution_matrix;
+mod quality_mismatch;
+
 #[derive(Error, Debug)]
 pub enum RegulatorError {
     #[error("Gap extend penalty only allow positive integer.")]
     InvalidGapExtendPenalty,
     #[error("Maximum penalty per length only allow positive value.")]
     InvalidMaxPenaltyPerLength,
+    #[error("Invalid substitution matrix: {0}")]
+    InvalidSubstitutionMatrix(String),
+    #[error("Second gap penalty requires larger gap-open penalty and smaller gap-extend penalty than the first.")]
+    InvalidSecondGapPenalty,
+    #[error("Gap extend penalty must be larger than the maximum penalty per length.")]
+    TooSmallGapExtendPenalty,
+    #[error("Minimum mismatch penalty must be positive and not larger than the mismatch penalty.")]
+    InvalidMinimumMismatchPenalty,
+    #[error("Ambiguous match penalty must be smaller than the mismatch penalty.")]
+    InvalidAmbiguousMatchPenalty,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -22,6 +36,7 @@ pub struct AlignmentRegulator {
     pub(crate) min_penalty_for_pattern: MinPenaltyForPattern,
     pub(crate) gcd_for_compression: u32,
     pub(crate) pattern_size: u32,
+    pub(crate) pattern_occurrence_cap: Option<u32>,
 }
 
 impl AlignmentRegulator {
@@ -45,6 +60,124 @@ impl AlignmentRegulator {
         
         Ok(aligner)
     }
+    /// Generate new aligner with the substitution matrix instead of the single mismatch penalty.
+    pub fn new_with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_alignment_length: u32,
+        maximum_penalty_per_alignment_length: f32,
+    ) -> Result<Self, RegulatorError> {
+        if gap_extend_penalty == 0 {
+            return Err(RegulatorError::InvalidGapExtendPenalty);
+        } else if maximum_penalty_per_alignment_length <= 0.0 {
+            return Err(RegulatorError::InvalidMaxPenaltyPerLength);
+        }
+
+        let penalties = Penalty::new_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty);
+        let cutoff = Cutoff::new(minimum_alignment_length, maximum_penalty_per_alignment_length);
+        let aligner = Self::new_with_penalties_and_cutoff(penalties, cutoff);
+
+        Ok(aligner)
+    }
+    /// Generate new aligner with the two-piece affine gap penalty.
+    ///  - The cost of a gap of length `L` is `min(o1 + e1*L, o2 + e2*L)`.
+    ///  - `o2` must be larger than `o1` and `e2` must be smaller than `e1`.
+    pub fn new_with_two_piece_gap(
+        mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        second_gap_open_penalty: u32,
+        second_gap_extend_penalty: u32,
+        minimum_alignment_length: u32,
+        maximum_penalty_per_alignment_length: f32,
+    ) -> Result<Self, RegulatorError> {
+        if second_gap_extend_penalty == 0 {
+            return Err(RegulatorError::InvalidGapExtendPenalty);
+        } else if maximum_penalty_per_alignment_length <= 0.0 {
+            return Err(RegulatorError::InvalidMaxPenaltyPerLength);
+        } else if second_gap_open_penalty <= gap_open_penalty || second_gap_extend_penalty >= gap_extend_penalty {
+            return Err(RegulatorError::InvalidSecondGapPenalty);
+        } else if second_gap_extend_penalty as f32 <= maximum_penalty_per_alignment_length {
+            // Otherwise, a gap of infinite length can satisfy the cutoff.
+            return Err(RegulatorError::TooSmallGapExtendPenalty);
+        }
+
+        let mut penalties = Penalty::new(mismatch_penalty, gap_open_penalty, gap_extend_penalty);
+        penalties.second_gap = Some(GapPenalty {
+            o: second_gap_open_penalty,
+            e: second_gap_extend_penalty,
+        });
+        let cutoff = Cutoff::new(minimum_alignment_length, maximum_penalty_per_alignment_length);
+        let aligner = Self::new_with_penalties_and_cutoff(penalties, cutoff);
+
+        Ok(aligner)
+    }
+    /// Generate new aligner with the mismatch penalty depending on the quality of the query base.
+    ///  - The penalty of a mismatch at the base of Phred score `q` is linearly interpolated
+    ///    from `minimum_mismatch_penalty` (q=0) to `mismatch_penalty` (q>=40).
+    ///  - The query without the quality uses `mismatch_penalty` for all mismatches.
+    ///  - The pattern size is decided by `minimum_mismatch_penalty` not to miss the alignments.
+    pub fn new_with_quality_aware_mismatch(
+        mismatch_penalty: u32,
+        minimum_mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_alignment_length: u32,
+        maximum_penalty_per_alignment_length: f32,
+    ) -> Result<Self, RegulatorError> {
+        if gap_extend_penalty == 0 {
+            return Err(RegulatorError::InvalidGapExtendPenalty);
+        } else if maximum_penalty_per_alignment_length <= 0.0 {
+            return Err(RegulatorError::InvalidMaxPenaltyPerLength);
+        } else if minimum_mismatch_penalty == 0 || minimum_mismatch_penalty > mismatch_penalty {
+            return Err(RegulatorError::InvalidMinimumMismatchPenalty);
+        }
+
+        let quality_mismatch = QualityMismatchPenalty::new(mismatch_penalty, minimum_mismatch_penalty);
+        let mut penalties = Penalty::new(quality_mismatch.get_minimum_penalty(), gap_open_penalty, gap_extend_penalty);
+        penalties.quality_mismatch = Some(quality_mismatch);
+        let cutoff = Cutoff::new(minimum_alignment_length, maximum_penalty_per_alignment_length);
+        let aligner = Self::new_with_penalties_and_cutoff(penalties, cutoff);
+
+        Ok(aligner)
+    }
+    /// Generate new aligner matching the IUPAC ambiguity codes.
+    ///  - The different codes sharing a nucleotide (e.g., `R` and `A`) are the ambiguous match
+    ///    with the `ambiguous_match_penalty`, and are recorded as `AlignmentOperation::AmbiguousMatch`.
+    ///  - The patterns are still located by the exact match.
+    ///  - ⚠️ If `ambiguous_match_penalty` is 0, the completeness is not guaranteed:
+    ///    the alignment whose every pattern has the ambiguous match can be missed.
+    pub fn new_with_iupac_matching(
+        mismatch_penalty: u32,
+        ambiguous_match_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        minimum_alignment_length: u32,
+        maximum_penalty_per_alignment_length: f32,
+    ) -> Result<Self, RegulatorError> {
+        if gap_extend_penalty == 0 {
+            return Err(RegulatorError::InvalidGapExtendPenalty);
+        } else if maximum_penalty_per_alignment_length <= 0.0 {
+            return Err(RegulatorError::InvalidMaxPenaltyPerLength);
+        } else if ambiguous_match_penalty >= mismatch_penalty {
+            return Err(RegulatorError::InvalidAmbiguousMatchPenalty);
+        }
+
+        // The ambiguous match of zero penalty is counted as the match in the wave front.
+        // Otherwise, it is the mismatch of the reduced penalty.
+        let mut penalties = if ambiguous_match_penalty == 0 {
+            Penalty::new(mismatch_penalty, gap_open_penalty, gap_extend_penalty)
+        } else {
+            let substitution_matrix = SubstitutionMatrix::new_iupac(ambiguous_match_penalty, mismatch_penalty)?;
+            Penalty::new_with_substitution_matrix(substitution_matrix, gap_open_penalty, gap_extend_penalty)
+        };
+        penalties.ambiguous_match = Some(ambiguous_match_penalty);
+        let cutoff = Cutoff::new(minimum_alignment_length, maximum_penalty_per_alignment_length);
+        let aligner = Self::new_with_penalties_and_cutoff(penalties, cutoff);
+
+        Ok(aligner)
+    }
     fn new_with_penalties_and_cutoff(mut penalties: Penalty, mut cutoff: Cutoff) -> Self {
         let gcd = penalties.gcd_of_penalties();
         penalties.divide_by_gcd(gcd);
@@ -59,6 +192,7 @@ impl AlignmentRegulator {
             min_penalty_for_pattern,
             gcd_for_compression: gcd,
             pattern_size: max_pattern_size,
+            pattern_occurrence_cap: None,
         }
     }
     pub fn result_of_uncompressed_penalty(&self, mut reference_alignment_result: AlignmentResult) -> AlignmentResult {
@@ -69,8 +203,35 @@ impl AlignmentRegulator {
         reference_alignment_result
     }
     /// Get mismatch penalty
+    ///  - If the substitution matrix is used, the minimum penalty of the matrix.
+    ///  - If the quality-aware mismatch penalty is used, the penalty of the highest quality.
+    ///  - If the IUPAC matching is used, the penalty of the bases sharing no nucleotide.
     pub fn get_mismatch_penalty(&self) -> u32 {
-        self.penalties.x * self.gcd_for_compression
+        match (&self.penalties.quality_mismatch, &self.penalties.ambiguous_match, &self.penalties.substitution_matrix) {
+            (Some(quality_mismatch), _, _) => quality_mismatch.get_maximum_penalty() * self.gcd_for_compression,
+            (None, Some(_), Some(substitution_matrix)) => substitution_matrix.penalty_for_undefined * self.gcd_for_compression,
+            _ => self.penalties.x * self.gcd_for_compression,
+        }
+    }
+    /// Get mismatch penalty of the lowest quality
+    ///  - `None` if the quality-aware mismatch penalty is not used.
+    pub fn get_minimum_mismatch_penalty(&self) -> Option<u32> {
+        self.penalties.quality_mismatch.as_ref().map(|quality_mismatch| {
+            quality_mismatch.get_minimum_penalty() * self.gcd_for_compression
+        })
+    }
+    /// Get substitution matrix
+    pub fn get_substitution_matrix(&self) -> Option<SubstitutionMatrix> {
+        self.penalties.substitution_matrix.as_ref().map(|substitution_matrix| {
+            let mut substitution_matrix = substitution_matrix.clone();
+            substitution_matrix.multiply_gcd(self.gcd_for_compression);
+            substitution_matrix
+        })
+    }
+    /// Get penalty of the ambiguous match of the IUPAC codes
+    ///  - `None` if the IUPAC matching is not used.
+    pub fn get_ambiguous_match_penalty(&self) -> Option<u32> {
+        self.penalties.ambiguous_match.map(|penalty| penalty * self.gcd_for_compression)
     }
     /// Get gap-open penalty
     pub fn get_gap_open_penalty(&self) -> u32 {
@@ -80,6 +241,13 @@ impl AlignmentRegulator {
     pub fn get_gap_extend_penalty(&self) -> u32 {
         self.penalties.e * self.gcd_for_compression
     }
+    /// Get (gap-open, gap-extend) penalties of the second piece
+    ///  - `None` if the two-piece gap penalty is not used.
+    pub fn get_second_gap_penalties(&self) -> Option<(u32, u32)> {
+        self.penalties.second_gap.as_ref().map(|second_gap| {
+            (second_gap.o * self.gcd_for_compression, second_gap.e * self.gcd_for_compression)
+        })
+    }
     /// Get minimum aligned length
     pub fn get_minimum_aligned_length(&self) -> u32 {
         self.cutoff.minimum_length
@@ -92,6 +260,16 @@ impl AlignmentRegulator {
     pub fn get_pattern_size(&self) -> u32 {
         self.pattern_size
     }
+    /// Set the maximum occurrences of a pattern in the reference.
+    ///  - The pattern occurring more than the cap is not used as the anchor.
+    ///  - `None` (default) uses all patterns. Only in this case, all alignments satisfying the cutoff are guaranteed to be found.
+    pub fn set_pattern_occurrence_cap(&mut self, cap: Option<u32>) {
+        self.pattern_occurrence_cap = cap;
+    }
+    /// Get the maximum occurrences of a pattern in the reference
+    pub fn get_pattern_occurrence_cap(&self) -> Option<u32> {
+        self.pattern_occurrence_cap
+    }
 }
 
 #[inline]
@@ -154,15 +332,53 @@ impl Penalty {
             x: mismatch,
             o: gap_open,
             e: gap_extend,
+            substitution_matrix: None,
+            second_gap: None,
+            quality_mismatch: None,
+            ambiguous_match: None,
+        }
+    }
+    fn new_with_substitution_matrix(substitution_matrix: SubstitutionMatrix, gap_open: u32, gap_extend: u32) -> Self {
+        Self {
+            x: substitution_matrix.get_minimum_penalty(),
+            o: gap_open,
+            e: gap_extend,
+            substitution_matrix: Some(substitution_matrix),
+            second_gap: None,
+            quality_mismatch: None,
+            ambiguous_match: None,
         }
     }
     fn gcd_of_penalties(&self) -> u32 {
-        gcd(gcd(self.x, self.o), self.e)
+        let mut gcd_of_gaps = gcd(gcd(self.x, self.o), self.e);
+        if let Some(second_gap) = &self.second_gap {
+            gcd_of_gaps = gcd(gcd(gcd_of_gaps, second_gap.o), second_gap.e);
+        }
+        if let Some(quality_mismatch) = &self.quality_mismatch {
+            gcd_of_gaps = gcd(gcd_of_gaps, quality_mismatch.gcd_of_penalties());
+        }
+        match &self.substitution_matrix {
+            None => gcd_of_gaps,
+            Some(substitution_matrix) => gcd(gcd_of_gaps, substitution_matrix.gcd_of_penalties()),
+        }
     }
     fn divide_by_gcd(&mut self, gcd: u32) {
         self.x /= gcd;
         self.o /= gcd;
         self.e /= gcd;
+        if let Some(second_gap) = &mut self.second_gap {
+            second_gap.o /= gcd;
+            second_gap.e /= gcd;
+        }
+        if let Some(substitution_matrix) = &mut self.substitution_matrix {
+            substitution_matrix.divide_by_gcd(gcd);
+        }
+        if let Some(quality_mismatch) = &mut self.quality_mismatch {
+            quality_mismatch.divide_by_gcd(gcd);
+        }
+        if let Some(ambiguous_match) = &mut self.ambiguous_match {
+            *ambiguous_match /= gcd;
+        }
     }
 }
 
@@ -184,18 +400,21 @@ impl Cutoff {
 
 impl MinPenaltyForPattern {
     fn new(penalties: &Penalty) -> Self {
+        // The lower envelope of the two-piece gap penalty never exceeds the real gap cost,
+        // so the minimum penalties stay valid lower bounds.
+        let (o, e) = penalties.lower_envelope_of_gap_penalties();
         let odd: u32;
         let even: u32;
-        if penalties.x <= penalties.o + penalties.e {
+        if penalties.x <= o + e {
             odd = penalties.x;
-            if penalties.x * 2 <= penalties.o + (penalties.e * 2) {
+            if penalties.x * 2 <= o + (e * 2) {
                 even = penalties.x;
             } else {
-                even = penalties.o + (penalties.e * 2) - penalties.x;
+                even = o + (e * 2) - penalties.x;
             }
         } else {
-            odd = penalties.o + penalties.e;
-            even = penalties.e;
+            odd = o + e;
+            even = e;
         }
         Self {
             odd,
@@ -221,6 +440,26 @@ mod tests {
         assert_eq!(gcd, 1);
         penalties.divide_by_gcd(gcd);
         assert_eq!(penalties, Penalty::new(4, 5, 3));
+
+        let substitution_matrix = SubstitutionMatrix::new_transition_transversion(2, 6).unwrap();
+        let mut penalties = Penalty::new_with_substitution_matrix(substitution_matrix, 8, 4);
+        let gcd = penalties.gcd_of_penalties();
+        assert_eq!(gcd, 2);
+        penalties.divide_by_gcd(gcd);
+        assert_eq!(penalties.x, 1);
+        assert_eq!(penalties.substitution_matrix.unwrap().penalty_of_pair(b'A', b'T'), 3);
+
+        let mut penalties = Penalty::new(4, 6, 2);
+        penalties.second_gap = Some(GapPenalty { o: 24, e: 1 });
+        let gcd = penalties.gcd_of_penalties();
+        assert_eq!(gcd, 1);
+    }
+    #[test]
+    fn test_pattern_size_is_not_increased_by_second_gap() {
+        let regulator = AlignmentRegulator::new(4, 6, 2, 100, 0.1).unwrap();
+        let two_piece_regulator = AlignmentRegulator::new_with_two_piece_gap(4, 6, 2, 24, 1, 100, 0.1).unwrap();
+        assert!(two_piece_regulator.get_pattern_size() <= regulator.get_pattern_size());
+        assert_eq!(two_piece_regulator.get_second_gap_penalties(), Some((24, 1)));
     }
 
     #[allow(dead_code)]
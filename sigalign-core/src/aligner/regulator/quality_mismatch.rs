@@ -0,0 +1,38 @@
+use crate::core::regulators::{
+    QualityMismatchPenalty, PHRED_OFFSET, QUALITY_CAP,
+};
+use num::integer::gcd;
+
+impl QualityMismatchPenalty {
+    /// `min_penalty` for the Phred score 0, and `max_penalty` for `QUALITY_CAP` or higher.
+    pub(crate) fn new(max_penalty: u32, min_penalty: u32) -> Self {
+        let penalty_of_phred = |phred: u8| -> u32 {
+            let phred = phred.min(QUALITY_CAP) as u32;
+            min_penalty + ((max_penalty - min_penalty) * phred + QUALITY_CAP as u32 / 2) / QUALITY_CAP as u32
+        };
+        let mut distinct_penalties: Vec<u32> = (0..=QUALITY_CAP).map(penalty_of_phred).collect();
+        distinct_penalties.dedup(); // Already sorted
+
+        let mut index_of_quality = [0; 256];
+        for (quality, index) in index_of_quality.iter_mut().enumerate() {
+            let penalty = penalty_of_phred((quality as u8).saturating_sub(PHRED_OFFSET));
+            *index = distinct_penalties.binary_search(&penalty).unwrap() as u8;
+        }
+        Self {
+            index_of_quality,
+            distinct_penalties,
+        }
+    }
+    pub(crate) fn get_minimum_penalty(&self) -> u32 {
+        self.distinct_penalties[0]
+    }
+    pub(crate) fn get_maximum_penalty(&self) -> u32 {
+        *self.distinct_penalties.last().unwrap()
+    }
+    pub(crate) fn gcd_of_penalties(&self) -> u32 {
+        self.distinct_penalties.iter().fold(0, |acc, penalty| gcd(acc, *penalty))
+    }
+    pub(crate) fn divide_by_gcd(&mut self, gcd: u32) {
+        self.distinct_penalties.iter_mut().for_each(|penalty| *penalty /= gcd);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::core::{
+    regulators::{SubstitutionMatrix, UNDEFINED_BASE_INDEX},
+    iupac::{IUPAC_CODES, is_compatible},
+};
+use super::RegulatorError;
+use num::integer::gcd;
+
+// The index of distinct penalties is stored in `u8`.
+const MAX_DISTINCT_PENALTY_COUNT: usize = u8::MAX as usize + 1;
+
+impl SubstitutionMatrix {
+    /// Make a new substitution matrix.
+    ///  - `penalties[i][j]` is the penalty when the `alphabet[i]` in target is aligned to the `alphabet[j]` in query.
+    ///  - The penalties on the diagonal have to be 0, and the others have to be positive.
+    ///  - If only one case of a letter is in the alphabet, the other case is treated as the same base.
+    pub fn new<P: AsRef<[u32]>>(
+        alphabet: &[u8],
+        penalties: &[P],
+    ) -> Result<Self, RegulatorError> {
+        let alphabet_size = alphabet.len();
+        if alphabet_size == 0 || alphabet_size >= UNDEFINED_BASE_INDEX as usize {
+            return Err(RegulatorError::InvalidSubstitutionMatrix("The size of alphabet must be in 1..255".to_string()));
+        }
+        let mut index_of_base = [UNDEFINED_BASE_INDEX; 256];
+        for (index, base) in alphabet.iter().enumerate() {
+            if index_of_base[*base as usize] != UNDEFINED_BASE_INDEX {
+                return Err(RegulatorError::InvalidSubstitutionMatrix(format!("Duplicated base in alphabet: {}", *base as char)));
+            }
+            index_of_base[*base as usize] = index as u8;
+        }
+        for (index, base) in alphabet.iter().enumerate() {
+            let other_case = if base.is_ascii_uppercase() {
+                base.to_ascii_lowercase()
+            } else {
+                base.to_ascii_uppercase()
+            };
+            if index_of_base[other_case as usize] == UNDEFINED_BASE_INDEX {
+                index_of_base[other_case as usize] = index as u8;
+            }
+        }
+
+        if penalties.len() != alphabet_size || penalties.iter().any(|row| row.as_ref().len() != alphabet_size) {
+            return Err(RegulatorError::InvalidSubstitutionMatrix("The shape of penalties must be the (alphabet size) x (alphabet size)".to_string()));
+        }
+        let mut flattened_penalties = Vec::with_capacity(alphabet_size * alphabet_size);
+        for (target_index, row) in penalties.iter().enumerate() {
+            for (query_index, penalty) in row.as_ref().iter().enumerate() {
+                if target_index == query_index && *penalty != 0 {
+                    return Err(RegulatorError::InvalidSubstitutionMatrix("The penalty of the same base must be 0".to_string()));
+                } else if target_index != query_index && *penalty == 0 {
+                    return Err(RegulatorError::InvalidSubstitutionMatrix("The penalty of the different bases must be positive".to_string()));
+                }
+                flattened_penalties.push(*penalty);
+            }
+        }
+
+        let mut distinct_penalties: Vec<u32> = flattened_penalties.iter().filter(|v| **v != 0).cloned().collect();
+        distinct_penalties.sort_unstable();
+        distinct_penalties.dedup();
+        if distinct_penalties.len() > MAX_DISTINCT_PENALTY_COUNT {
+            return Err(RegulatorError::InvalidSubstitutionMatrix(format!("The number of distinct penalties must be at most {}", MAX_DISTINCT_PENALTY_COUNT)));
+        }
+        // If the alphabet has only one base, there is no mismatch in the alphabet.
+        //  - Use 1 for the pair of undefined bases.
+        let penalty_for_undefined = distinct_penalties.last().cloned().unwrap_or(1);
+        if distinct_penalties.is_empty() {
+            distinct_penalties.push(penalty_for_undefined);
+        }
+
+        Ok(Self {
+            index_of_base,
+            alphabet_size,
+            penalties: flattened_penalties,
+            penalty_for_undefined,
+            distinct_penalties,
+        })
+    }
+    /// Make a new substitution matrix for DNA (`A`, `C`, `G`, `T`)
+    /// with the different penalties for transition (A<->G, C<->T) and transversion.
+    pub fn new_transition_transversion(
+        transition_penalty: u32,
+        transversion_penalty: u32,
+    ) -> Result<Self, RegulatorError> {
+        let ts = transition_penalty;
+        let tv = transversion_penalty;
+        Self::new(
+            b"ACGT",
+            &[
+                [0, tv, ts, tv],
+                [tv, 0, tv, ts],
+                [ts, tv, 0, tv],
+                [tv, ts, tv, 0],
+            ],
+        )
+    }
+    /// Make a new substitution matrix for the IUPAC codes.
+    ///  - The different codes sharing a nucleotide (e.g., `R` and `A`) have the `ambiguous_match_penalty`.
+    ///  - The others have the `mismatch_penalty`.
+    pub fn new_iupac(
+        ambiguous_match_penalty: u32,
+        mismatch_penalty: u32,
+    ) -> Result<Self, RegulatorError> {
+        let penalties: Vec<Vec<u32>> = IUPAC_CODES.iter().map(|target_base| {
+            IUPAC_CODES.iter().map(|query_base| {
+                if target_base == query_base {
+                    0
+                } else if is_compatible(*target_base, *query_base) {
+                    ambiguous_match_penalty
+                } else {
+                    mismatch_penalty
+                }
+            }).collect()
+        }).collect();
+        Self::new(IUPAC_CODES, &penalties)
+    }
+    /// Make a new substitution matrix from the similarity scores (e.g., BLOSUM).
+    ///  - The penalty of (a, b) is `S(a, a) + S(b, b) - 2 * S(a, b)`.
+    pub fn from_similarity_scores<S: AsRef<[i32]>>(
+        alphabet: &[u8],
+        scores: &[S],
+    ) -> Result<Self, RegulatorError> {
+        if scores.len() != alphabet.len() || scores.iter().any(|row| row.as_ref().len() != alphabet.len()) {
+            return Err(RegulatorError::InvalidSubstitutionMatrix("The shape of scores must be the (alphabet size) x (alphabet size)".to_string()));
+        }
+        let diagonal: Vec<i64> = scores.iter().enumerate().map(|(index, row)| row.as_ref()[index] as i64).collect();
+        let mut penalties = Vec::with_capacity(alphabet.len());
+        for (target_index, row) in scores.iter().enumerate() {
+            let mut penalties_of_row = Vec::with_capacity(alphabet.len());
+            for (query_index, score) in row.as_ref().iter().enumerate() {
+                let penalty = diagonal[target_index] + diagonal[query_index] - 2 * (*score as i64);
+                if penalty < 0 || penalty > u32::MAX as i64 {
+                    return Err(RegulatorError::InvalidSubstitutionMatrix("The score of different bases must be lower than the scores of the same bases".to_string()));
+                }
+                penalties_of_row.push(penalty as u32);
+            }
+            penalties.push(penalties_of_row);
+        }
+        Self::new(alphabet, &penalties)
+    }
+    /// BLOSUM62 derived substitution matrix for amino acids.
+    pub fn blosum62() -> Self {
+        Self::from_similarity_scores(BLOSUM62_ALPHABET, &BLOSUM62_SCORES).unwrap()
+    }
+    /// Get the minimum penalty of mismatch.
+    pub fn get_minimum_penalty(&self) -> u32 {
+        self.distinct_penalties[0]
+    }
+
+    pub(crate) fn gcd_of_penalties(&self) -> u32 {
+        self.distinct_penalties.iter().fold(0, |acc, v| gcd(acc, *v))
+    }
+    pub(crate) fn divide_by_gcd(&mut self, gcd: u32) {
+        self.penalties.iter_mut().for_each(|v| *v /= gcd);
+        self.distinct_penalties.iter_mut().for_each(|v| *v /= gcd);
+        self.penalty_for_undefined /= gcd;
+    }
+    pub(crate) fn multiply_gcd(&mut self, gcd: u32) {
+        self.penalties.iter_mut().for_each(|v| *v *= gcd);
+        self.distinct_penalties.iter_mut().for_each(|v| *v *= gcd);
+        self.penalty_for_undefined *= gcd;
+    }
+}
+
+const BLOSUM62_ALPHABET: &[u8] = b"ARNDCQEGHILKMFPSTWYVBZX*";
+#[rustfmt::skip]
+const BLOSUM62_SCORES: [[i32; 24]; 24] = [
+    [ 4, -1, -2, -2,  0, -1, -1,  0, -2, -1, -1, -1, -1, -2, -1,  1,  0, -3, -2,  0, -2, -1,  0, -4],
+    [-1,  5,  0, -2, -3,  1,  0, -2,  0, -3, -2,  2, -1, -3, -2, -1, -1, -3, -2, -3, -1,  0, -1, -4],
+    [-2,  0,  6,  1, -3,  0,  0,  0,  1, -3, -3,  0, -2, -3, -2,  1,  0, -4, -2, -3,  3,  0, -1, -4],
+    [-2, -2,  1,  6, -3,  0,  2, -1, -1, -3, -4, -1, -3, -3, -1,  0, -1, -4, -3, -3,  4,  1, -1, -4],
+    [ 0, -3, -3, -3,  9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1, -3, -3, -2, -4],
+    [-1,  1,  0,  0, -3,  5,  2, -2,  0, -3, -2,  1,  0, -3, -1,  0, -1, -2, -1, -2,  0,  3, -1, -4],
+    [-1,  0,  0,  2, -4,  2,  5, -2,  0, -3, -3,  1, -2, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4],
+    [ 0, -2,  0, -1, -3, -2, -2,  6, -2, -4, -4, -2, -3, -3, -2,  0, -2, -2, -3, -3, -1, -2, -1, -4],
+    [-2,  0,  1, -1, -3,  0,  0, -2,  8, -3, -3, -1, -2, -1, -2, -1, -2, -2,  2, -3,  0,  0, -1, -4],
+    [-1, -3, -3, -3, -1, -3, -3, -4, -3,  4,  2, -3,  1,  0, -3, -2, -1, -3, -1,  3, -3, -3, -1, -4],
+    [-1, -2, -3, -4, -1, -2, -3, -4, -3,  2,  4, -2,  2,  0, -3, -2, -1, -2, -1,  1, -4, -3, -1, -4],
+    [-1,  2,  0, -1, -3,  1,  1, -2, -1, -3, -2,  5, -1, -3, -1,  0, -1, -3, -2, -2,  0,  1, -1, -4],
+    [-1, -1, -2, -3, -1,  0, -2, -3, -2,  1,  2, -1,  5,  0, -2, -1, -1, -1, -1,  1, -3, -1, -1, -4],
+    [-2, -3, -3, -3, -2, -3, -3, -3, -1,  0,  0, -3,  0,  6, -4, -2, -2,  1,  3, -1, -3, -3, -1, -4],
+    [-1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4,  7, -1, -1, -4, -3, -2, -2, -1, -2, -4],
+    [ 1, -1,  1,  0, -1,  0,  0,  0, -1, -2, -2,  0, -1, -2, -1,  4,  1, -3, -2, -2,  0,  0,  0, -4],
+    [ 0, -1,  0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1,  1,  5, -2, -2,  0, -1, -1,  0, -4],
+    [-3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1,  1, -4, -3, -2, 11,  2, -3, -4, -3, -2, -4],
+    [-2, -2, -2, -3, -2, -1, -2, -3,  2, -1, -1, -2, -1,  3, -3, -2, -2,  2,  7, -1, -3, -2, -1, -4],
+    [ 0, -3, -3, -3, -1, -2, -2, -3, -3,  3,  1, -2,  1, -1, -2, -2,  0, -3, -1,  4, -3, -2, -1, -4],
+    [-2, -1,  3,  4, -3,  0,  1, -1,  0, -3, -4,  0, -3, -3, -2,  0, -1, -4, -3, -3,  4,  1, -1, -4],
+    [-1,  0,  0,  1, -3,  3,  4, -2,  0, -3, -3,  1, -1, -3, -1,  0, -1, -3, -2, -2,  1,  4, -1, -4],
+    [ 0, -1, -1, -1, -2, -1, -1, -1, -1, -1, -1, -1, -1, -1, -2,  0,  0, -2, -1, -1, -1, -1, -1, -4],
+    [-4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4, -4,  1],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blosum62_is_symmetric_and_valid() {
+        let scores_are_symmetric = (0..24).all(|i| (0..24).all(|j| BLOSUM62_SCORES[i][j] == BLOSUM62_SCORES[j][i]));
+        assert!(scores_are_symmetric);
+
+        let matrix = SubstitutionMatrix::blosum62();
+        assert_eq!(matrix.penalty_of_pair(b'A', b'A'), 0);
+        assert_eq!(matrix.penalty_of_pair(b'I', b'V'), 2);
+        assert_eq!(matrix.penalty_of_pair(b'i', b'V'), 2);
+        assert_eq!(matrix.penalty_of_pair(b'W', b'?'), matrix.penalty_for_undefined);
+    }
+}
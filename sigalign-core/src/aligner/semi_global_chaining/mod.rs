@@ -1,5 +1,6 @@
 use crate::{
-    results::AlignmentResult,
+    core::stats::{StatsCollector, NoStats},
+    results::{AlignmentResult, AlignmentStats},
     reference::{
         Reference, PatternIndex, SequenceStorage,
     },
@@ -13,6 +14,7 @@ use super::{
     AllocationStrategy,
 };
 
+#[derive(Clone)]
 pub struct SemiGlobalChainingAligner<A: AllocationStrategy> {
     sorted_regulators: Vec<AlignmentRegulator>,
     space_manager: MultipleSemiGlobalSpaceManager<A>,
@@ -25,6 +27,41 @@ impl<A: AllocationStrategy> Aligner for SemiGlobalChainingAligner<A> {
         sequence_buffer: &mut S::Buffer,
         sorted_target_indices: &[u32],
         query: &[u8],
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, None, &mut NoStats)
+    }
+    fn alignment_with_stats<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        stats: &mut AlignmentStats,
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, None, stats)
+    }
+    fn alignment_with_qualities<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: &[u8],
+    ) -> AlignmentResult {
+        self.alignment_with_collector(reference, sequence_buffer, sorted_target_indices, query, Some(query_qualities), &mut NoStats)
+    }
+}
+
+impl<A: AllocationStrategy> SemiGlobalChainingAligner<A> {
+    #[inline]
+    fn alignment_with_collector<I: PatternIndex, S: SequenceStorage, C: StatsCollector> (
+        &mut self,
+        reference: &Reference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: Option<&[u8]>,
+        stats: &mut C,
     ) -> AlignmentResult {
         let most_lenient_regulator = self.sorted_regulators.last().unwrap();
         self.space_manager.allocate_more_space_if_needed(query.len() as u32, most_lenient_regulator);
@@ -37,8 +74,10 @@ impl<A: AllocationStrategy> Aligner for SemiGlobalChainingAligner<A> {
                 reference,
                 sequence_buffer,
                 query,
-                sorted_target_indices,
+                query_qualities,
+                &self.space_manager.sorted_target_indices_buffer,
                 regulator.pattern_size,
+                regulator.pattern_occurrence_cap,
                 &regulator.penalties,
                 &regulator.cutoff,
                 &mut self.space_manager.spare_penalty_calculators[index],
@@ -46,9 +85,11 @@ impl<A: AllocationStrategy> Aligner for SemiGlobalChainingAligner<A> {
                 &mut self.space_manager.traversed_anchor_index_buffer,
                 &mut self.space_manager.operations_buffer,
                 &mut self.space_manager.extension_buffer,
+                stats,
             );
             alignment_result.0.into_iter().for_each(|mut target_alignment_result| {
                 target_alignment_result.multiply_gcd(regulator.gcd_for_compression);
+                target_alignment_result.cutoff_tier = index as u32;
                 // Remove the target index from the buffer
                 let index_of_target_index = self.space_manager.sorted_target_indices_buffer.binary_search(&target_alignment_result.index).unwrap();
                 self.space_manager.sorted_target_indices_buffer.remove(index_of_target_index);
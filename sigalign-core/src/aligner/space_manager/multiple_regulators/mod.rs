@@ -25,6 +25,7 @@ pub trait MultipleSpaceManager {
     fn reset(&mut self);
 }
 
+#[derive(Clone)]
 pub struct MultipleLocalSpaceManager<A: AllocationStrategy> {
     query_length_checker: QueryLengthChecker<A>,
     pub spare_penalty_calculators: Vec<SparePenaltyCalculator>,
@@ -99,6 +100,7 @@ impl<A: AllocationStrategy> MultipleSpaceManager for MultipleLocalSpaceManager<A
     }
 }
 
+#[derive(Clone)]
 pub struct MultipleSemiGlobalSpaceManager<A: AllocationStrategy> {
     query_length_checker: QueryLengthChecker<A>,
     pub spare_penalty_calculators: Vec<SparePenaltyCalculator>,
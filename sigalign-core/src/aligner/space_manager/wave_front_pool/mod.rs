@@ -28,14 +28,15 @@ fn safe_max_penalty_from_len(
     maximum_scaled_penalty_per_length: u32,
     penalties: &Penalty,
 ) -> u32 {
+    let (gap_open_penalty, gap_extend_penalty) = penalties.lower_envelope_of_gap_penalties();
     u32::max(
-        penalties.o,
+        gap_open_penalty,
         (
             maximum_scaled_penalty_per_length * (
-                penalties.e * query_len - penalties.o
+                gap_extend_penalty * query_len - gap_open_penalty
             )
         ) / (
-            PREC_SCALE * penalties.e - maximum_scaled_penalty_per_length
+            PREC_SCALE * gap_extend_penalty - maximum_scaled_penalty_per_length
         ) + 1
     )
 }
@@ -0,0 +1,41 @@
+//! IUPAC nucleotide ambiguity codes
+//!  - Each code is the set of nucleotides (A, C, G, T) it represents. `U` is the same as `T`.
+//!  - Both uppercase and lowercase codes are defined. The other characters are the empty set.
+
+/// IUPAC codes in uppercase
+pub const IUPAC_CODES: &[u8] = b"ACGTURYSWKMBDHVN";
+
+const A: u8 = 0b0001;
+const C: u8 = 0b0010;
+const G: u8 = 0b0100;
+const T: u8 = 0b1000;
+
+const NUCLEOTIDES_OF_CODE: [u8; 256] = {
+    let mut table = [0; 256];
+    let codes: [(u8, u8); 16] = [
+        (b'A', A), (b'C', C), (b'G', G), (b'T', T), (b'U', T),
+        (b'R', A | G), (b'Y', C | T), (b'S', C | G), (b'W', A | T), (b'K', G | T), (b'M', A | C),
+        (b'B', C | G | T), (b'D', A | G | T), (b'H', A | C | T), (b'V', A | C | G),
+        (b'N', A | C | G | T),
+    ];
+    let mut index = 0;
+    while index < codes.len() {
+        let (code, nucleotides) = codes[index];
+        table[code as usize] = nucleotides;
+        table[code.to_ascii_lowercase() as usize] = nucleotides;
+        index += 1;
+    }
+    table
+};
+
+/// Two bases are compatible if they share at least one nucleotide (e.g., `R` and `A`).
+#[inline(always)]
+pub fn is_compatible(base_1: u8, base_2: u8) -> bool {
+    NUCLEOTIDES_OF_CODE[base_1 as usize] & NUCLEOTIDES_OF_CODE[base_2 as usize] != 0
+}
+
+/// Different bases that are compatible
+#[inline(always)]
+pub fn is_ambiguous_match(base_1: u8, base_2: u8) -> bool {
+    base_1 != base_2 && is_compatible(base_1, base_2)
+}
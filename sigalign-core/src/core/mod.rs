@@ -1,4 +1,10 @@
 pub mod regulators;
+pub mod stats;
+pub mod sequence_length;
+pub mod iupac;
+use std::cell::Cell;
+use std::sync::Arc;
+use stats::StatsCollector;
 
 /// `BufferedPatternLocator` represents types that can perform pattern searches within a buffered sequence.
 ///
@@ -9,9 +15,64 @@ pub trait BufferedPatternLocator {
     type Buffer: SequenceBuffer;
 
     fn locate(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> Vec<PatternLocation>;
+    /// `locate` that also reports the occurrences of the pattern to the `StatsCollector`.
+    ///  - If the occurrences are over the `occurrence_cap`, the pattern is dropped (returns `None`).
+    ///  - The locations are shared, so that they can be returned from a cache without copying.
+    #[inline]
+    fn locate_with_stats<C: StatsCollector>(
+        &self,
+        pattern: &[u8],
+        search_range: &SearchRange,
+        occurrence_cap: Option<u32>,
+        stats: &mut C,
+    ) -> Option<Arc<[PatternLocation]>> {
+        let pattern_locations = self.locate(pattern, search_range.sorted_target_indices());
+        if C::ENABLED || occurrence_cap.is_some() {
+            let occurrence_count: usize = pattern_locations.iter().map(|v| v.sorted_positions.len()).sum();
+            stats.add_pattern_occurrences(occurrence_count as u64);
+            if let Some(cap) = occurrence_cap {
+                if occurrence_count > cap as usize {
+                    stats.add_over_cap_pattern();
+                    return None
+                }
+            }
+        }
+        Some(pattern_locations.into())
+    }
     fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer);
 }
 
+/// Sorted indices of the targets to search, shared by all patterns of a query.
+///  - The `BufferedPatternLocator` can keep the key of the targets (e.g., to look up a cache),
+///    so that it is computed once per query, not for each pattern.
+pub struct SearchRange<'a> {
+    sorted_target_indices: &'a [u32],
+    key: Cell<Option<SearchRangeKey>>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SearchRangeKey {
+    pub hash: u64,
+    // ID of the verified entry, if the targets are compared with it.
+    pub verified_id: Option<u64>,
+}
+impl<'a> SearchRange<'a> {
+    pub fn new(sorted_target_indices: &'a [u32]) -> Self {
+        Self {
+            sorted_target_indices,
+            key: Cell::new(None),
+        }
+    }
+    pub fn sorted_target_indices(&self) -> &'a [u32] {
+        self.sorted_target_indices
+    }
+    pub(crate) fn get_key(&self) -> Option<SearchRangeKey> {
+        self.key.get()
+    }
+    pub(crate) fn set_key(&self, key: SearchRangeKey) {
+        self.key.set(Some(key))
+    }
+}
+
 pub trait SequenceBuffer {
     fn buffered_sequence(&self) -> &[u8];
 }
@@ -22,8 +83,9 @@ pub trait SequenceBuffer {
 /// these positions are automatically sorted when searching for an index within a target.
 /// **Note that the algorithm does not perform reordering**.
 /// 
-/// Each position's value is restricted to the bounds of a `u32`, limiting the range of each position.
-#[derive(Debug)]
+/// Each position is counted from the start of the target, and restricted to the bounds of a `u32`.
+/// The positions in the whole reference can be larger (see `SequenceLength`).
+#[derive(Debug, Clone)]
 pub struct PatternLocation {
     pub target_index: u32,
     pub sorted_positions: Vec<u32>,
@@ -3,9 +3,102 @@ pub const PREC_SCALE: u32 = 100_000; // Ensuring accuracy to the fourth decimal
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Penalty {
-    pub x: u32,
+    pub x: u32, // If substitution matrix is used, minimum of the mismatch penalties.
     pub o: u32,
     pub e: u32,
+    pub substitution_matrix: Option<SubstitutionMatrix>,
+    pub second_gap: Option<GapPenalty>, // Second piece of the two-piece affine gap penalty
+    pub quality_mismatch: Option<QualityMismatchPenalty>, // If used, `x` is the penalty of the lowest quality. Exclusive with `substitution_matrix`.
+    pub ambiguous_match: Option<u32>, // Penalty of the IUPAC ambiguous match. If positive, `substitution_matrix` has it.
+}
+
+/// Gap penalty of the second piece of the two-piece (convex) affine gap penalty.
+///  - The cost of a gap of length `L` is `min(o + e*L, second.o + second.e*L)`.
+///  - `o` is larger and `e` is smaller than those of the first piece.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GapPenalty {
+    pub o: u32,
+    pub e: u32,
+}
+
+/// Mismatch penalties for each pair of (target base, query base).
+///  - Penalty of the pair of the same base is always 0.
+///  - Pair including the base not in the alphabet has the maximum penalty of the matrix.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SubstitutionMatrix {
+    pub(crate) index_of_base: [u8; 256],
+    pub(crate) alphabet_size: usize,
+    pub(crate) penalties: Vec<u32>, // alphabet_size * alphabet_size (row: target, column: query)
+    pub(crate) penalty_for_undefined: u32,
+    pub(crate) distinct_penalties: Vec<u32>, // Sorted in ascending order
+}
+pub const UNDEFINED_BASE_INDEX: u8 = u8::MAX;
+
+/// Mismatch penalties by the quality of the query base.
+///  - The penalty increases linearly from the minimum (Phred score 0) to the maximum (Phred score `QUALITY_CAP` or higher).
+///  - The quality is encoded in Phred+33 (ASCII).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QualityMismatchPenalty {
+    pub(crate) index_of_quality: [u8; 256],
+    pub(crate) distinct_penalties: Vec<u32>, // Sorted in ascending order
+}
+/// Offset of the Phred+33 (ASCII) encoded qualities.
+pub const PHRED_OFFSET: u8 = 33;
+pub const QUALITY_CAP: u8 = 40;
+
+impl Penalty {
+    /// Mismatch penalty of the `Component` filled from M.
+    #[inline(always)]
+    pub fn mismatch_penalty_of_index(&self, mismatch_index: u8) -> u32 {
+        match (&self.substitution_matrix, &self.quality_mismatch) {
+            (Some(substitution_matrix), _) => substitution_matrix.distinct_penalties[mismatch_index as usize],
+            (None, Some(quality_mismatch)) => quality_mismatch.distinct_penalties[mismatch_index as usize],
+            (None, None) => self.x,
+        }
+    }
+    /// (gap-open, gap-extend) penalties of the first or second piece of the gap penalty.
+    #[inline(always)]
+    pub fn gap_penalties_of_piece(&self, is_second_piece: bool) -> (u32, u32) {
+        match (is_second_piece, &self.second_gap) {
+            (true, Some(second_gap)) => (second_gap.o, second_gap.e),
+            _ => (self.o, self.e),
+        }
+    }
+    /// (gap-open, gap-extend) penalties that never exceed the cost of any gap.
+    ///  - Without the second gap, same as (o, e).
+    ///  - Used in place of (o, e) for the bounds that must be kept for the exactness.
+    #[inline]
+    pub fn lower_envelope_of_gap_penalties(&self) -> (u32, u32) {
+        match &self.second_gap {
+            None => (self.o, self.e),
+            Some(second_gap) => (self.o.min(second_gap.o), self.e.min(second_gap.e)),
+        }
+    }
+}
+
+impl SubstitutionMatrix {
+    #[inline(always)]
+    pub fn penalty_of_pair(&self, target_base: u8, query_base: u8) -> u32 {
+        let target_index = self.index_of_base[target_base as usize];
+        let query_index = self.index_of_base[query_base as usize];
+        if target_index == UNDEFINED_BASE_INDEX || query_index == UNDEFINED_BASE_INDEX {
+            self.penalty_for_undefined
+        } else {
+            self.penalties[target_index as usize * self.alphabet_size + query_index as usize]
+        }
+    }
+}
+
+impl QualityMismatchPenalty {
+    /// Penalty of the mismatch at the query base of the quality.
+    ///  - Without the quality, the maximum penalty is used.
+    #[inline(always)]
+    pub fn penalty_of_quality(&self, quality: Option<u8>) -> u32 {
+        match quality {
+            Some(quality) => self.distinct_penalties[self.index_of_quality[quality as usize] as usize],
+            None => *self.distinct_penalties.last().unwrap(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -1,36 +1,45 @@
-use num_traits::PrimInt;
+use num::PrimInt;
 
-pub trait SeqLen:
+/// Integer type for the positions in the concatenated sequence of `Reference`.
+///  - `u32` for up to 4 Gbp, `u64` for the larger.
+pub trait SequenceLength:
     PrimInt
     + std::ops::AddAssign
     + std::ops::SubAssign
+    + std::fmt::Debug
+    + bytemuck::Pod
 {
     const ZERO: Self;
     const ONE: Self;
     fn as_u32(self) -> u32;
     fn from_u32(value: u32) -> Self;
     fn as_u64(self) -> u64;
+    fn from_u64(value: u64) -> Self;
     fn as_usize(self) -> usize;
     fn from_usize(value: usize) -> Self;
     fn as_i64(self) -> i64;
 }
 
-impl SeqLen for u32 {
+impl SequenceLength for u32 {
     const ZERO: Self = 0;
     const ONE: Self = 1;
     #[inline(always)]
     fn as_u32(self) -> u32 {
-        self as u32
+        self
     }
     #[inline(always)]
     fn from_u32(value: u32) -> Self {
-        value as Self
+        value
     }
     #[inline(always)]
     fn as_u64(self) -> u64 {
         self as u64
     }
     #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        value as Self
+    }
+    #[inline(always)]
     fn as_usize(self) -> usize {
         self as usize
     }
@@ -43,7 +52,7 @@ impl SeqLen for u32 {
         self as _
     }
 }
-impl SeqLen for u64 {
+impl SequenceLength for u64 {
     const ZERO: Self = 0;
     const ONE: Self = 1;
     #[inline(always)]
@@ -56,7 +65,11 @@ impl SeqLen for u64 {
     }
     #[inline(always)]
     fn as_u64(self) -> u64 {
-        self as u64
+        self
+    }
+    #[inline(always)]
+    fn from_u64(value: u64) -> Self {
+        value
     }
     #[inline(always)]
     fn as_usize(self) -> usize {
@@ -70,4 +83,4 @@ impl SeqLen for u64 {
     fn as_i64(self) -> i64 {
         self as _
     }
-}
\ No newline at end of file
+}
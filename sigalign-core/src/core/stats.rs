@@ -0,0 +1,134 @@
+use std::time::Instant;
+use crate::results::AlignmentStats;
+
+/// Collector of the `AlignmentStats` threaded through the algorithms.
+///  - `NoStats` is a no-op collector; all calls are removed at compile time.
+///  - Expensive counting has to be guarded by `ENABLED`.
+pub trait StatsCollector {
+    const ENABLED: bool;
+
+    #[inline(always)]
+    fn start_timer() -> Option<Instant> {
+        if Self::ENABLED {
+            Some(Instant::now())
+        } else {
+            None
+        }
+    }
+    fn add_pattern_count(&mut self, count: u64);
+    fn add_located_pattern(&mut self);
+    fn add_over_cap_pattern(&mut self);
+    fn add_pattern_occurrences(&mut self, count: u64);
+    fn add_pattern_cache_hit(&mut self);
+    fn add_pattern_cache_miss(&mut self);
+    fn add_anchors_before_merge(&mut self, count: u64);
+    fn add_anchors_after_merge(&mut self, count: u64);
+    fn add_extended_anchor(&mut self);
+    fn add_skipped_anchors(&mut self, count: u64);
+    fn add_filled_wave_front_cells(&mut self, count: u64);
+    fn add_pattern_locating_time(&mut self, timer: Option<Instant>);
+    fn add_anchor_merging_time(&mut self, timer: Option<Instant>);
+    fn add_extension_time(&mut self, timer: Option<Instant>);
+}
+
+pub struct NoStats;
+
+impl StatsCollector for NoStats {
+    const ENABLED: bool = false;
+
+    #[inline(always)]
+    fn add_pattern_count(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_located_pattern(&mut self) {}
+    #[inline(always)]
+    fn add_over_cap_pattern(&mut self) {}
+    #[inline(always)]
+    fn add_pattern_occurrences(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_pattern_cache_hit(&mut self) {}
+    #[inline(always)]
+    fn add_pattern_cache_miss(&mut self) {}
+    #[inline(always)]
+    fn add_anchors_before_merge(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_anchors_after_merge(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_extended_anchor(&mut self) {}
+    #[inline(always)]
+    fn add_skipped_anchors(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_filled_wave_front_cells(&mut self, _count: u64) {}
+    #[inline(always)]
+    fn add_pattern_locating_time(&mut self, _timer: Option<Instant>) {}
+    #[inline(always)]
+    fn add_anchor_merging_time(&mut self, _timer: Option<Instant>) {}
+    #[inline(always)]
+    fn add_extension_time(&mut self, _timer: Option<Instant>) {}
+}
+
+impl StatsCollector for AlignmentStats {
+    const ENABLED: bool = true;
+
+    #[inline]
+    fn add_pattern_count(&mut self, count: u64) {
+        self.pattern_count += count;
+    }
+    #[inline]
+    fn add_located_pattern(&mut self) {
+        self.located_pattern_count += 1;
+    }
+    #[inline]
+    fn add_over_cap_pattern(&mut self) {
+        self.over_cap_pattern_count += 1;
+    }
+    #[inline]
+    fn add_pattern_occurrences(&mut self, count: u64) {
+        self.pattern_occurrence_count += count;
+    }
+    #[inline]
+    fn add_pattern_cache_hit(&mut self) {
+        self.pattern_cache_hit_count += 1;
+    }
+    #[inline]
+    fn add_pattern_cache_miss(&mut self) {
+        self.pattern_cache_miss_count += 1;
+    }
+    #[inline]
+    fn add_anchors_before_merge(&mut self, count: u64) {
+        self.anchor_count_before_merge += count;
+    }
+    #[inline]
+    fn add_anchors_after_merge(&mut self, count: u64) {
+        self.anchor_count_after_merge += count;
+    }
+    #[inline]
+    fn add_extended_anchor(&mut self) {
+        self.extended_anchor_count += 1;
+    }
+    #[inline]
+    fn add_skipped_anchors(&mut self, count: u64) {
+        self.skipped_anchor_count += count;
+    }
+    #[inline]
+    fn add_filled_wave_front_cells(&mut self, count: u64) {
+        self.filled_wave_front_cell_count += count;
+    }
+    #[inline]
+    fn add_pattern_locating_time(&mut self, timer: Option<Instant>) {
+        if let Some(start) = timer {
+            self.pattern_locating_time += start.elapsed();
+        }
+    }
+    #[inline]
+    fn add_anchor_merging_time(&mut self, timer: Option<Instant>) {
+        if let Some(start) = timer {
+            self.anchor_merging_time += start.elapsed();
+        }
+    }
+    #[inline]
+    fn add_extension_time(&mut self, timer: Option<Instant>) {
+        if let Some(start) = timer {
+            self.extension_time += start.elapsed();
+        }
+    }
+}
@@ -2,8 +2,11 @@ use super::{
     Reference,
     PatternIndex,
     SequenceStorage,
+    MaskedRegions,
+    TargetMetadataStore,
 };
 use std::io::{Write, Read, Error};
+use std::sync::Arc;
 
 /// Save and load the structure
 pub trait Serialize {
@@ -23,21 +26,93 @@ impl<I, S> Serialize for Reference<I, S> where
         W: Write
     {
         self.target_boundaries.save_to(&mut writer)?;
+        write_padding(&mut writer, self.target_boundaries.to_be_saved_size())?;
         self.pattern_index.save_to(&mut writer)?;
         self.sequence_storage.save_to(&mut writer)?;
+        self.masked_regions.save_to(&mut writer)?;
+        self.target_metadata.save_to(&mut writer)?;
         Ok(())
     }
     fn load_from<R>(mut reader: R) -> Result<Self, Error> where
         R: Read,
         Self: Sized
     {
-        let target_boundaries = Vec::load_from(&mut reader)?;
+        let target_boundaries: Vec<I::Position> = Vec::load_from(&mut reader)?;
+        skip_padding(&mut reader, target_boundaries.to_be_saved_size())?;
         let pattern_index = I::load_from(&mut reader)?;
         let sequence_storage = S::load_from(&mut reader)?;
+        let masked_regions = MaskedRegions::load_from(&mut reader)?;
+        let target_metadata = TargetMetadataStore::load_from(&mut reader)?;
         Ok(Self {
             target_boundaries,
             pattern_index,
             sequence_storage,
+            masked_regions,
+            target_metadata,
+            pattern_location_cache: None,
+        })
+    }
+}
+
+/// Bytes shared by the structures loaded from them (e.g., a memory-mapped file).
+pub type SharedBytes = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
+/// Alignment (in bytes) of the arrays to be borrowed from the `SharedBytes`.
+///  - The pattern index is saved from the offset aligned to it, so that its arrays can be aligned.
+pub const SHARED_BYTES_ALIGNMENT: usize = 8;
+
+/// Size of the zero padding after the `size` bytes, to be aligned to `SHARED_BYTES_ALIGNMENT`.
+#[inline]
+pub fn padding_to_alignment(size: usize) -> usize {
+    (SHARED_BYTES_ALIGNMENT - size % SHARED_BYTES_ALIGNMENT) % SHARED_BYTES_ALIGNMENT
+}
+/// Write the zero padding after the `size` bytes.
+pub fn write_padding<W>(mut writer: W, size: usize) -> Result<(), Error> where
+    W: Write
+{
+    writer.write_all(&[0; SHARED_BYTES_ALIGNMENT][..padding_to_alignment(size)])
+}
+/// Skip the zero padding after the `size` bytes.
+pub fn skip_padding<R>(mut reader: R, size: usize) -> Result<(), Error> where
+    R: Read
+{
+    let mut padding = [0; SHARED_BYTES_ALIGNMENT];
+    reader.read_exact(&mut padding[..padding_to_alignment(size)])
+}
+
+/// Load the structure saved by `Serialize` from the shared bytes.
+///  - The structure can keep the `SharedBytes` to borrow its large parts instead of copying them.
+///  - The `offset` is the start of the structure, and moved to its end after loading.
+pub trait LoadShared {
+    fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized;
+}
+
+impl<I, S> LoadShared for Reference<I, S> where
+    I: PatternIndex + LoadShared,
+    S: SequenceStorage + LoadShared,
+{
+    fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized
+    {
+        let all_bytes: &[u8] = (**bytes).as_ref();
+        let mut reader = all_bytes.get(*offset..).ok_or(std::io::ErrorKind::UnexpectedEof)?;
+        let target_boundaries: Vec<I::Position> = Vec::load_from(&mut reader)?;
+        skip_padding(&mut reader, target_boundaries.to_be_saved_size())?;
+        *offset = all_bytes.len() - reader.len();
+        let pattern_index = I::load_shared(bytes, offset)?;
+        let sequence_storage = S::load_shared(bytes, offset)?;
+        let mut reader = all_bytes.get(*offset..).ok_or(std::io::ErrorKind::UnexpectedEof)?;
+        let masked_regions = MaskedRegions::load_from(&mut reader)?;
+        let target_metadata = TargetMetadataStore::load_from(&mut reader)?;
+        *offset = all_bytes.len() - reader.len();
+        Ok(Self {
+            target_boundaries,
+            pattern_index,
+            sequence_storage,
+            masked_regions,
+            target_metadata,
+            pattern_location_cache: None,
         })
     }
 }
@@ -52,8 +127,12 @@ impl<I, S> EstimateSize for Reference<I, S> where
     S: SequenceStorage + EstimateSize,
 {
     fn serialized_size(&self) -> usize {
-        (self.target_boundaries.len() * std::mem::size_of::<u32>())
+        let target_boundaries_size = self.target_boundaries.to_be_saved_size();
+        target_boundaries_size
+        + padding_to_alignment(target_boundaries_size)
         + self.sequence_storage.serialized_size()
         + self.pattern_index.serialized_size()
+        + self.masked_regions.serialized_size()
+        + self.target_metadata.serialized_size()
     }
 }
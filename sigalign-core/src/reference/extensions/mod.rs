@@ -7,12 +7,20 @@ use super::{
     Reference,
     PatternIndex,
     SequenceStorage,
+    MaskedRegions,
+    TargetMetadataStore,
 };
 
 mod io;
 pub use io::{
     Serialize,
     EstimateSize,
+    LoadShared,
+    SharedBytes,
+    SHARED_BYTES_ALIGNMENT,
+    padding_to_alignment,
+    write_padding,
+    skip_padding,
 };
 mod label;
 pub use label::LabelStorage;
@@ -0,0 +1,91 @@
+/// Regions of targets where the patterns are not located (e.g., soft-masked repeats).
+///  - The masked regions are still aligned when the alignment is extended over them.
+///  - Each region is a half-open interval `[start, end)` counted from the start of the target.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaskedRegions {
+    // Regions of `target_index` are `starts[offsets[target_index]..offsets[target_index+1]]`.
+    // Empty if nothing is masked.
+    offsets: Vec<u32>,
+    starts: Vec<u32>,
+    ends: Vec<u32>,
+}
+
+impl MaskedRegions {
+    /// Make `MaskedRegions` from the sorted and non-overlapping regions of each target.
+    pub fn new(regions_of_targets: Vec<Vec<(u32, u32)>>) -> Self {
+        if regions_of_targets.iter().all(|regions| regions.is_empty()) {
+            return Self::default()
+        }
+        let mut offsets = Vec::with_capacity(regions_of_targets.len() + 1);
+        let mut starts = Vec::new();
+        let mut ends = Vec::new();
+        offsets.push(0);
+        for regions in regions_of_targets {
+            for (start, end) in regions {
+                starts.push(start);
+                ends.push(end);
+            }
+            offsets.push(starts.len() as u32);
+        }
+        Self { offsets, starts, ends }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+    /// Get the masked regions of the target.
+    pub fn get_regions_of_target(&self, target_index: u32) -> Vec<(u32, u32)> {
+        let (starts, ends) = self.get_starts_and_ends(target_index);
+        starts.iter().zip(ends).map(|(start, end)| (*start, *end)).collect()
+    }
+    /// Check if `[start, end)` of the target overlaps with any masked region.
+    #[inline]
+    pub fn overlaps(&self, target_index: u32, start: u32, end: u32) -> bool {
+        let (starts, ends) = self.get_starts_and_ends(target_index);
+        // The first region ending after the `start`
+        let index = ends.partition_point(|&region_end| region_end <= start);
+        match starts.get(index) {
+            Some(&region_start) => region_start < end,
+            None => false,
+        }
+    }
+    #[inline]
+    fn get_starts_and_ends(&self, target_index: u32) -> (&[u32], &[u32]) {
+        match (self.offsets.get(target_index as usize), self.offsets.get(target_index as usize + 1)) {
+            (Some(&left), Some(&right)) => {
+                let range = left as usize..right as usize;
+                (&self.starts[range.clone()], &self.ends[range])
+            },
+            _ => (&[], &[]),
+        }
+    }
+}
+
+// Impl Extensions
+use super::extensions::{Serialize, EstimateSize};
+use capwriter::{Save, Load};
+impl Serialize for MaskedRegions {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        self.offsets.save_to(&mut writer)?;
+        self.starts.save_to(&mut writer)?;
+        self.ends.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let offsets = Vec::load_from(&mut reader)?;
+        let starts = Vec::load_from(&mut reader)?;
+        let ends = Vec::load_from(&mut reader)?;
+        Ok(Self { offsets, starts, ends })
+    }
+}
+impl EstimateSize for MaskedRegions {
+    fn serialized_size(&self) -> usize {
+        self.offsets.to_be_saved_size()
+        + self.starts.to_be_saved_size()
+        + self.ends.to_be_saved_size()
+    }
+}
@@ -108,14 +108,21 @@ let reference = Reference::<Lfi32B2V64, InMemoryStorage>::load_from(&buffer[..])
 // Internal components
 mod pattern_index;
 mod sequence_storage;
+mod masked_regions;
+mod target_metadata;
+mod pattern_location_cache;
 // Implementations
 mod pattern_locate; // Implements the `BufferedPatternLocater` trait.
 mod debug;
 // Extensions for additional features
 pub mod extensions;
 
-pub use pattern_index::PatternIndex;
+pub use pattern_index::{PatternIndex, SortedPositions};
+pub use crate::core::sequence_length::SequenceLength;
 pub use sequence_storage::SequenceStorage;
+pub use masked_regions::MaskedRegions;
+pub use target_metadata::{TargetMetadata, TargetMetadataStore};
+pub use pattern_location_cache::{PatternLocationCache, PatternLocationCacheStats};
 pub use crate::core::{PatternLocation, SequenceBuffer};
 
 /// A database for multiple target sequences.
@@ -124,9 +131,12 @@ pub struct Reference<I, S> where
     I: PatternIndex,
     S: SequenceStorage,
 {
-    target_boundaries: Vec<u32>,
+    target_boundaries: Vec<I::Position>,
     pattern_index: I,
     sequence_storage: S,
+    masked_regions: MaskedRegions,
+    target_metadata: TargetMetadataStore,
+    pattern_location_cache: Option<PatternLocationCache>,
 }
 
 impl<I, S> Reference<I, S> where
@@ -139,11 +149,16 @@ impl<I, S> Reference<I, S> where
     ) -> Result<Self, I::BuildError> {
         let (concatenated_sequence, target_boundaries) = sequence_storage.get_concatenated_sequence_with_boundaries_of_targets();
         let pattern_index = I::new(concatenated_sequence, pattern_index_option)?;
+        // The `PatternIndex` is built, so the boundaries fit in the `Position`.
+        let target_boundaries = target_boundaries.into_iter().map(I::Position::from_u64).collect();
 
         Ok(Self {
             target_boundaries,
             pattern_index,
             sequence_storage,
+            masked_regions: MaskedRegions::default(),
+            target_metadata: TargetMetadataStore::default(),
+            pattern_location_cache: None,
         })
     }
     pub fn get_sequence_storage(&self) -> &S {
@@ -152,4 +167,38 @@ impl<I, S> Reference<I, S> where
     pub fn get_pattern_index(&self) -> &I {
         &self.pattern_index
     }
+    /// Set the regions where the patterns are not located.
+    pub fn set_masked_regions(&mut self, masked_regions: MaskedRegions) {
+        self.masked_regions = masked_regions;
+        if let Some(cache) = &self.pattern_location_cache {
+            cache.clear();
+        }
+    }
+    pub fn get_masked_regions(&self) -> &MaskedRegions {
+        &self.masked_regions
+    }
+    /// Set the metadata of the targets (e.g., description of the FASTA header).
+    pub fn set_target_metadata(&mut self, target_metadata: TargetMetadataStore) {
+        self.target_metadata = target_metadata;
+    }
+    pub fn get_target_metadata(&self) -> &TargetMetadataStore {
+        &self.target_metadata
+    }
+    /// Get the metadata of the target. None if the target index is out of range.
+    ///  - The length is always filled, even if no metadata is set.
+    pub fn get_metadata(&self, target_index: u32) -> Option<TargetMetadata> {
+        let start = self.target_boundaries.get(target_index as usize)?;
+        let end = self.target_boundaries.get(target_index as usize + 1)?;
+        let length = (*end - *start).as_u64();
+        Some(self.target_metadata.get_metadata_of_target(target_index, length))
+    }
+    /// Set the cache of the located patterns shared by the queries.
+    ///  - `None` to disable the cache (default).
+    ///  - The cache is not saved with the `Reference`.
+    pub fn set_pattern_location_cache(&mut self, pattern_location_cache: Option<PatternLocationCache>) {
+        self.pattern_location_cache = pattern_location_cache;
+    }
+    pub fn get_pattern_location_cache(&self) -> Option<&PatternLocationCache> {
+        self.pattern_location_cache.as_ref()
+    }
 }
@@ -1,12 +1,41 @@
 /*!
 Provides the `PatternIndex` and its basic implementations.
 */
+use std::borrow::Cow;
+
+use crate::core::sequence_length::SequenceLength;
+
 pub trait PatternIndex: Sized {
     type Option;
     type BuildError: std::error::Error;
+    /// Type of the positions in the concatenated sequence.
+    ///  - `u32` can index the concatenated sequence shorter than `u32::MAX`.
+    type Position: SequenceLength;
 
     /// Create a new `PatternIndex` instance with the given concatenated sequence.
-    fn new(concatenated_sequence : Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError>;
+    ///  - The sequence is borrowed from the `SequenceStorage` keeping it as is.
+    ///    Call `into_owned` only if the index consumes the sequence, because it copies the borrowed one.
+    fn new(concatenated_sequence: Cow<'_, [u8]>, option: Self::Option) -> Result<Self, Self::BuildError>;
     /// Get sorted positions of the given pattern in concatenated sequence.
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32>;
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<Self::Position>;
+    /// Get sorted positions in the width that the index keeps them.
+    ///  - Override if the index of the wider `Position` can keep the positions in `u32`,
+    ///    so that they are not widened by copying.
+    ///  - By default, `get_sorted_positions` is used.
+    fn get_sorted_positions_by_width(&self, pattern: &[u8]) -> SortedPositions<Self::Position> {
+        SortedPositions::Position(self.get_sorted_positions(pattern))
+    }
+    /// Count the occurrences of the given pattern in concatenated sequence.
+    ///  - By default, the positions are located and counted. Override if the index can count without locating.
+    fn count(&self, pattern: &[u8]) -> u64 {
+        self.get_sorted_positions(pattern).len() as u64
+    }
+}
+
+/// Sorted positions located by the `PatternIndex`.
+pub enum SortedPositions<P: SequenceLength> {
+    /// In the `Position` of the `PatternIndex`.
+    Position(Vec<P>),
+    /// In `u32`, when the positions fit in it.
+    U32(Vec<u32>),
 }
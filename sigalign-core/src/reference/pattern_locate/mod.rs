@@ -1,9 +1,12 @@
 use ahash::AHashMap;
 
-use crate::core::{BufferedPatternLocator, PatternLocation};
+use std::sync::Arc;
+
+use crate::core::{BufferedPatternLocator, PatternLocation, SearchRange, stats::{StatsCollector, NoStats}, sequence_length::SequenceLength};
 use super::Reference;
-use super::pattern_index::PatternIndex;
+use super::pattern_index::{PatternIndex, SortedPositions};
 use super::sequence_storage::SequenceStorage;
+use super::pattern_location_cache::CachedLocations;
 
 impl<I, S> BufferedPatternLocator for Reference<I, S> where
     I: PatternIndex,
@@ -13,9 +16,79 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
 
     #[inline]
     fn locate(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> Vec<PatternLocation> {
-        let sorted_positions = self.pattern_index.get_sorted_positions(pattern);
-        // TODO: Applying cap is valuable?
+        if self.pattern_location_cache.is_none() {
+            return self.locate_in_targets(pattern, sorted_target_indices).0
+        }
+        match self.locate_with_stats(pattern, &SearchRange::new(sorted_target_indices), None, &mut NoStats) {
+            Some(pattern_locations) => pattern_locations.to_vec(),
+            None => Vec::new(),
+        }
+    }
+    #[inline]
+    fn locate_with_stats<C: StatsCollector>(
+        &self,
+        pattern: &[u8],
+        search_range: &SearchRange,
+        occurrence_cap: Option<u32>,
+        stats: &mut C,
+    ) -> Option<Arc<[PatternLocation]>> {
+        // The cap is applied to the occurrences in the whole reference, not only in the searched targets.
+        //  - Counted before locating, so the over-cap pattern is never located.
+        if let Some(cap) = occurrence_cap {
+            let occurrence_count = self.pattern_index.count(pattern);
+            if occurrence_count > cap as u64 {
+                stats.add_pattern_occurrences(occurrence_count);
+                stats.add_over_cap_pattern();
+                return None
+            }
+        }
+        let sorted_target_indices = search_range.sorted_target_indices();
+        let Some(cache) = &self.pattern_location_cache else {
+            let (pattern_locations, occurrence_count) = self.locate_in_targets(pattern, sorted_target_indices);
+            stats.add_pattern_occurrences(occurrence_count);
+            return Some(pattern_locations.into())
+        };
+        if let Some((pattern_locations, occurrence_count)) = cache.get(pattern, search_range) {
+            stats.add_pattern_cache_hit();
+            stats.add_pattern_occurrences(occurrence_count);
+            return Some(pattern_locations)
+        }
+        stats.add_pattern_cache_miss();
+        let (pattern_locations, occurrence_count) = self.locate_in_targets(pattern, sorted_target_indices);
+        stats.add_pattern_occurrences(occurrence_count);
+        let pattern_locations: Arc<[PatternLocation]> = pattern_locations.into();
+        cache.insert(pattern, search_range, CachedLocations {
+            occurrence_count,
+            pattern_locations: pattern_locations.clone(),
+        });
+        Some(pattern_locations)
+    }
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        self.sequence_storage.fill_buffer(target_index, buffer)
+    }
+}
+
+impl<I, S> Reference<I, S> where
+    I: PatternIndex,
+    S: SequenceStorage,
+{
+    /// Returns the locations in the targets and the occurrences in the whole reference.
+    fn locate_in_targets(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> (Vec<PatternLocation>, u64) {
+        match self.pattern_index.get_sorted_positions_by_width(pattern) {
+            SortedPositions::Position(sorted_positions) => self.locate_positions_in_targets(sorted_positions, pattern, sorted_target_indices),
+            SortedPositions::U32(sorted_positions) => self.locate_positions_in_targets(sorted_positions, pattern, sorted_target_indices),
+        }
+    }
+    //  - The positions and the boundaries are compared in `u64`, without copying the positions.
+    fn locate_positions_in_targets<P: SequenceLength>(
+        &self,
+        sorted_positions: Vec<P>,
+        pattern: &[u8],
+        sorted_target_indices: &[u32],
+    ) -> (Vec<PatternLocation>, u64) {
+        let occurrence_count = sorted_positions.len() as u64;
         let mut positions_by_target: AHashMap<u32, Vec<u32>> = AHashMap::new();
+        let pattern_length = pattern.len() as u64;
 
         let search_range_count = sorted_target_indices.len();
 
@@ -26,6 +99,7 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
         let mut index;
 
         for position in sorted_positions {
+            let position = position.as_u64();
             // reset
             right = search_range_count;
             left = mid;
@@ -35,15 +109,22 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
                 mid = left + size / 2;
                 index = sorted_target_indices[mid];
                 
-                let start = self.target_boundaries[index as usize];
-                let end = self.target_boundaries[(index + 1) as usize];
+                let start = self.target_boundaries[index as usize].as_u64();
+                let end = self.target_boundaries[(index + 1) as usize].as_u64();
 
                 if position >= end {
                     left = mid + 1;
                 } else if start > position {
                     right = mid;
-                } else if position + pattern.len() as u32 <= end {
-                    let ref_pos = position - start;
+                } else if position + pattern_length <= end {
+                    // The position in the target longer than `u32::MAX` cannot be represented.
+                    let Ok(ref_end) = u32::try_from(position + pattern_length - start) else {
+                        break;
+                    };
+                    let ref_pos = ref_end - pattern_length as u32;
+                    if self.masked_regions.overlaps(index, ref_pos, ref_end) {
+                        break;
+                    }
                     match positions_by_target.get_mut(&index) {
                         Some(v) => {
                             v.push(ref_pos);
@@ -61,26 +142,24 @@ impl<I, S> BufferedPatternLocator for Reference<I, S> where
             }
         }
 
-        positions_by_target.into_iter().map(|(target_index, positions)| {
+        let pattern_locations = positions_by_target.into_iter().map(|(target_index, positions)| {
             PatternLocation {
                 target_index,
                 sorted_positions: positions,
             }
-        }).collect()
-    }
-    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
-        self.sequence_storage.fill_buffer(target_index, buffer)
+        }).collect();
+        (pattern_locations, occurrence_count)
     }
-}
-
-impl<I, S> Reference<I, S> where
-    I: PatternIndex,
-    S: SequenceStorage,
-{
     #[inline]
     pub fn locate_pattern(&self, pattern: &[u8], sorted_target_indices: &[u32]) -> Vec<PatternLocation> {
         self.locate(pattern, sorted_target_indices)
     }
+    /// Count the occurrences of the pattern in the concatenated sequence of all targets.
+    ///  - The occurrences spanning two adjacent targets are also counted.
+    #[inline]
+    pub fn count_pattern(&self, pattern: &[u8]) -> u64 {
+        self.pattern_index.count(pattern)
+    }
     #[inline]
     pub fn get_sequence_buffer(&self) -> S::Buffer {
         self.sequence_storage.get_buffer()
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use ahash::{AHashMap, RandomState};
+
+use crate::core::{PatternLocation, SearchRange, SearchRangeKey};
+
+/// Size-bounded cache of the located patterns shared by all queries aligned to the `Reference`.
+///  - Useful when many queries share the same patterns (e.g., amplicon or targeted panels).
+///  - The entries are keyed by the pattern together with the searched targets.
+///    The searched targets are keyed by their hash, computed once per query.
+///  - The size is bounded by the bytes of the patterns, the locations, and the searched targets.
+///    When the cache is full, the oldest entry is evicted first, with its searched targets if no other entry uses them.
+///  - The cache never changes the results; it only skips re-locating the same pattern.
+#[derive(Debug)]
+pub struct PatternLocationCache {
+    capacity: usize,
+    entries: RwLock<CacheEntries>,
+    hit_count: AtomicU64,
+    miss_count: AtomicU64,
+}
+
+/// Hit and miss counts of the `PatternLocationCache`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PatternLocationCacheStats {
+    pub hit_count: u64,
+    pub miss_count: u64,
+    /// Number of the entries currently cached
+    pub entry_count: usize,
+    /// Bytes of the entries currently cached
+    pub size: usize,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    // Searched targets by their hash
+    search_ranges: AHashMap<u64, CachedSearchRange>,
+    // ID of the next search range. Never reused, so that the evicted one is not mistaken.
+    next_search_range_id: u64,
+    entry_count: usize,
+    size: usize,
+    // (hash and ID of the search range, pattern)
+    insertion_order: VecDeque<(u64, u64, Vec<u8>)>,
+}
+#[derive(Debug)]
+struct CachedSearchRange {
+    id: u64,
+    sorted_target_indices: Vec<u32>,
+    locations_by_pattern: AHashMap<Vec<u8>, CachedLocations>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CachedLocations {
+    /// Occurrences in the whole reference (before filtering by the targets)
+    pub occurrence_count: u64,
+    pub pattern_locations: Arc<[PatternLocation]>,
+}
+
+// Fixed seeds, so that the hash of the same targets is the same in every query.
+const SEARCH_RANGE_HASHER: RandomState = RandomState::with_seeds(
+    0x243f_6a88_85a3_08d3, 0x1319_8a2e_0370_7344, 0xa409_3822_299f_31d0, 0x082e_fa98_ec4e_6c89,
+);
+
+impl PatternLocationCache {
+    /// Make an empty cache holding at most `capacity` bytes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: RwLock::new(CacheEntries::default()),
+            hit_count: AtomicU64::new(0),
+            miss_count: AtomicU64::new(0),
+        }
+    }
+    /// Maximum bytes of the cached entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    pub fn get_stats(&self) -> PatternLocationCacheStats {
+        let entries = self.entries.read().unwrap();
+        PatternLocationCacheStats {
+            hit_count: self.hit_count.load(Ordering::Relaxed),
+            miss_count: self.miss_count.load(Ordering::Relaxed),
+            entry_count: entries.entry_count,
+            size: entries.size,
+        }
+    }
+    /// Remove all entries and reset the counts.
+    pub fn clear(&self) {
+        let mut entries = self.entries.write().unwrap();
+        // Keep the ID not to be reused.
+        let next_search_range_id = entries.next_search_range_id;
+        *entries = CacheEntries { next_search_range_id, ..Default::default() };
+        self.hit_count.store(0, Ordering::Relaxed);
+        self.miss_count.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns the shared locations and the occurrence count if cached.
+    pub(crate) fn get(
+        &self,
+        pattern: &[u8],
+        search_range: &SearchRange,
+    ) -> Option<(Arc<[PatternLocation]>, u64)> {
+        let entries = self.entries.read().unwrap();
+        let cached = entries.verified_search_range(search_range).and_then(|cached_search_range| {
+            cached_search_range.locations_by_pattern.get(pattern)
+        }).map(|cached| {
+            (cached.pattern_locations.clone(), cached.occurrence_count)
+        });
+        match cached {
+            Some(_) => self.hit_count.fetch_add(1, Ordering::Relaxed),
+            None => self.miss_count.fetch_add(1, Ordering::Relaxed),
+        };
+        cached
+    }
+    pub(crate) fn insert(
+        &self,
+        pattern: &[u8],
+        search_range: &SearchRange,
+        cached_locations: CachedLocations,
+    ) {
+        let entry_size = Self::size_of_entry(pattern, &cached_locations.pattern_locations);
+        let search_range_size = std::mem::size_of_val(search_range.sorted_target_indices());
+        if entry_size + search_range_size > self.capacity {
+            return
+        }
+        let mut entries = self.entries.write().unwrap();
+        let hash = search_range_hash(search_range);
+        match entries.verified_search_range(search_range) {
+            Some(cached_search_range) => {
+                // Inserted by the other thread in the meantime
+                if cached_search_range.locations_by_pattern.contains_key(pattern) {
+                    return
+                }
+            },
+            None => {
+                // Other targets of the same hash are cached (not replaced, because it is very rare).
+                if entries.search_ranges.contains_key(&hash) {
+                    return
+                }
+            },
+        }
+
+        // Evict
+        let mut required_size = entry_size;
+        if !entries.search_ranges.contains_key(&hash) {
+            required_size += search_range_size;
+        }
+        while entries.size + required_size > self.capacity {
+            let Some((oldest_hash, oldest_id, oldest_pattern)) = entries.insertion_order.pop_front() else {
+                break;
+            };
+            entries.remove_entry(oldest_hash, oldest_id, &oldest_pattern);
+            // The search range of the new entry can be evicted.
+            if oldest_hash == hash && !entries.search_ranges.contains_key(&hash) {
+                required_size = entry_size + search_range_size;
+            }
+        }
+
+        // Insert
+        let cached_search_range = match entries.search_ranges.get(&hash) {
+            Some(cached_search_range) => cached_search_range.id,
+            None => {
+                let id = entries.next_search_range_id;
+                entries.next_search_range_id += 1;
+                entries.search_ranges.insert(hash, CachedSearchRange {
+                    id,
+                    sorted_target_indices: search_range.sorted_target_indices().to_vec(),
+                    locations_by_pattern: AHashMap::new(),
+                });
+                entries.size += search_range_size;
+                id
+            },
+        };
+        search_range.set_key(SearchRangeKey { hash, verified_id: Some(cached_search_range) });
+        entries.search_ranges.get_mut(&hash).unwrap().locations_by_pattern.insert(pattern.to_vec(), cached_locations);
+        entries.insertion_order.push_back((hash, cached_search_range, pattern.to_vec()));
+        entries.entry_count += 1;
+        entries.size += entry_size;
+    }
+    fn size_of_entry(pattern: &[u8], pattern_locations: &[PatternLocation]) -> usize {
+        pattern.len()
+        + std::mem::size_of_val(pattern_locations)
+        + pattern_locations.iter().map(|pattern_location| {
+            std::mem::size_of_val(pattern_location.sorted_positions.as_slice())
+        }).sum::<usize>()
+    }
+}
+
+impl CacheEntries {
+    // The cached search range of the same targets.
+    //  - The targets are compared only at the first time in a query.
+    #[inline]
+    fn verified_search_range(&self, search_range: &SearchRange) -> Option<&CachedSearchRange> {
+        let hash = search_range_hash(search_range);
+        let cached_search_range = self.search_ranges.get(&hash)?;
+        if search_range.get_key().and_then(|key| key.verified_id) == Some(cached_search_range.id) {
+            return Some(cached_search_range)
+        }
+        if cached_search_range.sorted_target_indices.as_slice() == search_range.sorted_target_indices() {
+            search_range.set_key(SearchRangeKey { hash, verified_id: Some(cached_search_range.id) });
+            Some(cached_search_range)
+        } else {
+            None
+        }
+    }
+    fn remove_entry(&mut self, hash: u64, id: u64, pattern: &[u8]) {
+        let Some(cached_search_range) = self.search_ranges.get_mut(&hash) else {
+            return
+        };
+        if cached_search_range.id != id {
+            return
+        }
+        if let Some(cached_locations) = cached_search_range.locations_by_pattern.remove(pattern) {
+            self.entry_count -= 1;
+            self.size -= PatternLocationCache::size_of_entry(pattern, &cached_locations.pattern_locations);
+        }
+        if cached_search_range.locations_by_pattern.is_empty() {
+            let cached_search_range = self.search_ranges.remove(&hash).unwrap();
+            self.size -= std::mem::size_of_val(cached_search_range.sorted_target_indices.as_slice());
+        }
+    }
+}
+
+// Hash of the targets, computed once per query.
+#[inline]
+fn search_range_hash(search_range: &SearchRange) -> u64 {
+    if let Some(key) = search_range.get_key() {
+        return key.hash
+    }
+    let hash = SEARCH_RANGE_HASHER.hash_one(search_range.sorted_target_indices());
+    search_range.set_key(SearchRangeKey { hash, verified_id: None });
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached_locations(target_index: u32) -> CachedLocations {
+        CachedLocations {
+            occurrence_count: 1,
+            pattern_locations: vec![PatternLocation { target_index, sorted_positions: vec![0, 10, 20] }].into(),
+        }
+    }
+
+    #[test]
+    fn search_ranges_are_evicted_with_their_patterns() {
+        let cache = PatternLocationCache::new(1024);
+        for target_index in 0..1000 {
+            let sorted_target_indices = [target_index, target_index + 1];
+            let search_range = SearchRange::new(&sorted_target_indices);
+            cache.insert(b"ACGT", &search_range, cached_locations(target_index));
+            assert!(cache.get(b"ACGT", &search_range).is_some());
+
+            let stats = cache.get_stats();
+            assert!(stats.size <= cache.capacity());
+            let entries = cache.entries.read().unwrap();
+            assert_eq!(entries.insertion_order.len(), entries.entry_count);
+            assert!(entries.search_ranges.len() <= entries.entry_count);
+        }
+        // Too large to be cached
+        let sorted_target_indices: Vec<u32> = (0..1000).collect();
+        let search_range = SearchRange::new(&sorted_target_indices);
+        cache.insert(b"ACGT", &search_range, cached_locations(0));
+        assert!(cache.get(b"ACGT", &search_range).is_none());
+    }
+    #[test]
+    fn cached_locations_are_shared() {
+        let cache = PatternLocationCache::new(1024);
+        let sorted_target_indices = [0, 1, 2];
+        let search_range = SearchRange::new(&sorted_target_indices);
+        cache.insert(b"ACGT", &search_range, cached_locations(1));
+        let (first, _) = cache.get(b"ACGT", &search_range).unwrap();
+        // The other query searching the same targets
+        let (second, _) = cache.get(b"ACGT", &SearchRange::new(&[0, 1, 2])).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(cache.get(b"ACGT", &SearchRange::new(&[0, 1])).is_none());
+    }
+}
@@ -2,6 +2,8 @@
 Provides the `SequenceStorage` and its basic implementations.
 */
 
+use std::borrow::Cow;
+
 use super::SequenceBuffer;
 pub trait SequenceStorage {
     type Buffer: SequenceBuffer;
@@ -10,9 +12,11 @@ pub trait SequenceStorage {
     fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer);
     fn num_targets(&self) -> u32;
 
+    /// The boundaries are the positions in the concatenated sequence (`num_targets` + 1 items, starting with 0).
+    ///  - Override to borrow the concatenated sequence if the storage keeps it as is.
     fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
-        Vec<u8>,
-        Vec<u32>,
+        Cow<'_, [u8]>,
+        Vec<u64>,
     ) {
         let num_targets = self.num_targets();
         let mut boundaries = Vec::with_capacity(num_targets as usize + 1);
@@ -24,13 +28,13 @@ pub trait SequenceStorage {
         for target_index in 0..num_targets {
             self.fill_buffer(target_index, &mut buffer);
             let target_sequence = buffer.buffered_sequence();
-            accumulated_length += target_sequence.len() as u32;
+            accumulated_length += target_sequence.len() as u64;
             boundaries.push(accumulated_length);
             concatenated_sequence.extend_from_slice(target_sequence)
         }
 
         (
-            concatenated_sequence,
+            Cow::Owned(concatenated_sequence),
             boundaries,
         )
     }
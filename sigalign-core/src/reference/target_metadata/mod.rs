@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize as SerdeSerialize};
+
+/// Metadata of a target beyond its label.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(SerdeSerialize, Deserialize)]
+pub struct TargetMetadata {
+    /// Description of the FASTA header (after the ID). Empty if not kept.
+    #[cfg_attr(feature = "short_key", serde(rename = "desc"))]
+    pub description: String,
+    /// Length of the target sequence.
+    #[cfg_attr(feature = "short_key", serde(rename = "len"))]
+    pub length: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "short_key", serde(rename = "taxid"))]
+    pub taxonomy_id: Option<u64>,
+    /// Key and value pairs in the order of addition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "short_key", serde(rename = "attr"))]
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Store of the `TargetMetadata` of all targets in `Reference`.
+///  - The length of the target is not stored, but taken from the `Reference`.
+///  - Targets are pushed in the order of the target index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetMetadataStore {
+    descriptions: StringList,
+    // `NO_TAXONOMY_ID` if the target has no taxonomy ID.
+    taxonomy_ids: Vec<u64>,
+    // Attributes of `target_index` are `attribute_keys[attribute_offsets[target_index]..attribute_offsets[target_index+1]]`.
+    attribute_offsets: Vec<u32>,
+    attribute_keys: StringList,
+    attribute_values: StringList,
+}
+
+const NO_TAXONOMY_ID: u64 = u64::MAX;
+
+impl Default for TargetMetadataStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TargetMetadataStore {
+    pub fn new() -> Self {
+        Self {
+            descriptions: StringList::default(),
+            taxonomy_ids: Vec::new(),
+            attribute_offsets: vec![0],
+            attribute_keys: StringList::default(),
+            attribute_values: StringList::default(),
+        }
+    }
+    /// Add the metadata of the next target.
+    ///  - ⚠️ Panics if the `taxonomy_id` is `u64::MAX`.
+    pub fn push(
+        &mut self,
+        description: &str,
+        taxonomy_id: Option<u64>,
+        attributes: &[(String, String)],
+    ) {
+        assert_ne!(taxonomy_id, Some(NO_TAXONOMY_ID), "The taxonomy ID is too large.");
+        self.descriptions.push(description);
+        self.taxonomy_ids.push(taxonomy_id.unwrap_or(NO_TAXONOMY_ID));
+        for (key, value) in attributes {
+            self.attribute_keys.push(key);
+            self.attribute_values.push(value);
+        }
+        self.attribute_offsets.push(self.attribute_keys.len() as u32);
+    }
+    /// Number of targets of which the metadata is pushed.
+    pub fn num_targets(&self) -> u32 {
+        self.taxonomy_ids.len() as u32
+    }
+    pub fn is_empty(&self) -> bool {
+        self.taxonomy_ids.is_empty()
+    }
+    /// Get the metadata of the target with the `length`.
+    ///  - The target without pushed metadata has the empty metadata.
+    pub fn get_metadata_of_target(&self, target_index: u32, length: u64) -> TargetMetadata {
+        let target_index = target_index as usize;
+        if target_index >= self.taxonomy_ids.len() {
+            return TargetMetadata { length, ..Default::default() }
+        }
+        let taxonomy_id = match self.taxonomy_ids[target_index] {
+            NO_TAXONOMY_ID => None,
+            v => Some(v),
+        };
+        let attribute_range = self.attribute_offsets[target_index] as usize..self.attribute_offsets[target_index + 1] as usize;
+        let attributes = attribute_range.map(|attribute_index| (
+            self.attribute_keys.get(attribute_index).to_string(),
+            self.attribute_values.get(attribute_index).to_string(),
+        )).collect();
+        TargetMetadata {
+            description: self.descriptions.get(target_index).to_string(),
+            length,
+            taxonomy_id,
+            attributes,
+        }
+    }
+}
+
+// Strings concatenated in one buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StringList {
+    concatenated: String,
+    index: Vec<usize>,
+}
+impl Default for StringList {
+    fn default() -> Self {
+        Self {
+            concatenated: String::new(),
+            index: vec![0],
+        }
+    }
+}
+impl StringList {
+    fn push(&mut self, string: &str) {
+        self.concatenated.push_str(string);
+        self.index.push(self.concatenated.len());
+    }
+    fn get(&self, index: usize) -> &str {
+        &self.concatenated[self.index[index]..self.index[index + 1]]
+    }
+    fn len(&self) -> usize {
+        self.index.len() - 1
+    }
+}
+
+// Impl Extensions
+use super::extensions::{Serialize, EstimateSize};
+use capwriter::{Save, Load};
+impl Serialize for TargetMetadataStore {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        self.descriptions.save_to(&mut writer)?;
+        self.taxonomy_ids.save_to(&mut writer)?;
+        self.attribute_offsets.save_to(&mut writer)?;
+        self.attribute_keys.save_to(&mut writer)?;
+        self.attribute_values.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let descriptions = StringList::load_from(&mut reader)?;
+        let taxonomy_ids = Vec::load_from(&mut reader)?;
+        let attribute_offsets = Vec::load_from(&mut reader)?;
+        let attribute_keys = StringList::load_from(&mut reader)?;
+        let attribute_values = StringList::load_from(&mut reader)?;
+        let target_metadata_store = Self { descriptions, taxonomy_ids, attribute_offsets, attribute_keys, attribute_values };
+        if !target_metadata_store.is_valid() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "The target metadata is corrupted"));
+        }
+        Ok(target_metadata_store)
+    }
+}
+impl TargetMetadataStore {
+    // The lengths agree with each other, and the offsets are in bounds.
+    fn is_valid(&self) -> bool {
+        let num_targets = self.taxonomy_ids.len();
+        let num_attributes = self.attribute_keys.len();
+        self.descriptions.len() == num_targets
+        && self.attribute_values.len() == num_attributes
+        && self.attribute_offsets.len() == num_targets + 1
+        && self.attribute_offsets[0] == 0
+        && self.attribute_offsets.windows(2).all(|offsets| offsets[0] <= offsets[1])
+        && self.attribute_offsets[num_targets] as usize == num_attributes
+    }
+}
+impl EstimateSize for TargetMetadataStore {
+    fn serialized_size(&self) -> usize {
+        self.descriptions.serialized_size()
+        + self.taxonomy_ids.to_be_saved_size()
+        + self.attribute_offsets.to_be_saved_size()
+        + self.attribute_keys.serialized_size()
+        + self.attribute_values.serialized_size()
+    }
+}
+impl Serialize for StringList {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        self.concatenated.as_bytes().save_to(&mut writer)?;
+        self.index.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let concatenated = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(std::io::ErrorKind::InvalidData.into()),
+        };
+        let index = Vec::load_from(&mut reader)?;
+        let string_list = Self { concatenated, index };
+        if !string_list.is_valid() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "The list of strings is corrupted"));
+        }
+        Ok(string_list)
+    }
+}
+impl StringList {
+    // The index starts from zero, ends at the length, and never splits a character.
+    fn is_valid(&self) -> bool {
+        self.index.first() == Some(&0)
+        && self.index.last() == Some(&self.concatenated.len())
+        && self.index.windows(2).all(|index| index[0] <= index[1])
+        && self.index.iter().all(|index| self.concatenated.is_char_boundary(*index))
+    }
+}
+impl EstimateSize for StringList {
+    fn serialized_size(&self) -> usize {
+        self.concatenated.as_bytes().to_be_saved_size()
+        + self.index.to_be_saved_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_of_two_targets() -> TargetMetadataStore {
+        let mut store = TargetMetadataStore::new();
+        store.push("first", Some(9606), &[("key".to_string(), "value".to_string())]);
+        store.push("second", None, &[]);
+        store
+    }
+    fn reload(store: &TargetMetadataStore) -> Result<TargetMetadataStore, std::io::Error> {
+        let mut buffer = Vec::new();
+        store.save_to(&mut buffer).unwrap();
+        TargetMetadataStore::load_from(&buffer[..])
+    }
+
+    #[test]
+    fn test_valid_store_is_reloaded() {
+        let store = store_of_two_targets();
+        assert_eq!(reload(&store).unwrap(), store);
+    }
+    #[test]
+    fn test_corrupted_store_is_rejected() {
+        let mut store = store_of_two_targets();
+        store.taxonomy_ids.push(NO_TAXONOMY_ID);
+        assert_eq!(reload(&store).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let mut store = store_of_two_targets();
+        *store.attribute_offsets.last_mut().unwrap() = 2;
+        assert_eq!(reload(&store).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+        let mut store = store_of_two_targets();
+        store.descriptions.index[1] = 100;
+        assert_eq!(reload(&store).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,55 @@
+use super::{
+    AnchorAlignmentResult, AlignmentOperation, AlignmentOperations,
+};
+use crate::core::iupac::is_ambiguous_match;
+
+impl AnchorAlignmentResult {
+    /// Split the `Match` and `Subst` operations into `Match`, `AmbiguousMatch`, and `Subst`
+    /// by the IUPAC codes of the aligned bases.
+    ///  - `target` and `query` are the whole sequences that are aligned.
+    pub fn mark_ambiguous_matches(&mut self, target: &[u8], query: &[u8]) {
+        let mut query_index = self.position.query.0 as usize;
+        let mut target_index = self.position.target.0 as usize;
+        let mut operations: Vec<AlignmentOperations> = Vec::with_capacity(self.operations.len());
+        for AlignmentOperations { operation, count } in self.operations.iter() {
+            match operation {
+                AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => {
+                    for _ in 0..*count {
+                        let (target_base, query_base) = (target[target_index], query[query_index]);
+                        let operation = if target_base == query_base {
+                            AlignmentOperation::Match
+                        } else if is_ambiguous_match(target_base, query_base) {
+                            AlignmentOperation::AmbiguousMatch
+                        } else {
+                            AlignmentOperation::Subst
+                        };
+                        push_operation(&mut operations, operation, 1);
+                        query_index += 1;
+                        target_index += 1;
+                    }
+                },
+                AlignmentOperation::Deletion => {
+                    push_operation(&mut operations, AlignmentOperation::Deletion, *count);
+                    target_index += *count as usize;
+                },
+                AlignmentOperation::Insertion => {
+                    push_operation(&mut operations, AlignmentOperation::Insertion, *count);
+                    query_index += *count as usize;
+                },
+            }
+        }
+        self.operations = operations;
+    }
+}
+
+#[inline]
+fn push_operation(operations: &mut Vec<AlignmentOperations>, operation: AlignmentOperation, count: u32) {
+    match operations.last_mut() {
+        Some(last) if last.operation == operation => {
+            last.count += count;
+        },
+        _ => {
+            operations.push(AlignmentOperations { operation, count });
+        },
+    }
+}
@@ -11,6 +11,16 @@ impl AlignmentResult {
     pub fn count_alignments(&self) -> usize {
         self.0.iter().map(|x| x.count_alignments()).sum()
     }
+    /// Keep the first `limit` alignments in the order of the targets.
+    ///  - The targets left without alignments are removed.
+    pub fn truncate_alignments(&mut self, limit: usize) {
+        let mut remained = limit;
+        self.0.retain_mut(|target_result| {
+            target_result.alignments.truncate(remained);
+            remained -= target_result.alignments.len();
+            !target_result.alignments.is_empty()
+        });
+    }
 }
 impl TargetAlignmentResult {
     pub fn count_alignments(&self) -> usize {
@@ -21,6 +31,16 @@ impl LabeledAlignmentResult {
     pub fn count_alignments(&self) -> usize {
         self.0.iter().map(|x| x.count_alignments()).sum()
     }
+    /// Keep the first `limit` alignments in the order of the targets.
+    ///  - The targets left without alignments are removed.
+    pub fn truncate_alignments(&mut self, limit: usize) {
+        let mut remained = limit;
+        self.0.retain_mut(|target_result| {
+            target_result.alignments.truncate(remained);
+            remained -= target_result.alignments.len();
+            !target_result.alignments.is_empty()
+        });
+    }
 }
 impl LabeledTargetAlignmentResult {
     pub fn count_alignments(&self) -> usize {
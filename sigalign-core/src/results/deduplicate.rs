@@ -74,7 +74,7 @@ impl AnchorAlignmentResult {
         let mut paths = AHashSet::new();
         self.operations.iter().for_each(|operation| {
             match operation.operation {
-                AlignmentOperation::Match | AlignmentOperation::Subst => {
+                AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => {
                     for _ in 0..operation.count {
                         paths.insert((query_index, target_index));
                         query_index += 1;
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use crate::reference::{
-    Reference, PatternIndex, SequenceStorage,
+    Reference, PatternIndex, SequenceStorage, TargetMetadata,
     extensions::LabelStorage,
 };
 use super::{
@@ -24,6 +24,13 @@ pub struct LabeledTargetAlignmentResult {
     pub label: String,
     #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
     pub alignments: Vec<AnchorAlignmentResult>,
+    #[serde(default)]
+    #[cfg_attr(feature = "short_key", serde(rename = "tier"))]
+    pub cutoff_tier: u32,
+    /// Metadata of the target, only if it is requested when labeling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "short_key", serde(rename = "meta"))]
+    pub metadata: Option<TargetMetadata>,
 }
 
 impl AlignmentResult {
@@ -50,7 +57,9 @@ impl TargetAlignmentResult {
         LabeledTargetAlignmentResult {
             index: self.index,
             label: reference.label_of_target_unchecked(self.index),
-            alignments: self.alignments
+            alignments: self.alignments,
+            cutoff_tier: self.cutoff_tier,
+            metadata: None,
         }
     }
 }
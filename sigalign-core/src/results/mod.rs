@@ -16,6 +16,12 @@ pub struct TargetAlignmentResult {
     pub index: u32,
     #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
     pub alignments: Vec<AnchorAlignmentResult>,
+    /// Index of the cutoff that produced the alignments.
+    ///  - For chaining aligners, cutoffs are ordered from strict to lenient.
+    ///  - Always `0` for aligners with a single cutoff.
+    #[serde(default)]
+    #[cfg_attr(feature = "short_key", serde(rename = "tier"))]
+    pub cutoff_tier: u32,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -30,6 +36,11 @@ pub struct AnchorAlignmentResult {
     pub position: AlignmentPosition,
     #[cfg_attr(feature = "short_key", serde(rename = "ops"))]
     pub operations: Vec<AlignmentOperations>,
+    /// Strand of the query that is aligned.
+    ///  - Always `Forward` unless both strands are aligned.
+    #[serde(default)]
+    #[cfg_attr(feature = "short_key", serde(rename = "strd"))]
+    pub strand: Strand,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -62,11 +73,29 @@ pub enum AlignmentOperation {
     Deletion,
     #[cfg_attr(feature = "short_key", serde(rename = "I"))]
     Insertion,
+    /// Different bases sharing a nucleotide in the IUPAC codes (e.g., `R` and `A`).
+    ///  - Only with the IUPAC matching.
+    #[cfg_attr(feature = "short_key", serde(rename = "A"))]
+    AmbiguousMatch,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Serialize, Deserialize)]
+pub enum Strand {
+    #[default]
+    #[cfg_attr(feature = "short_key", serde(rename = "+"))]
+    Forward,
+    #[cfg_attr(feature = "short_key", serde(rename = "-"))]
+    Reverse,
 }
 
 pub mod labeled;
 mod to_json;
+mod stats;
+pub use stats::AlignmentStats;
 
 // Features
 mod count_alignments;
 mod deduplicate;
+mod strand;
+mod ambiguous_match;
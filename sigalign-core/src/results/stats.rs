@@ -0,0 +1,39 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Diagnostics of the alignment of queries.
+///  - Counts and times are accumulated over all queries aligned with the same `AlignmentStats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct AlignmentStats {
+    /// Number of patterns split from the queries
+    pub pattern_count: u64,
+    /// Number of patterns that have at least one occurrence in the searched targets
+    pub located_pattern_count: u64,
+    /// Number of patterns dropped because their occurrences are over the cap of the aligner
+    ///  - Always zero without the cap (see `AlignmentRegulator::set_pattern_occurrence_cap`).
+    pub over_cap_pattern_count: u64,
+    /// Total occurrences of the patterns returned by the pattern index (before filtering by targets)
+    pub pattern_occurrence_count: u64,
+    /// Number of patterns found in the cache of the reference
+    ///  - Both cache counts are zero without the cache (see `Reference::set_pattern_location_cache`).
+    pub pattern_cache_hit_count: u64,
+    /// Number of patterns not found in the cache of the reference
+    pub pattern_cache_miss_count: u64,
+    /// Number of anchors before merging the ungapped anchors
+    pub anchor_count_before_merge: u64,
+    /// Number of anchors after merging the ungapped anchors
+    pub anchor_count_after_merge: u64,
+    /// Number of anchors that are extended
+    pub extended_anchor_count: u64,
+    /// Number of anchors that are skipped because they are traversed by the other extensions
+    pub skipped_anchor_count: u64,
+    /// Number of cells filled in the wave fronts
+    pub filled_wave_front_cell_count: u64,
+    /// Time to locate the patterns
+    pub pattern_locating_time: Duration,
+    /// Time to merge the ungapped anchors
+    pub anchor_merging_time: Duration,
+    /// Time to extend the anchors and backtrace
+    pub extension_time: Duration,
+}
@@ -0,0 +1,58 @@
+use super::{
+    AlignmentResult,
+    TargetAlignmentResult,
+    AnchorAlignmentResult,
+    Strand,
+};
+
+impl AlignmentResult {
+    /// Set the strand of all alignments.
+    pub fn set_strand(&mut self, strand: Strand) {
+        self.0.iter_mut().for_each(|target_result| {
+            target_result.alignments.iter_mut().for_each(|alignment| {
+                alignment.strand = strand;
+            });
+        });
+    }
+    /// Flip the query orientation of the alignments of reverse strand.
+    ///  - `query_length` is the length of the whole query.
+    ///  - Applying twice restores the result.
+    pub fn flip_query_orientation_of_reverse_strand(&mut self, query_length: u32) {
+        self.0.iter_mut().for_each(|target_result| {
+            target_result.alignments.iter_mut().for_each(|alignment| {
+                if alignment.strand == Strand::Reverse {
+                    alignment.flip_query_orientation(query_length);
+                }
+            });
+        });
+    }
+    /// Merge the results of other alignment (e.g., of the other strand).
+    ///  - The alignments of the same target are collected into one `TargetAlignmentResult`.
+    ///  - If the cutoff tiers are different, the smaller (stricter) one is kept.
+    pub fn merge(&mut self, other: Self) {
+        for other_target_result in other.0 {
+            match self.0.iter_mut().find(|target_result| target_result.index == other_target_result.index) {
+                Some(target_result) => target_result.merge(other_target_result),
+                None => self.0.push(other_target_result),
+            }
+        }
+    }
+}
+
+impl TargetAlignmentResult {
+    fn merge(&mut self, other: Self) {
+        self.alignments.extend(other.alignments);
+        self.cutoff_tier = self.cutoff_tier.min(other.cutoff_tier);
+    }
+}
+
+impl AnchorAlignmentResult {
+    /// Convert the query position and operations to the reverse complementary query.
+    ///  - The target position is not changed.
+    ///  - The operations are reversed to follow the direction of the converted query.
+    pub fn flip_query_orientation(&mut self, query_length: u32) {
+        let (start, end) = self.position.query;
+        self.position.query = (query_length - end, query_length - start);
+        self.operations.reverse();
+    }
+}
@@ -2,4 +2,7 @@
 pub type EndianType = byteorder::LittleEndian;
 #[cfg(target_endian = "big")]
 pub type EndianType = byteorder::BigEndian;
-pub use byteorder::{ReadBytesExt, WriteBytesExt};
\ No newline at end of file
+pub use byteorder::{ReadBytesExt, WriteBytesExt};
+
+mod section;
+pub use section::Section;
@@ -0,0 +1,158 @@
+use std::io::{Read, Write, Error, ErrorKind};
+use std::ops::Deref;
+
+use bytemuck::Pod;
+use sigalign_core::reference::extensions::{
+    SharedBytes,
+    padding_to_alignment,
+    write_padding,
+    skip_padding,
+};
+use super::{EndianType, ReadBytesExt, WriteBytesExt};
+
+/// Array that can be borrowed from the `SharedBytes` (e.g., a memory-mapped file) without copying.
+///  - Saved as the number of the elements (`u64`), the elements, and the zero padding to `SHARED_BYTES_ALIGNMENT`.
+///    Therefore, the elements are aligned if the section starts at the aligned offset.
+///  - If the elements in the shared bytes are not aligned, they are copied.
+///  - Copied to `Owned` before modification.
+pub enum Section<T: Pod> {
+    Owned(Vec<T>),
+    Shared {
+        bytes: SharedBytes,
+        // Pointing into the `bytes`, which is kept alive and never modified.
+        pointer: *const T,
+        len: usize,
+    },
+}
+
+// SAFETY: The `Shared` only reads the `SharedBytes`, which is `Send` and `Sync`.
+unsafe impl<T: Pod + Send> Send for Section<T> {}
+unsafe impl<T: Pod + Sync> Sync for Section<T> {}
+
+impl<T: Pod> Section<T> {
+    pub fn new() -> Self {
+        Self::Owned(Vec::new())
+    }
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared { .. })
+    }
+    pub fn to_mut(&mut self) -> &mut Vec<T> {
+        if let Self::Shared { .. } = self {
+            *self = Self::Owned(self.to_vec());
+        }
+        match self {
+            Self::Owned(v) => v,
+            Self::Shared { .. } => unreachable!(),
+        }
+    }
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::Owned(v) => v,
+            Self::Shared { .. } => self.to_vec(),
+        }
+    }
+
+    pub fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write,
+    {
+        let bytes: &[u8] = bytemuck::cast_slice(self);
+        writer.write_u64::<EndianType>(self.len() as u64)?;
+        writer.write_all(bytes)?;
+        write_padding(&mut writer, bytes.len())?;
+        Ok(())
+    }
+    pub fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+    {
+        let len = reader.read_u64::<EndianType>()? as usize;
+        let mut elements = vec![T::zeroed(); len];
+        let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut elements);
+        reader.read_exact(bytes)?;
+        skip_padding(&mut reader, bytes.len())?;
+        Ok(Self::Owned(elements))
+    }
+    /// Load the section starting at the `offset` of the `bytes`, and move the `offset` to its end.
+    pub fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> {
+        let all_bytes: &[u8] = (**bytes).as_ref();
+        let mut reader = all_bytes.get(*offset..).ok_or(ErrorKind::UnexpectedEof)?;
+        let len = reader.read_u64::<EndianType>()? as usize;
+        let start = *offset + std::mem::size_of::<u64>();
+        let end = len.checked_mul(std::mem::size_of::<T>())
+            .and_then(|size| start.checked_add(size))
+            .filter(|end| *end <= all_bytes.len())
+            .ok_or(ErrorKind::UnexpectedEof)?;
+        let elements_bytes = &all_bytes[start..end];
+        let section = match bytemuck::try_cast_slice::<u8, T>(elements_bytes) {
+            Ok(elements) => Self::Shared {
+                bytes: bytes.clone(),
+                pointer: elements.as_ptr(),
+                len,
+            },
+            // Not aligned
+            Err(_) => {
+                let mut elements = vec![T::zeroed(); len];
+                bytemuck::cast_slice_mut::<T, u8>(&mut elements).copy_from_slice(elements_bytes);
+                Self::Owned(elements)
+            },
+        };
+        *offset = end + padding_to_alignment(end - start);
+        if *offset > all_bytes.len() {
+            return Err(ErrorKind::UnexpectedEof.into())
+        }
+        Ok(section)
+    }
+    pub fn serialized_size(&self) -> usize {
+        let size = std::mem::size_of_val::<[T]>(self);
+        std::mem::size_of::<u64>()
+        + size
+        + padding_to_alignment(size)
+    }
+}
+
+impl<T: Pod> Deref for Section<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(v) => v,
+            // SAFETY: The `pointer` is aligned and points to `len` elements in the `bytes` (checked at loading).
+            Self::Shared { pointer, len, .. } => unsafe { std::slice::from_raw_parts(*pointer, *len) },
+        }
+    }
+}
+
+impl<T: Pod> From<Vec<T>> for Section<T> {
+    fn from(value: Vec<T>) -> Self {
+        Self::Owned(value)
+    }
+}
+
+impl<T: Pod> Clone for Section<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Owned(v) => Self::Owned(v.clone()),
+            Self::Shared { bytes, pointer, len } => Self::Shared {
+                bytes: bytes.clone(),
+                pointer: *pointer,
+                len: *len,
+            },
+        }
+    }
+}
+
+impl<T: Pod + PartialEq> PartialEq for Section<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+impl<T: Pod + Eq> Eq for Section<T> {}
+
+impl<T: Pod> std::fmt::Debug for Section<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Section")
+            .field("length", &self.len())
+            .field("is_shared", &self.is_shared())
+            .finish()
+    }
+}
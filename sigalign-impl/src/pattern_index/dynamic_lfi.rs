@@ -1,19 +1,32 @@
+use std::borrow::Cow;
+
 use crate::utils::get_unique_characters_of_sequence;
 use super::lfi::{
     Lfi32B2V64,
     Lfi32B3V64,
     Lfi32B4V64,
     Lfi32B5V64,
+    Lfi64B2V64,
+    Lfi64B3V64,
+    Lfi64B4V64,
+    Lfi64B5V64,
     LfiOption,
 };
 pub use super::lfi::LfiBuildError; // Re-export
-use sigalign_core::reference::PatternIndex;
+use sigalign_core::reference::{PatternIndex, SortedPositions};
 
+/// `Lfi` with the block and the position type chosen by the sequence.
+///  - The block is chosen by the number of characters.
+///  - The 64-bit positions (`P64` variants) are used only if the sequence is longer than `u32::MAX`.
 pub enum DynamicLfi {
     B2(Lfi32B2V64),
     B3(Lfi32B3V64),
     B4(Lfi32B4V64),
     B5(Lfi32B5V64),
+    B2P64(Lfi64B2V64),
+    B3P64(Lfi64B3V64),
+    B4P64(Lfi64B4V64),
+    B5P64(Lfi64B5V64),
 }
 #[derive(Debug, Clone)]
 pub struct DynamicLfiOption {
@@ -29,14 +42,33 @@ impl DynamicLfiOption {
             use_safe_guard: self.use_safe_guard,
         }
     }
+    /// Estimate the peak memory (in bytes) to build the index of the sequence of `sequence_length`.
+    ///  - The copy of the sequence is counted, since it is transformed in place.
+    ///  - The lookup table is counted by its maximum size.
+    pub fn estimate_peak_build_memory(&self, sequence_length: u64) -> u64 {
+        BUILD_BYTES_PER_BASE * sequence_length
+        + BUILD_FIXED_BYTES
+        + self.lookup_table_max_bytes_size
+    }
 }
 
+// Bytes per base to build the FM-index, depending on the suffix array sorting of `lt-fm-index`.
+//  - `libdivsufsort` (not on wasm32, see `Cargo.toml`): the sequence and the 64-bit suffix array cloned for the BWT (1 + 8 + 8).
+//  - crate `bio` (wasm32): measured up to 51 bytes per base with a counting allocator, and the sequence.
+#[cfg(not(target_arch = "wasm32"))]
+const BUILD_BYTES_PER_BASE: u64 = 17;
+#[cfg(target_arch = "wasm32")]
+const BUILD_BYTES_PER_BASE: u64 = 53;
+// Bucket arrays of `libdivsufsort` and the other working memory not growing with the sequence.
+const BUILD_FIXED_BYTES: u64 = 1 << 20;
+
 impl PatternIndex for DynamicLfi {
     type Option = DynamicLfiOption;
     type BuildError = LfiBuildError;
+    type Position = u64;
 
     fn new(
-        concatenated_sequence: Vec<u8>,
+        concatenated_sequence: Cow<'_, [u8]>,
         option: Self::Option,
     ) -> Result<Self, Self::BuildError> {
         let lfi_option = option.to_lfi_option();
@@ -49,7 +81,26 @@ impl PatternIndex for DynamicLfi {
             }
         };
 
-        if chr_count <= 3 {
+        if chr_count > 31 {
+            return Err(Self::BuildError::OverMaximumCharacters { max: 31, input: chr_count as u32 })
+        }
+        let use_64_bit_position = concatenated_sequence.len() >= u32::MAX as usize;
+
+        if use_64_bit_position {
+            if chr_count <= 3 {
+                let inner = Lfi64B2V64::new(concatenated_sequence, lfi_option)?;
+                Ok(Self::B2P64(inner))
+            } else if chr_count <= 7 {
+                let inner = Lfi64B3V64::new(concatenated_sequence, lfi_option)?;
+                Ok(Self::B3P64(inner))
+            } else if chr_count <= 15 {
+                let inner = Lfi64B4V64::new(concatenated_sequence, lfi_option)?;
+                Ok(Self::B4P64(inner))
+            } else {
+                let inner = Lfi64B5V64::new(concatenated_sequence, lfi_option)?;
+                Ok(Self::B5P64(inner))
+            }
+        } else if chr_count <= 3 {
             let inner = Lfi32B2V64::new(concatenated_sequence, lfi_option)?;
             Ok(Self::B2(inner))
         } else if chr_count <= 7 {
@@ -58,23 +109,55 @@ impl PatternIndex for DynamicLfi {
         } else if chr_count <= 15 {
             let inner = Lfi32B4V64::new(concatenated_sequence, lfi_option)?;
             Ok(Self::B4(inner))
-        } else if chr_count <= 31 {
+        } else {
             let inner = Lfi32B5V64::new(concatenated_sequence, lfi_option)?;
             Ok(Self::B5(inner))
-        } else {
-            Err(Self::BuildError::OverMaximumCharacters { max: 31, input: chr_count as u32 })
         }
     }
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+    /// The positions of the index for `u32` are widened by copying. Use `get_sorted_positions_by_width` to avoid it.
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u64> {
         match self {
-            Self::B2(v) => v.get_sorted_positions(pattern),
-            Self::B3(v) => v.get_sorted_positions(pattern),
-            Self::B4(v) => v.get_sorted_positions(pattern),
-            Self::B5(v) => v.get_sorted_positions(pattern),
+            Self::B2(v) => to_u64_positions(v.get_sorted_positions(pattern)),
+            Self::B3(v) => to_u64_positions(v.get_sorted_positions(pattern)),
+            Self::B4(v) => to_u64_positions(v.get_sorted_positions(pattern)),
+            Self::B5(v) => to_u64_positions(v.get_sorted_positions(pattern)),
+            Self::B2P64(v) => v.get_sorted_positions(pattern),
+            Self::B3P64(v) => v.get_sorted_positions(pattern),
+            Self::B4P64(v) => v.get_sorted_positions(pattern),
+            Self::B5P64(v) => v.get_sorted_positions(pattern),
+        }
+    }
+    fn get_sorted_positions_by_width(&self, pattern: &[u8]) -> SortedPositions<u64> {
+        match self {
+            Self::B2(v) => SortedPositions::U32(v.get_sorted_positions(pattern)),
+            Self::B3(v) => SortedPositions::U32(v.get_sorted_positions(pattern)),
+            Self::B4(v) => SortedPositions::U32(v.get_sorted_positions(pattern)),
+            Self::B5(v) => SortedPositions::U32(v.get_sorted_positions(pattern)),
+            Self::B2P64(v) => SortedPositions::Position(v.get_sorted_positions(pattern)),
+            Self::B3P64(v) => SortedPositions::Position(v.get_sorted_positions(pattern)),
+            Self::B4P64(v) => SortedPositions::Position(v.get_sorted_positions(pattern)),
+            Self::B5P64(v) => SortedPositions::Position(v.get_sorted_positions(pattern)),
+        }
+    }
+    fn count(&self, pattern: &[u8]) -> u64 {
+        match self {
+            Self::B2(v) => v.count(pattern),
+            Self::B3(v) => v.count(pattern),
+            Self::B4(v) => v.count(pattern),
+            Self::B5(v) => v.count(pattern),
+            Self::B2P64(v) => v.count(pattern),
+            Self::B3P64(v) => v.count(pattern),
+            Self::B4P64(v) => v.count(pattern),
+            Self::B5P64(v) => v.count(pattern),
         }
     }
 }
 
+#[inline]
+fn to_u64_positions(positions: Vec<u32>) -> Vec<u64> {
+    positions.into_iter().map(|x| x as u64).collect()
+}
+
 // Impl Extensions
 use sigalign_core::reference::extensions::{
     Serialize,
@@ -92,21 +175,41 @@ impl Serialize for DynamicLfi {
                 v.save_to(&mut writer)?;
                 Ok(())
             },
+            Self::B2P64(v) => {
+                writer.write_u64::<EndianType>(Self::B2P64_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
             Self::B3(v) => {
                 writer.write_u64::<EndianType>(Self::B3_MAGIC_NUMBER)?;
                 v.save_to(&mut writer)?;
                 Ok(())
             },
+            Self::B3P64(v) => {
+                writer.write_u64::<EndianType>(Self::B3P64_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
             Self::B4(v) => {
                 writer.write_u64::<EndianType>(Self::B4_MAGIC_NUMBER)?;
                 v.save_to(&mut writer)?;
                 Ok(())
             },
+            Self::B4P64(v) => {
+                writer.write_u64::<EndianType>(Self::B4P64_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
             Self::B5(v) => {
                 writer.write_u64::<EndianType>(Self::B5_MAGIC_NUMBER)?;
                 v.save_to(&mut writer)?;
                 Ok(())
             },
+            Self::B5P64(v) => {
+                writer.write_u64::<EndianType>(Self::B5P64_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
         }
     }
     fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
@@ -114,23 +217,47 @@ impl Serialize for DynamicLfi {
         Self: Sized,
     {
         let magic_number = reader.read_u64::<EndianType>()?;
+        Self::load_with_magic_number(magic_number, reader)
+    }
+}
+impl DynamicLfi {
+    /// `load_from` after the magic number is read.
+    pub(crate) fn load_with_magic_number<R>(magic_number: u64, mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+    {
         match magic_number {
             Self::B2_MAGIC_NUMBER => {
                 let inner = Lfi32B2V64::load_from(&mut reader)?;
                 Ok(Self::B2(inner))
             },
+            Self::B2P64_MAGIC_NUMBER => {
+                let inner = Lfi64B2V64::load_from(&mut reader)?;
+                Ok(Self::B2P64(inner))
+            },
             Self::B3_MAGIC_NUMBER => {
                 let inner = Lfi32B3V64::load_from(&mut reader)?;
                 Ok(Self::B3(inner))
             },
+            Self::B3P64_MAGIC_NUMBER => {
+                let inner = Lfi64B3V64::load_from(&mut reader)?;
+                Ok(Self::B3P64(inner))
+            },
             Self::B4_MAGIC_NUMBER => {
                 let inner = Lfi32B4V64::load_from(&mut reader)?;
                 Ok(Self::B4(inner))
             },
+            Self::B4P64_MAGIC_NUMBER => {
+                let inner = Lfi64B4V64::load_from(&mut reader)?;
+                Ok(Self::B4P64(inner))
+            },
             Self::B5_MAGIC_NUMBER => {
                 let inner = Lfi32B5V64::load_from(&mut reader)?;
                 Ok(Self::B5(inner))
             },
+            Self::B5P64_MAGIC_NUMBER => {
+                let inner = Lfi64B5V64::load_from(&mut reader)?;
+                Ok(Self::B5P64(inner))
+            },
             _ => {
                 Err((std::io::ErrorKind::InvalidData).into())
             },
@@ -147,6 +274,14 @@ impl DynamicLfi {
     const B4_MAGIC_NUMBER: u64 = 1848733752;
     // LtFmIndexPosition32Block5Vector64: 6a2427ab
     const B5_MAGIC_NUMBER: u64 = 1780754347;
+    // LtFmIndexPosition64Block2Vector64: 1c1d8f89
+    const B2P64_MAGIC_NUMBER: u64 = 471699337;
+    // LtFmIndexPosition64Block3Vector64: 98c3c36e
+    const B3P64_MAGIC_NUMBER: u64 = 2562966382;
+    // LtFmIndexPosition64Block4Vector64: 19aa151f
+    const B4P64_MAGIC_NUMBER: u64 = 430576927;
+    // LtFmIndexPosition64Block5Vector64: 38026e9c
+    const B5P64_MAGIC_NUMBER: u64 = 939683484;
 }
 //  - EstimateSize
 impl EstimateSize for DynamicLfi {
@@ -154,9 +289,13 @@ impl EstimateSize for DynamicLfi {
         std::mem::size_of::<u64>()
         + match self {
             Self::B2(v) => v.serialized_size(),
+            Self::B2P64(v) => v.serialized_size(),
             Self::B3(v) => v.serialized_size(),
+            Self::B3P64(v) => v.serialized_size(),
             Self::B4(v) => v.serialized_size(),
+            Self::B4P64(v) => v.serialized_size(),
             Self::B5(v) => v.serialized_size(),
+            Self::B5P64(v) => v.serialized_size(),
         }
     }
 }
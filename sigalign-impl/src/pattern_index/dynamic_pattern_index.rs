@@ -0,0 +1,164 @@
+use std::borrow::Cow;
+
+use thiserror::Error;
+
+use super::dynamic_lfi::{
+    DynamicLfi,
+    DynamicLfiOption,
+    LfiBuildError,
+};
+use super::kmer_index::{
+    KmerIndex,
+    KmerIndexOption,
+    KmerIndexBuildError,
+};
+use sigalign_core::reference::{PatternIndex, SortedPositions};
+
+/// `PatternIndex` that is either the FM-index (`DynamicLfi`) or the hash table of k-mers (`KmerIndex`).
+pub enum DynamicPatternIndex {
+    Lfi(Box<DynamicLfi>),
+    Kmer(KmerIndex),
+}
+#[derive(Debug, Clone)]
+pub enum DynamicPatternIndexOption {
+    Lfi(DynamicLfiOption),
+    Kmer(KmerIndexOption),
+}
+impl DynamicPatternIndexOption {
+    /// Estimate the peak memory (in bytes) to build the index of the sequence of `sequence_length`.
+    pub fn estimate_peak_build_memory(&self, sequence_length: u64) -> u64 {
+        match self {
+            Self::Lfi(option) => option.estimate_peak_build_memory(sequence_length),
+            Self::Kmer(option) => option.estimate_peak_build_memory(sequence_length),
+        }
+    }
+}
+impl DynamicPatternIndex {
+    /// Bytes of the built index in memory.
+    ///  - The FM-index keeps the arrays as they are saved, so the saved size is used.
+    pub fn size_in_memory(&self) -> usize {
+        match self {
+            Self::Lfi(v) => v.serialized_size(),
+            Self::Kmer(v) => v.size_in_memory(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PatternIndexBuildError {
+    #[error(transparent)]
+    Lfi(#[from] LfiBuildError),
+    #[error(transparent)]
+    Kmer(#[from] KmerIndexBuildError),
+}
+
+impl PatternIndex for DynamicPatternIndex {
+    type Option = DynamicPatternIndexOption;
+    type BuildError = PatternIndexBuildError;
+    type Position = u64;
+
+    fn new(
+        concatenated_sequence: Cow<'_, [u8]>,
+        option: Self::Option,
+    ) -> Result<Self, Self::BuildError> {
+        match option {
+            DynamicPatternIndexOption::Lfi(option) => {
+                let inner = DynamicLfi::new(concatenated_sequence, option)?;
+                Ok(Self::Lfi(Box::new(inner)))
+            },
+            DynamicPatternIndexOption::Kmer(option) => {
+                let inner = KmerIndex::new(concatenated_sequence, option)?;
+                Ok(Self::Kmer(inner))
+            },
+        }
+    }
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u64> {
+        match self {
+            Self::Lfi(v) => v.get_sorted_positions(pattern),
+            Self::Kmer(v) => v.get_sorted_positions(pattern).into_iter().map(|x| x as u64).collect(),
+        }
+    }
+    fn get_sorted_positions_by_width(&self, pattern: &[u8]) -> SortedPositions<u64> {
+        match self {
+            Self::Lfi(v) => v.get_sorted_positions_by_width(pattern),
+            Self::Kmer(v) => SortedPositions::U32(v.get_sorted_positions(pattern)),
+        }
+    }
+    fn count(&self, pattern: &[u8]) -> u64 {
+        match self {
+            Self::Lfi(v) => v.count(pattern),
+            Self::Kmer(v) => v.count(pattern),
+        }
+    }
+}
+
+// Impl Extensions
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LoadShared,
+    SharedBytes,
+};
+//  - Serialize
+use crate::core::{EndianType, WriteBytesExt, ReadBytesExt};
+impl Serialize for DynamicPatternIndex {
+    // `DynamicLfi` starts with its own magic number, so it is saved as it is.
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        match self {
+            Self::Lfi(v) => {
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
+            Self::Kmer(v) => {
+                writer.write_u64::<EndianType>(Self::KMER_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)?;
+                Ok(())
+            },
+        }
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized,
+    {
+        let magic_number = reader.read_u64::<EndianType>()?;
+        match magic_number {
+            Self::KMER_MAGIC_NUMBER => {
+                let inner = KmerIndex::load_from(&mut reader)?;
+                Ok(Self::Kmer(inner))
+            },
+            _ => {
+                let inner = DynamicLfi::load_with_magic_number(magic_number, &mut reader)?;
+                Ok(Self::Lfi(Box::new(inner)))
+            },
+        }
+    }
+}
+//  - LoadShared
+//    The index is copied to the memory, since neither the FM-index nor the hash table can borrow the bytes.
+impl LoadShared for DynamicPatternIndex {
+    fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, std::io::Error> where
+        Self: Sized,
+    {
+        let all_bytes: &[u8] = (**bytes).as_ref();
+        let mut reader = all_bytes.get(*offset..).ok_or(std::io::ErrorKind::UnexpectedEof)?;
+        let pattern_index = Self::load_from(&mut reader)?;
+        *offset = all_bytes.len() - reader.len();
+        Ok(pattern_index)
+    }
+}
+impl DynamicPatternIndex {
+    // MAGIC NUMBERS: FNV1A32 hash value of
+    // KmerIndexPosition32: f69049d0
+    const KMER_MAGIC_NUMBER: u64 = 4136651216;
+}
+//  - EstimateSize
+impl EstimateSize for DynamicPatternIndex {
+    fn serialized_size(&self) -> usize {
+        match self {
+            Self::Lfi(v) => v.serialized_size(),
+            Self::Kmer(v) => std::mem::size_of::<u64>() + v.serialized_size(),
+        }
+    }
+}
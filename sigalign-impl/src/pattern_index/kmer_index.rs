@@ -0,0 +1,318 @@
+use std::borrow::Cow;
+
+use thiserror::Error;
+use ahash::AHashMap;
+
+use crate::utils::get_unique_characters_of_sequence;
+use sigalign_core::reference::PatternIndex;
+
+/// `PatternIndex` storing the positions of every k-mer in a hash table.
+///  - Much faster to build than the FM-index, but uses more memory (a position per base).
+///  - The sequence is not kept. The pattern is split into the k-mers covering it,
+///    and located where all of them are found at their offsets.
+///  - The pattern shorter than the k-mer size is located by the range of the k-mers starting with it.
+pub struct KmerIndex {
+    kmer_size: u32,
+    bits_per_character: u32,
+    code_of_character: Vec<u16>, // 256 items. 0 for the character not in the sequence.
+    sequence_length: u32,
+    // Last `kmer_size - 1` characters of the sequence, where no k-mer starts.
+    tail_of_sequence: Vec<u8>,
+    // Sorted layout: positions of `sorted_kmers[i]` are `positions[offsets[i]..offsets[i+1]]`
+    sorted_kmers: Vec<u64>,
+    offsets: Vec<u32>,
+    positions: Vec<u32>,
+    // Not saved (rebuilt from `sorted_kmers`)
+    index_of_kmer: AHashMap<u64, u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KmerIndexOption {
+    pub kmer_size: u32,
+}
+impl KmerIndexOption {
+    pub fn new(kmer_size: u32) -> Self {
+        Self { kmer_size }
+    }
+    /// Estimate the peak memory (in bytes) to build the index of the sequence of `sequence_length`.
+    ///  - The sequence is not counted, since it is borrowed from the storage.
+    ///  - Measured up to 92 bytes per base with a counting allocator, when all k-mers are distinct.
+    ///    The hash tables of the k-mers are the most of it, and are less than half full at worst.
+    pub fn estimate_peak_build_memory(&self, sequence_length: u64) -> u64 {
+        100 * sequence_length + (1 << 20)
+    }
+}
+
+impl PatternIndex for KmerIndex {
+    type Option = KmerIndexOption;
+    type BuildError = KmerIndexBuildError;
+    type Position = u32;
+
+    fn new(concatenated_sequence: Cow<'_, [u8]>, option: Self::Option) -> Result<Self, Self::BuildError> {
+        let sequence_length = concatenated_sequence.len();
+        if sequence_length >= u32::MAX as usize {
+            return Err(Self::BuildError::SequenceLengthOver(u32::MAX as u64));
+        }
+
+        let unique_characters = get_unique_characters_of_sequence(&concatenated_sequence);
+        let mut code_of_character = vec![0; 256];
+        for (index, character) in unique_characters.iter().enumerate() {
+            code_of_character[*character as usize] = index as u16 + 1;
+        }
+        let bits_per_character = u32::max(1, u32::BITS - (unique_characters.len() as u32).leading_zeros());
+        let max_kmer_size = u64::BITS / bits_per_character;
+        if option.kmer_size == 0 || option.kmer_size > max_kmer_size {
+            return Err(Self::BuildError::InvalidKmerSize {
+                max: max_kmer_size,
+                input: option.kmer_size,
+            });
+        }
+
+        let tail_length = sequence_length.min(option.kmer_size as usize - 1);
+        let mut kmer_index = Self {
+            kmer_size: option.kmer_size,
+            bits_per_character,
+            code_of_character,
+            sequence_length: sequence_length as u32,
+            tail_of_sequence: concatenated_sequence[sequence_length - tail_length..].to_vec(),
+            sorted_kmers: Vec::new(),
+            offsets: Vec::new(),
+            positions: Vec::new(),
+            index_of_kmer: AHashMap::new(),
+        };
+        kmer_index.fill_positions_of_kmers(&concatenated_sequence);
+        Ok(kmer_index)
+    }
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+        if pattern.is_empty() {
+            return Vec::new()
+        }
+        if pattern.len() < self.kmer_size as usize {
+            let mut positions = self.positions_of_kmers_starting_with(pattern).to_vec();
+            positions.extend(self.positions_in_tail(pattern));
+            positions.sort_unstable();
+            return positions
+        }
+        let kmers_covering_pattern = self.kmers_covering_pattern(pattern);
+        let Some((driver_offset, driver_positions)) = kmers_covering_pattern.iter().min_by_key(|(_, positions)| positions.len()) else {
+            return Vec::new()
+        };
+        driver_positions.iter().filter_map(|&position| {
+            let start = position.checked_sub(*driver_offset)?;
+            kmers_covering_pattern.iter().all(|(offset, positions)| {
+                positions.binary_search(&(start + offset)).is_ok()
+            }).then_some(start)
+        }).collect()
+    }
+    fn count(&self, pattern: &[u8]) -> u64 {
+        if pattern.is_empty() {
+            return 0
+        }
+        if pattern.len() < self.kmer_size as usize {
+            return (
+                self.positions_of_kmers_starting_with(pattern).len()
+                + self.positions_in_tail(pattern).count()
+            ) as u64
+        }
+        if pattern.len() == self.kmer_size as usize {
+            return self.positions_of_kmer(pattern).len() as u64
+        }
+        self.get_sorted_positions(pattern).len() as u64
+    }
+}
+
+impl KmerIndex {
+    pub fn get_kmer_size(&self) -> u32 {
+        self.kmer_size
+    }
+    /// Bytes of the index in memory, with the hash table that is not saved.
+    pub fn size_in_memory(&self) -> usize {
+        self.code_of_character.capacity() * std::mem::size_of::<u16>()
+        + self.tail_of_sequence.capacity()
+        + self.sorted_kmers.capacity() * std::mem::size_of::<u64>()
+        + self.offsets.capacity() * std::mem::size_of::<u32>()
+        + self.positions.capacity() * std::mem::size_of::<u32>()
+        // The buckets are 8/7 of the capacity, with a control byte each.
+        + self.index_of_kmer.capacity() * 8 / 7 * (std::mem::size_of::<(u64, u32)>() + 1)
+    }
+    fn fill_positions_of_kmers(&mut self, concatenated_sequence: &[u8]) {
+        // (1) Count k-mers
+        let mut count_of_kmer: AHashMap<u64, u32> = AHashMap::new();
+        self.for_each_kmer(concatenated_sequence, |kmer, _| {
+            *count_of_kmer.entry(kmer).or_insert(0) += 1;
+        });
+        // (2) Offsets of sorted k-mers
+        let mut sorted_kmers: Vec<u64> = count_of_kmer.keys().copied().collect();
+        sorted_kmers.sort_unstable();
+        let mut offsets = Vec::with_capacity(sorted_kmers.len() + 1);
+        offsets.push(0);
+        let mut accumulated_count = 0;
+        for kmer in &sorted_kmers {
+            accumulated_count += count_of_kmer[kmer];
+            offsets.push(accumulated_count);
+        }
+        self.sorted_kmers = sorted_kmers;
+        self.offsets = offsets;
+        self.build_index_of_kmer();
+        // (3) Fill positions (already sorted by the scan)
+        let mut next_offsets = self.offsets.clone();
+        let mut positions = vec![0; accumulated_count as usize];
+        self.for_each_kmer(concatenated_sequence, |kmer, position| {
+            let next_offset = &mut next_offsets[self.index_of_kmer[&kmer] as usize];
+            positions[*next_offset as usize] = position;
+            *next_offset += 1;
+        });
+        self.positions = positions;
+    }
+    fn build_index_of_kmer(&mut self) {
+        self.index_of_kmer = self.sorted_kmers.iter().enumerate().map(|(index, kmer)| {
+            (*kmer, index as u32)
+        }).collect();
+    }
+    #[inline]
+    fn for_each_kmer<F: FnMut(u64, u32)>(&self, concatenated_sequence: &[u8], mut f: F) {
+        let kmer_size = self.kmer_size as usize;
+        if concatenated_sequence.len() < kmer_size {
+            return
+        }
+        let mask = u64::MAX >> (u64::BITS - self.kmer_size * self.bits_per_character);
+        let mut kmer = 0;
+        for (index, character) in concatenated_sequence.iter().enumerate() {
+            kmer = ((kmer << self.bits_per_character) | self.code_of_character[*character as usize] as u64) & mask;
+            if index + 1 >= kmer_size {
+                f(kmer, (index + 1 - kmer_size) as u32);
+            }
+        }
+    }
+    /// Sorted positions of the k-mer
+    #[inline]
+    fn positions_of_kmer(&self, kmer_sequence: &[u8]) -> &[u32] {
+        let Some(kmer) = self.kmer_of_pattern(kmer_sequence) else {
+            return &[]
+        };
+        let Some(&index) = self.index_of_kmer.get(&kmer) else {
+            return &[]
+        };
+        &self.positions[
+            self.offsets[index as usize] as usize..self.offsets[index as usize + 1] as usize
+        ]
+    }
+    /// (Offset in the pattern, sorted positions) of the k-mers covering the pattern.
+    ///  - The k-mers are not overlapped, except for the last one aligned to the end of the pattern.
+    #[inline]
+    fn kmers_covering_pattern<'a>(&'a self, pattern: &[u8]) -> Vec<(u32, &'a [u32])> {
+        let kmer_size = self.kmer_size as usize;
+        let last_offset = pattern.len() - kmer_size;
+        (0..last_offset).step_by(kmer_size).chain(std::iter::once(last_offset)).map(|offset| {
+            (offset as u32, self.positions_of_kmer(&pattern[offset..offset + kmer_size]))
+        }).collect()
+    }
+    /// Positions of all k-mers starting with the pattern shorter than the k-mer size.
+    ///  - The k-mers sharing the prefix are adjacent in `sorted_kmers`, so are their positions (unsorted).
+    #[inline]
+    fn positions_of_kmers_starting_with(&self, pattern: &[u8]) -> &[u32] {
+        let Some(prefix) = self.kmer_of_pattern(pattern) else {
+            return &[]
+        };
+        let shift = (self.kmer_size - pattern.len() as u32) * self.bits_per_character;
+        let first_kmer = prefix << shift;
+        let last_kmer = first_kmer | ((1 << shift) - 1);
+        let start = self.sorted_kmers.partition_point(|&kmer| kmer < first_kmer);
+        let end = self.sorted_kmers.partition_point(|&kmer| kmer <= last_kmer);
+        &self.positions[self.offsets[start] as usize..self.offsets[end] as usize]
+    }
+    /// Positions of the pattern shorter than the k-mer size in the tail, where no k-mer starts.
+    #[inline]
+    fn positions_in_tail<'a>(&'a self, pattern: &'a [u8]) -> impl Iterator<Item = u32> + 'a {
+        let start_of_tail = self.sequence_length - self.tail_of_sequence.len() as u32;
+        self.tail_of_sequence.windows(pattern.len()).enumerate().filter_map(move |(index, window)| {
+            (window == pattern).then_some(start_of_tail + index as u32)
+        })
+    }
+    #[inline]
+    fn kmer_of_pattern(&self, pattern: &[u8]) -> Option<u64> {
+        let mut kmer = 0;
+        for character in pattern {
+            let code = self.code_of_character[*character as usize];
+            if code == 0 {
+                return None
+            }
+            kmer = (kmer << self.bits_per_character) | code as u64;
+        }
+        Some(kmer)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum KmerIndexBuildError {
+    /// Triggered when sequence length exceeds the maximum allowable capacity.
+    #[error("Sequence length is over the maximum capacity {0}")]
+    SequenceLengthOver(u64),
+    /// Triggered when the k-mer size is zero or cannot be packed in 64 bits with the characters of sequence.
+    #[error("K-mer size should be in 1..={max}, input is {input}")]
+    InvalidKmerSize{
+        max: u32,
+        input: u32,
+    },
+}
+
+// Impl Extensions
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+};
+//  - Serialize
+use crate::core::{EndianType, WriteBytesExt, ReadBytesExt};
+use capwriter::{Save, Load};
+impl Serialize for KmerIndex {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
+        W: std::io::Write
+    {
+        writer.write_u32::<EndianType>(self.kmer_size)?;
+        writer.write_u32::<EndianType>(self.bits_per_character)?;
+        self.code_of_character.save_to(&mut writer)?;
+        writer.write_u32::<EndianType>(self.sequence_length)?;
+        self.tail_of_sequence.save_to(&mut writer)?;
+        self.sorted_kmers.save_to(&mut writer)?;
+        self.offsets.save_to(&mut writer)?;
+        self.positions.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, std::io::Error> where
+        R: std::io::Read,
+        Self: Sized
+    {
+        let kmer_size = reader.read_u32::<EndianType>()?;
+        let bits_per_character = reader.read_u32::<EndianType>()?;
+        let code_of_character = Vec::load_from(&mut reader)?;
+        let sequence_length = reader.read_u32::<EndianType>()?;
+        let tail_of_sequence = Vec::load_from(&mut reader)?;
+        let sorted_kmers = Vec::load_from(&mut reader)?;
+        let offsets = Vec::load_from(&mut reader)?;
+        let positions = Vec::load_from(&mut reader)?;
+        let mut kmer_index = Self {
+            kmer_size,
+            bits_per_character,
+            code_of_character,
+            sequence_length,
+            tail_of_sequence,
+            sorted_kmers,
+            offsets,
+            positions,
+            index_of_kmer: AHashMap::new(),
+        };
+        kmer_index.build_index_of_kmer();
+        Ok(kmer_index)
+    }
+}
+//  - EstimateSize
+impl EstimateSize for KmerIndex {
+    fn serialized_size(&self) -> usize {
+        3 * std::mem::size_of::<u32>()
+        + self.code_of_character.to_be_saved_size()
+        + self.tail_of_sequence.to_be_saved_size()
+        + self.sorted_kmers.to_be_saved_size()
+        + self.offsets.to_be_saved_size()
+        + self.positions.to_be_saved_size()
+    }
+}
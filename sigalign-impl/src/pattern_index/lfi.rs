@@ -1,9 +1,11 @@
+use std::borrow::Cow;
+
 use thiserror::Error;
 
 use crate::utils::get_unique_characters_of_sequence;
-use sigalign_core::reference::PatternIndex;
+use sigalign_core::reference::{PatternIndex, SequenceLength};
 use lt_fm_index::{
-    LtFmIndex, Block, blocks,
+    LtFmIndex, Block, blocks, Position,
 };
 
 pub type Lfi32B2V64 = Lfi32<blocks::Block2<u64>>;
@@ -11,8 +13,18 @@ pub type Lfi32B3V64 = Lfi32<blocks::Block3<u64>>;
 pub type Lfi32B4V64 = Lfi32<blocks::Block4<u64>>;
 pub type Lfi32B5V64 = Lfi32<blocks::Block5<u64>>;
 
-pub struct Lfi32<B: Block<u32>> {
-    inner: LtFmIndex<u32, B>,
+pub type Lfi64B2V64 = Lfi64<blocks::Block2<u64>>;
+pub type Lfi64B3V64 = Lfi64<blocks::Block3<u64>>;
+pub type Lfi64B4V64 = Lfi64<blocks::Block4<u64>>;
+pub type Lfi64B5V64 = Lfi64<blocks::Block5<u64>>;
+
+/// `Lfi` for the concatenated sequence shorter than `u32::MAX`.
+pub type Lfi32<B> = Lfi<u32, B>;
+/// `Lfi` for the concatenated sequence longer than `u32::MAX`.
+pub type Lfi64<B> = Lfi<u64, B>;
+
+pub struct Lfi<P: Position, B: Block<P>> {
+    inner: LtFmIndex<P, B>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,11 +47,15 @@ impl LfiOption {
     }
 }
 
-impl <B: Block<u32>> PatternIndex for Lfi32<B> {
+impl<P, B> PatternIndex for Lfi<P, B> where
+    P: Position + SequenceLength,
+    B: Block<P>,
+{
     type Option = LfiOption;
     type BuildError = LfiBuildError;
+    type Position = P;
     
-    fn new(concatenated_sequence : Vec<u8>, option: Self::Option) -> Result<Self, Self::BuildError> {
+    fn new(concatenated_sequence: Cow<'_, [u8]>, option: Self::Option) -> Result<Self, Self::BuildError> {
         let unique_sequence = get_unique_characters_of_sequence(&concatenated_sequence);
         let mut valid_characters: Vec<Vec<u8>> = unique_sequence.into_iter().map(|v| vec![v]).collect();
         if !option.use_safe_guard {
@@ -56,30 +72,35 @@ impl <B: Block<u32>> PatternIndex for Lfi32<B> {
             return Err(err);
         }
 
-        let sequence_length = concatenated_sequence.len();
-        if sequence_length >= u32::MAX as usize {
-            return Err(Self::BuildError::SequenceLengthOver(u32::MAX as u64));
+        let sequence_length = concatenated_sequence.len() as u64;
+        let max_sequence_length = SequenceLength::as_u64(P::max_value());
+        if sequence_length >= max_sequence_length {
+            return Err(Self::BuildError::SequenceLengthOver(max_sequence_length));
         }
         let lookup_table_kmer_size = calculate_lookup_table_kmer_size(
             characters_by_index.len(),
             option.lookup_table_max_bytes_size as usize,
         );
 
+        // The FM-index transforms the sequence in place.
         match LtFmIndex::build(
-            concatenated_sequence,
+            concatenated_sequence.into_owned(),
             &characters_by_index,
-            option.suffix_array_sampling_ratio as u32,
+            <P as Position>::from_u64(option.suffix_array_sampling_ratio),
             lookup_table_kmer_size,
         ) {
             Ok(v) => Ok(Self { inner: v }),
             Err(err) => Err(Self::BuildError::InvalidOption(format!("{}", err))),
         }
     }
-    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<u32> {
+    fn get_sorted_positions(&self, pattern: &[u8]) -> Vec<P> {
         let mut positions = self.inner.locate(pattern);
         positions.sort_unstable();
         positions
     }
+    fn count(&self, pattern: &[u8]) -> u64 {
+        Position::as_u64(self.inner.count(pattern))
+    }
 }
 
 fn calculate_lookup_table_kmer_size(
@@ -118,7 +139,10 @@ use sigalign_core::reference::extensions::{
     EstimateSize,
 };
 //  - Serialize
-impl<B: Block<u32>> Serialize for Lfi32<B> {
+impl<P, B> Serialize for Lfi<P, B> where
+    P: Position + SequenceLength,
+    B: Block<P>,
+{
     fn save_to<W>(&self, mut writer: W) -> Result<(), std::io::Error> where
         W: std::io::Write
     {
@@ -134,7 +158,10 @@ impl<B: Block<u32>> Serialize for Lfi32<B> {
     }
 }
 //  - EstimateSize
-impl<B: Block<u32>> EstimateSize for Lfi32<B> {
+impl<P, B> EstimateSize for Lfi<P, B> where
+    P: Position + SequenceLength,
+    B: Block<P>,
+{
     fn serialized_size(&self) -> usize {
         self.inner.to_be_saved_size()
     }
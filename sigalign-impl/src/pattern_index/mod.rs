@@ -1,2 +1,4 @@
 pub mod lfi;
 pub mod dynamic_lfi;
+pub mod kmer_index;
+pub mod dynamic_pattern_index;
@@ -0,0 +1,96 @@
+use std::io::{Read, Write, Error, ErrorKind};
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LabelStorage,
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::{BlockCompressedStorage, StorageId};
+
+//  - Serialize
+//    The blocks are saved compressed.
+//    Loading fails with `ErrorKind::InvalidData` if the blocks or the offsets are corrupted.
+impl Serialize for BlockCompressedStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        writer.write_u64::<EndianType>(self.block_size as u64)?;
+        writer.write_u64::<EndianType>(self.target_count as u64)?;
+        self.compressed_blocks.save_to(&mut writer)?;
+        self.block_offsets.save_to(&mut writer)?;
+        self.pending_block.save_to(&mut writer)?;
+        self.sequence_index.save_to(&mut writer)?;
+        self.concatenated_label.as_bytes().save_to(&mut writer)?;
+        self.label_index.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let block_size = reader.read_u64::<EndianType>()? as usize;
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        let compressed_blocks = Vec::load_from(&mut reader)?;
+        let block_offsets = Vec::load_from(&mut reader)?;
+        let pending_block = Vec::load_from(&mut reader)?;
+        let sequence_index = Vec::load_from(&mut reader)?;
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        let storage = Self {
+            storage_id: StorageId::new(),
+            block_size,
+            target_count,
+            compressed_blocks,
+            block_offsets,
+            pending_block,
+            sequence_index,
+            concatenated_label,
+            label_index,
+        };
+        if !storage.validate() {
+            return Err(ErrorKind::InvalidData.into())
+        }
+        Ok(storage)
+    }
+}
+
+//  - EstimateSize
+impl EstimateSize for BlockCompressedStorage {
+    fn serialized_size(&self) -> usize {
+        // block_size, target_count
+        2 * std::mem::size_of::<u64>()
+        // blocks
+        + self.compressed_blocks.to_be_saved_size()
+        + self.block_offsets.to_be_saved_size()
+        + self.pending_block.to_be_saved_size()
+        // sequence_index
+        + self.sequence_index.to_be_saved_size()
+        // concatenated_label
+        + self.concatenated_label.as_bytes().to_be_saved_size()
+        // label_index
+        + self.label_index.to_be_saved_size()
+    }
+}
+//  - Label Storage
+impl LabelStorage for BlockCompressedStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        self.concatenated_label[
+            self.label_index[target_index as usize]
+            ..self.label_index[target_index as usize +1]
+        ].to_string()
+    }
+}
+impl BlockCompressedStorage {
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        Some(self.label_of_target_unchecked(target_index))
+    }
+}
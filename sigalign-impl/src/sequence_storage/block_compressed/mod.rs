@@ -0,0 +1,304 @@
+use std::{borrow::Cow, io::{Read, Write}, str::Utf8Error, sync::atomic::{AtomicU64, Ordering}};
+
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    SequenceBuffer,
+    extensions::LabelStorage,
+};
+use sigalign_utils::sequence_reader::{
+    SeqRecord, IdRecord,
+    fasta::FastaReader,
+    decompress::get_gzip_decoder,
+};
+use super::in_memory::InMemoryStorage;
+
+/// `SequenceStorage` keeping the sequences in independently compressed blocks (deflate)
+///  - The concatenated sequence of the targets is split into blocks of `block_size` bytes.
+///  - Only the blocks overlapping the target are decompressed to fill the buffer,
+///    and the recently decompressed blocks are cached in each buffer.
+///  - Suitable for the large references whose targets are rarely hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockCompressedStorage {
+    storage_id: StorageId,
+    block_size: usize,
+    target_count: usize,
+    compressed_blocks: Vec<u8>,
+    // Offsets of the blocks in `compressed_blocks` (number of blocks + 1 items)
+    block_offsets: Vec<u64>,
+    // The last block that is not full yet is kept uncompressed.
+    pending_block: Vec<u8>,
+    sequence_index: Vec<u64>,
+    concatenated_label: String,
+    label_index: Vec<usize>,
+}
+/// Buffer of `BlockCompressedStorage` with the cache of the decompressed blocks.
+///  - The cached blocks are tagged with the storage, so the buffer can be filled by the other storages.
+pub struct BlockCompressedBuffer {
+    // (Storage ID, block index, block). Most recently used block is the last.
+    cached_blocks: Vec<(u64, usize, Vec<u8>)>,
+    sequence: Vec<u8>,
+}
+
+/// Default number of bytes in one block (64 KiB)
+pub const DEFAULT_BLOCK_SIZE: usize = 1 << 16;
+const CACHED_BLOCK_COUNT: usize = 8;
+
+// Identifier of the storage to tag the cached blocks
+//  - The cloned storage gets a new identifier, since the blocks of the clone can differ after the targets are added.
+//  - Ignored when the storages are compared.
+#[derive(Debug)]
+struct StorageId(u64);
+impl StorageId {
+    fn new() -> Self {
+        static NEXT_STORAGE_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_STORAGE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+impl Clone for StorageId {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+impl PartialEq for StorageId {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+impl Eq for StorageId {}
+
+// Sequence Storage
+impl SequenceStorage for BlockCompressedStorage {
+    type Buffer = BlockCompressedBuffer;
+
+    fn num_targets(&self) -> u32 {
+        self.target_count as u32
+    }
+    fn get_buffer(&self) -> Self::Buffer {
+        BlockCompressedBuffer::new()
+    }
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        let start = self.sequence_index[target_index as usize] as usize;
+        let end = self.sequence_index[target_index as usize + 1] as usize;
+        buffer.sequence.clear();
+        if start == end {
+            return
+        }
+        for block_index in (start / self.block_size)..=((end - 1) / self.block_size) {
+            let block_start = block_index * self.block_size;
+            let range_in_block = (start.max(block_start) - block_start)..(end.min(block_start + self.block_size) - block_start);
+            if block_index == self.full_block_count() {
+                buffer.sequence.extend_from_slice(&self.pending_block[range_in_block]);
+            } else {
+                buffer.cache_block(self.storage_id.0, block_index, || self.decompress_block(block_index));
+                let (_, _, block) = buffer.cached_blocks.last().unwrap();
+                buffer.sequence.extend_from_slice(&block[range_in_block]);
+            }
+        }
+    }
+    fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
+        Cow<'_, [u8]>,
+        Vec<u64>,
+    ) {
+        let mut concatenated_sequence = Vec::with_capacity(self.get_total_length() as usize);
+        for block_index in 0..self.full_block_count() {
+            concatenated_sequence.extend_from_slice(&self.decompress_block(block_index));
+        }
+        concatenated_sequence.extend_from_slice(&self.pending_block);
+        (Cow::Owned(concatenated_sequence), self.sequence_index.clone())
+    }
+}
+
+impl SequenceBuffer for BlockCompressedBuffer {
+    fn buffered_sequence(&self) -> &[u8] {
+        &self.sequence
+    }
+}
+
+impl BlockCompressedStorage {
+    pub fn new() -> Self {
+        Self::with_block_size(DEFAULT_BLOCK_SIZE)
+    }
+    /// Smaller blocks are faster to fill the buffer of short targets, but compressed less.
+    ///  - Panics if the `block_size` is zero.
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size > 0, "The block size must be positive.");
+        Self {
+            storage_id: StorageId::new(),
+            block_size,
+            target_count: 0,
+            compressed_blocks: Vec::new(),
+            block_offsets: vec![0],
+            pending_block: Vec::with_capacity(block_size),
+            sequence_index: vec![0],
+            concatenated_label: String::new(),
+            label_index: vec![0],
+        }
+    }
+    pub fn add_target(
+        &mut self,
+        label: &str,
+        sequence: &[u8],
+    ) {
+        self.target_count += 1;
+        self.push_sequence(sequence);
+        self.concatenated_label.push_str(label);
+        self.label_index.push(self.concatenated_label.len());
+    }
+    pub fn add_fasta<R: Read>(&mut self, reader: R) -> Result<(), Utf8Error> {
+        let mut fasta_reader = FastaReader::new(reader);
+        let mut seq_buffer = Vec::new();
+        while let Some(mut record) = fasta_reader.next() {
+            self.target_count += 1;
+            seq_buffer.clear();
+            record.extend_seq_buf(&mut seq_buffer);
+            self.push_sequence(&seq_buffer);
+            record.extend_id_string(&mut self.concatenated_label)?;
+            self.label_index.push(self.concatenated_label.len());
+        }
+        Ok(())
+    }
+    pub fn add_gzip_fasta<R: Read>(&mut self, reader: R) -> Result<(), Utf8Error> {
+        let decomp_reader = get_gzip_decoder(reader);
+        self.add_fasta(decomp_reader)
+    }
+    pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        let mut buffer = self.get_buffer();
+        self.fill_buffer(target_index, &mut buffer);
+        Some(buffer.sequence)
+    }
+    pub fn get_total_length(&self) -> u64 {
+        *self.sequence_index.last().unwrap()
+    }
+    pub fn get_block_size(&self) -> usize {
+        self.block_size
+    }
+    /// Bytes of the compressed blocks and the uncompressed last block
+    pub fn get_compressed_length(&self) -> u64 {
+        (self.compressed_blocks.len() + self.pending_block.len()) as u64
+    }
+    /// Remove all labels
+    /// !Cannot be undone
+    pub fn remove_labels(&mut self) {
+        self.concatenated_label = String::new();
+        self.label_index = vec![0; self.target_count+1];
+    }
+
+    fn push_sequence(&mut self, mut sequence: &[u8]) {
+        self.sequence_index.push(self.get_total_length() + sequence.len() as u64);
+        while !sequence.is_empty() {
+            let length_to_fill = (self.block_size - self.pending_block.len()).min(sequence.len());
+            self.pending_block.extend_from_slice(&sequence[..length_to_fill]);
+            sequence = &sequence[length_to_fill..];
+            if self.pending_block.len() == self.block_size {
+                self.compress_pending_block();
+            }
+        }
+    }
+    fn compress_pending_block(&mut self) {
+        let mut encoder = DeflateEncoder::new(&mut self.compressed_blocks, Compression::default());
+        // Writing to `Vec` never fails.
+        encoder.write_all(&self.pending_block).unwrap();
+        encoder.finish().unwrap();
+        self.block_offsets.push(self.compressed_blocks.len() as u64);
+        self.pending_block.clear();
+    }
+    fn full_block_count(&self) -> usize {
+        self.block_offsets.len() - 1
+    }
+    fn decompress_block(&self, block_index: usize) -> Vec<u8> {
+        let compressed_block = &self.compressed_blocks[
+            self.block_offsets[block_index] as usize
+            ..self.block_offsets[block_index + 1] as usize
+        ];
+        let mut block = Vec::with_capacity(self.block_size);
+        // The blocks are compressed by this storage, or validated when loaded (`validate`).
+        DeflateDecoder::new(compressed_block).read_to_end(&mut block).unwrap();
+        block
+    }
+    // Check that the loaded storage is consistent, decompressing all blocks.
+    fn validate(&self) -> bool {
+        let full_block_count = match self.block_offsets.len().checked_sub(1) {
+            Some(v) => v,
+            None => return false,
+        };
+        let blocks_are_valid = self.block_size != 0
+            && self.pending_block.len() < self.block_size
+            && self.block_offsets[0] == 0
+            && self.block_offsets.windows(2).all(|v| v[0] <= v[1])
+            && self.block_offsets[full_block_count] == self.compressed_blocks.len() as u64;
+        if !blocks_are_valid {
+            return false
+        }
+        let total_length = (full_block_count as u64).checked_mul(self.block_size as u64)
+            .and_then(|v| v.checked_add(self.pending_block.len() as u64));
+        let targets_are_valid = self.sequence_index.len() == self.target_count + 1
+            && self.sequence_index[0] == 0
+            && self.sequence_index.windows(2).all(|v| v[0] <= v[1])
+            && Some(self.sequence_index[self.target_count]) == total_length
+            && self.label_index.len() == self.target_count + 1
+            && self.label_index.windows(2).all(|v| v[0] <= v[1])
+            && self.label_index.iter().all(|&v| self.concatenated_label.is_char_boundary(v));
+        if !targets_are_valid {
+            return false
+        }
+        let mut block = Vec::with_capacity(self.block_size);
+        (0..full_block_count).all(|block_index| {
+            let compressed_block = &self.compressed_blocks[
+                self.block_offsets[block_index] as usize
+                ..self.block_offsets[block_index + 1] as usize
+            ];
+            block.clear();
+            // Read one more byte than the block size to detect the longer block.
+            let decoded = DeflateDecoder::new(compressed_block)
+                .take(self.block_size as u64 + 1)
+                .read_to_end(&mut block);
+            decoded.is_ok() && block.len() == self.block_size
+        })
+    }
+}
+
+impl BlockCompressedBuffer {
+    pub fn new() -> Self {
+        Self {
+            cached_blocks: Vec::with_capacity(CACHED_BLOCK_COUNT),
+            sequence: Vec::new(),
+        }
+    }
+    /// Move the block to the last of the cache, decompressing it if not cached.
+    fn cache_block<F>(&mut self, storage_id: u64, block_index: usize, decompress: F) where
+        F: FnOnce() -> Vec<u8>,
+    {
+        match self.cached_blocks.iter().position(|(id, index, _)| *id == storage_id && *index == block_index) {
+            Some(position) => {
+                let cached = self.cached_blocks.remove(position);
+                self.cached_blocks.push(cached);
+            },
+            None => {
+                if self.cached_blocks.len() == CACHED_BLOCK_COUNT {
+                    self.cached_blocks.remove(0);
+                }
+                self.cached_blocks.push((storage_id, block_index, decompress()));
+            },
+        }
+    }
+}
+
+mod extensions;
+
+impl From<InMemoryStorage> for BlockCompressedStorage {
+    fn from(in_memory_storage: InMemoryStorage) -> Self {
+        let mut block_compressed_storage = Self::new();
+        let mut buffer = in_memory_storage.get_buffer();
+        for target_index in 0..in_memory_storage.num_targets() {
+            in_memory_storage.fill_buffer(target_index, &mut buffer);
+            let label = in_memory_storage.label_of_target_unchecked(target_index);
+            block_compressed_storage.add_target(&label, buffer.buffered_sequence());
+        }
+        block_compressed_storage
+    }
+}
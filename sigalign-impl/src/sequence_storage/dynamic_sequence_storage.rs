@@ -0,0 +1,218 @@
+use std::borrow::Cow;
+use std::io::{Read, Write, Error, ErrorKind};
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    SequenceBuffer,
+    extensions::{
+        Serialize,
+        EstimateSize,
+        LabelStorage,
+        LoadShared,
+        SharedBytes,
+    },
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::{
+    in_memory::{InMemoryStorage, InMemoryBuffer},
+    packed_nucleotide::{PackedNucleotideStorage, PackedNucleotideBuffer},
+    block_compressed::{BlockCompressedStorage, BlockCompressedBuffer},
+};
+
+/// `SequenceStorage` that is one byte per base (`InMemoryStorage`), 2-bit packed (`PackedNucleotideStorage`),
+/// or compressed in blocks (`BlockCompressedStorage`).
+///  - The `IndexedFastaStorage` is not a variant: it is used only with the `Reference` of "sigalign-core".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicSequenceStorage {
+    InMemory(InMemoryStorage),
+    PackedNucleotide(PackedNucleotideStorage),
+    BlockCompressed(BlockCompressedStorage),
+}
+/// Buffer of `DynamicSequenceStorage`
+///  - Replaced by the buffer of the storage, if it is of the other storage.
+pub enum DynamicSequenceBuffer {
+    InMemory(InMemoryBuffer),
+    PackedNucleotide(PackedNucleotideBuffer),
+    BlockCompressed(BlockCompressedBuffer),
+}
+
+impl SequenceStorage for DynamicSequenceStorage {
+    type Buffer = DynamicSequenceBuffer;
+
+    fn num_targets(&self) -> u32 {
+        match self {
+            Self::InMemory(v) => v.num_targets(),
+            Self::PackedNucleotide(v) => v.num_targets(),
+            Self::BlockCompressed(v) => v.num_targets(),
+        }
+    }
+    fn get_buffer(&self) -> Self::Buffer {
+        match self {
+            Self::InMemory(v) => DynamicSequenceBuffer::InMemory(v.get_buffer()),
+            Self::PackedNucleotide(v) => DynamicSequenceBuffer::PackedNucleotide(v.get_buffer()),
+            Self::BlockCompressed(v) => DynamicSequenceBuffer::BlockCompressed(v.get_buffer()),
+        }
+    }
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        match (self, &mut *buffer) {
+            (Self::InMemory(v), DynamicSequenceBuffer::InMemory(inner)) => v.fill_buffer(target_index, inner),
+            (Self::PackedNucleotide(v), DynamicSequenceBuffer::PackedNucleotide(inner)) => v.fill_buffer(target_index, inner),
+            (Self::BlockCompressed(v), DynamicSequenceBuffer::BlockCompressed(inner)) => v.fill_buffer(target_index, inner),
+            _ => {
+                *buffer = self.get_buffer();
+                self.fill_buffer(target_index, buffer);
+            },
+        }
+    }
+    fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
+        Cow<'_, [u8]>,
+        Vec<u64>,
+    ) {
+        match self {
+            Self::InMemory(v) => v.get_concatenated_sequence_with_boundaries_of_targets(),
+            Self::PackedNucleotide(v) => v.get_concatenated_sequence_with_boundaries_of_targets(),
+            Self::BlockCompressed(v) => v.get_concatenated_sequence_with_boundaries_of_targets(),
+        }
+    }
+}
+impl SequenceBuffer for DynamicSequenceBuffer {
+    fn buffered_sequence(&self) -> &[u8] {
+        match self {
+            Self::InMemory(v) => v.buffered_sequence(),
+            Self::PackedNucleotide(v) => v.buffered_sequence(),
+            Self::BlockCompressed(v) => v.buffered_sequence(),
+        }
+    }
+}
+
+impl DynamicSequenceStorage {
+    pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
+        match self {
+            Self::InMemory(v) => v.get_sequence_safely(target_index),
+            Self::PackedNucleotide(v) => v.get_sequence_safely(target_index),
+            Self::BlockCompressed(v) => v.get_sequence_safely(target_index),
+        }
+    }
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        match self {
+            Self::InMemory(v) => v.get_label_safely(target_index),
+            Self::PackedNucleotide(v) => v.get_label_safely(target_index),
+            Self::BlockCompressed(v) => v.get_label_safely(target_index),
+        }
+    }
+    pub fn get_total_length(&self) -> u64 {
+        match self {
+            Self::InMemory(v) => v.get_total_length(),
+            Self::PackedNucleotide(v) => v.get_total_length(),
+            Self::BlockCompressed(v) => v.get_total_length(),
+        }
+    }
+    /// Whether the sequences are borrowed from the loaded file instead of being copied to the memory
+    ///  - Always `false` for the `PackedNucleotideStorage` and the `BlockCompressedStorage`.
+    pub fn is_sequence_shared(&self) -> bool {
+        match self {
+            Self::InMemory(v) => v.is_sequence_shared(),
+            Self::PackedNucleotide(_) | Self::BlockCompressed(_) => false,
+        }
+    }
+}
+impl DynamicSequenceBuffer {
+    /// Buffer of the `InMemoryStorage`, replaced when it is filled by the other storage.
+    pub fn new() -> Self {
+        Self::InMemory(InMemoryBuffer::new())
+    }
+}
+
+// Impl Extensions
+//  - Serialize
+impl Serialize for DynamicSequenceStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        match self {
+            Self::InMemory(v) => {
+                writer.write_u64::<EndianType>(Self::IN_MEMORY_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)
+            },
+            Self::PackedNucleotide(v) => {
+                writer.write_u64::<EndianType>(Self::PACKED_NUCLEOTIDE_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)
+            },
+            Self::BlockCompressed(v) => {
+                writer.write_u64::<EndianType>(Self::BLOCK_COMPRESSED_MAGIC_NUMBER)?;
+                v.save_to(&mut writer)
+            },
+        }
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let magic_number = reader.read_u64::<EndianType>()?;
+        match magic_number {
+            Self::IN_MEMORY_MAGIC_NUMBER => Ok(Self::InMemory(InMemoryStorage::load_from(reader)?)),
+            Self::PACKED_NUCLEOTIDE_MAGIC_NUMBER => Ok(Self::PackedNucleotide(PackedNucleotideStorage::load_from(reader)?)),
+            Self::BLOCK_COMPRESSED_MAGIC_NUMBER => Ok(Self::BlockCompressed(BlockCompressedStorage::load_from(reader)?)),
+            _ => Err(ErrorKind::InvalidData.into()),
+        }
+    }
+}
+//  - LoadShared
+//    Only the sequences of the `InMemoryStorage` are borrowed.
+//    The others are copied to the memory.
+impl LoadShared for DynamicSequenceStorage {
+    fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized,
+    {
+        let all_bytes: &[u8] = (**bytes).as_ref();
+        let mut reader = all_bytes.get(*offset..).ok_or(ErrorKind::UnexpectedEof)?;
+        let magic_number = reader.read_u64::<EndianType>()?;
+        match magic_number {
+            Self::IN_MEMORY_MAGIC_NUMBER => {
+                *offset += std::mem::size_of::<u64>();
+                Ok(Self::InMemory(InMemoryStorage::load_shared(bytes, offset)?))
+            },
+            Self::PACKED_NUCLEOTIDE_MAGIC_NUMBER => {
+                let storage = PackedNucleotideStorage::load_from(&mut reader)?;
+                *offset = all_bytes.len() - reader.len();
+                Ok(Self::PackedNucleotide(storage))
+            },
+            Self::BLOCK_COMPRESSED_MAGIC_NUMBER => {
+                let storage = BlockCompressedStorage::load_from(&mut reader)?;
+                *offset = all_bytes.len() - reader.len();
+                Ok(Self::BlockCompressed(storage))
+            },
+            _ => Err(ErrorKind::InvalidData.into()),
+        }
+    }
+}
+impl DynamicSequenceStorage {
+    // MAGIC NUMBERS: FNV1A32 hash value of
+    // InMemoryStorage: 8084c7e2
+    const IN_MEMORY_MAGIC_NUMBER: u64 = 2156185570;
+    // PackedNucleotideStorage: f092e40c
+    const PACKED_NUCLEOTIDE_MAGIC_NUMBER: u64 = 4036158476;
+    // BlockCompressedStorage: 78a9c2a0
+    const BLOCK_COMPRESSED_MAGIC_NUMBER: u64 = 2024391328;
+}
+//  - EstimateSize
+impl EstimateSize for DynamicSequenceStorage {
+    fn serialized_size(&self) -> usize {
+        std::mem::size_of::<u64>()
+        + match self {
+            Self::InMemory(v) => v.serialized_size(),
+            Self::PackedNucleotide(v) => v.serialized_size(),
+            Self::BlockCompressed(v) => v.serialized_size(),
+        }
+    }
+}
+//  - Label Storage
+impl LabelStorage for DynamicSequenceStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        match self {
+            Self::InMemory(v) => v.label_of_target_unchecked(target_index),
+            Self::PackedNucleotide(v) => v.label_of_target_unchecked(target_index),
+            Self::BlockCompressed(v) => v.label_of_target_unchecked(target_index),
+        }
+    }
+}
@@ -6,8 +6,11 @@ use sigalign_core::reference::extensions::{
     Serialize,
     EstimateSize,
     LabelStorage,
+    LoadShared,
+    SharedBytes,
 };
 use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use crate::core::Section;
 use super::InMemoryStorage;
 
 //  - Serialize
@@ -27,7 +30,7 @@ impl Serialize for InMemoryStorage {
         Self: Sized,
     {
         let target_count = reader.read_u64::<EndianType>()? as usize;
-        let concatenated_sequence = Vec::load_from(&mut reader)?;
+        let concatenated_sequence = Section::load_from(&mut reader)?;
         let sequence_index = Vec::load_from(&mut reader)?;
         let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
             Ok(v) => v,
@@ -44,13 +47,42 @@ impl Serialize for InMemoryStorage {
     }
 }
 
+//  - LoadShared
+//    The concatenated sequence is borrowed from the shared bytes.
+impl LoadShared for InMemoryStorage {
+    fn load_shared(bytes: &SharedBytes, offset: &mut usize) -> Result<Self, Error> where
+        Self: Sized,
+    {
+        let all_bytes: &[u8] = (**bytes).as_ref();
+        let mut reader = all_bytes.get(*offset..).ok_or(ErrorKind::UnexpectedEof)?;
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        *offset = all_bytes.len() - reader.len();
+        let concatenated_sequence = Section::load_shared(bytes, offset)?;
+        let mut reader = all_bytes.get(*offset..).ok_or(ErrorKind::UnexpectedEof)?;
+        let sequence_index = Vec::load_from(&mut reader)?;
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        *offset = all_bytes.len() - reader.len();
+        Ok(Self {
+            target_count,
+            concatenated_sequence,
+            sequence_index,
+            concatenated_label,
+            label_index,
+        })
+    }
+}
+
 //  - EstimateSize
 impl EstimateSize for InMemoryStorage {
     fn serialized_size(&self) -> usize {
         // target_count
         std::mem::size_of::<u64>()
         // concatenated_sequence
-        + self.concatenated_sequence.to_be_saved_size()
+        + self.concatenated_sequence.serialized_size()
         // sequence_index
         + self.sequence_index.to_be_saved_size()
         // concatenated_label
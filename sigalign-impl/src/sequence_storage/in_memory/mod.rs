@@ -1,4 +1,4 @@
-use std::{io::Read, str::Utf8Error};
+use std::{borrow::Cow, io::Read, str::Utf8Error};
 
 use sigalign_core::reference::{
     SequenceStorage,
@@ -10,12 +10,14 @@ use sigalign_utils::sequence_reader::{
     decompress::get_gzip_decoder,
 };
 
+use crate::core::Section;
+
 // TODO: Debug impl manually
 /// Basic `SequenceStorage` implementation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InMemoryStorage {
     target_count: usize,
-    concatenated_sequence: Vec<u8>,
+    concatenated_sequence: Section<u8>,
     sequence_index: Vec<usize>,
     concatenated_label: String,
     label_index: Vec<usize>,
@@ -44,11 +46,11 @@ impl SequenceStorage for InMemoryStorage {
         buffer.len = self.sequence_index[target_index as usize +1] - start_index;
     }
     fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
-        Vec<u8>,
-        Vec<u32>,
+        Cow<'_, [u8]>,
+        Vec<u64>,
     ) {
-        let concatenated_sequence = self.concatenated_sequence.to_vec();
-        let boundaries = self.sequence_index.iter().map(|x| *x as u32).collect();
+        let concatenated_sequence = Cow::Borrowed(&self.concatenated_sequence[..]);
+        let boundaries = self.sequence_index.iter().map(|x| *x as u64).collect();
         (concatenated_sequence, boundaries)
     }
 }
@@ -62,7 +64,7 @@ impl InMemoryStorage {
     pub fn new() -> Self {
         Self {
             target_count: 0,
-            concatenated_sequence: Vec::new(),
+            concatenated_sequence: Section::new(),
             sequence_index: vec![0],
             concatenated_label: String::new(),
             label_index: vec![0],
@@ -74,7 +76,7 @@ impl InMemoryStorage {
         sequence: &[u8],
     ) {
         self.target_count += 1;
-        self.concatenated_sequence.extend_from_slice(sequence);
+        self.concatenated_sequence.to_mut().extend_from_slice(sequence);
         self.sequence_index.push(self.concatenated_sequence.len());
         self.concatenated_label.push_str(label);
         self.label_index.push(self.concatenated_label.len());
@@ -83,7 +85,7 @@ impl InMemoryStorage {
         let mut fasta_reader = FastaReader::new(reader);
         while let Some(mut record) = fasta_reader.next() {
             self.target_count += 1;
-            record.extend_seq_buf(&mut self.concatenated_sequence);
+            record.extend_seq_buf(self.concatenated_sequence.to_mut());
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -96,7 +98,7 @@ impl InMemoryStorage {
     pub fn fill_fasta_until_max_length<R: Read>(
         &mut self,
         reader: R,
-        max_length: u32,
+        max_length: u64,
     ) -> Result<Vec<Self>, Utf8Error> {
         let mut filled_storages = Vec::new();
 
@@ -106,7 +108,7 @@ impl InMemoryStorage {
         
         while let Some(mut record) = fasta_reader.next() {
             record.extend_seq_buf(&mut seq_buffer);
-            let new_seq_length = seq_buffer.len() as u32;
+            let new_seq_length = seq_buffer.len() as u64;
 
             if (current_seq_length != 0) && (current_seq_length + new_seq_length > max_length) {
                 let filled_storage = std::mem::replace(self, Self::new());
@@ -119,7 +121,7 @@ impl InMemoryStorage {
             // Add record to current storage
             current_seq_length += new_seq_length;
             self.target_count += 1;
-            self.concatenated_sequence.append(&mut seq_buffer);
+            self.concatenated_sequence.to_mut().append(&mut seq_buffer);
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -131,7 +133,7 @@ impl InMemoryStorage {
         let mut fasta_reader = FastaReader::new(decomp_reader);
         while let Some(mut record) = fasta_reader.next() {
             self.target_count += 1;
-            record.extend_seq_buf(&mut self.concatenated_sequence);
+            record.extend_seq_buf(self.concatenated_sequence.to_mut());
             self.sequence_index.push(self.concatenated_sequence.len());
             record.extend_id_string(&mut self.concatenated_label)?;
             self.label_index.push(self.concatenated_label.len());
@@ -141,7 +143,7 @@ impl InMemoryStorage {
     pub fn merge(&mut self, other: Self) {
         let Self {
             target_count: other_target_count,
-            concatenated_sequence: mut other_combined_sequence,
+            concatenated_sequence: other_combined_sequence,
             sequence_index: other_sequence_index,
             concatenated_label: other_combined_label,
             label_index: other_label_index,
@@ -149,7 +151,7 @@ impl InMemoryStorage {
         // record_count
         self.target_count += other_target_count;
         // concatenated_sequence
-        self.concatenated_sequence.append(&mut other_combined_sequence);
+        self.concatenated_sequence.to_mut().append(&mut other_combined_sequence.into_vec());
         // sequence_index
         let last_seq_idx = *self.sequence_index.last().unwrap();
         self.sequence_index.reserve(other_target_count);
@@ -165,6 +167,49 @@ impl InMemoryStorage {
             self.label_index.push(v+last_label_idx);
         });
     }
+    /// Split into storages in the order of targets.
+    /// Each storage has a total length of at most `max_length`
+    /// !If one record is longer than `max_length`, it will be in a storage of its own
+    ///  - The storages are split off from the end, so that the memory is not doubled while splitting.
+    pub fn split_by_max_length(mut self, max_length: u64) -> Vec<Self> {
+        // Index of the first target of each storage
+        let mut first_target_indices = Vec::new();
+        let mut current_seq_length = 0;
+        for target_index in 0..self.target_count {
+            let new_seq_length = (self.sequence_index[target_index+1] - self.sequence_index[target_index]) as u64;
+            if first_target_indices.is_empty() || (
+                (current_seq_length != 0) && (current_seq_length + new_seq_length > max_length)
+            ) {
+                first_target_indices.push(target_index);
+                current_seq_length = 0;
+            }
+            current_seq_length += new_seq_length;
+        }
+        if first_target_indices.len() <= 1 {
+            return vec![self];
+        }
+
+        let mut split_storages = Vec::with_capacity(first_target_indices.len());
+        for &first_target_index in first_target_indices.iter().rev() {
+            let seq_start = self.sequence_index[first_target_index];
+            let label_start = self.label_index[first_target_index];
+            let storage = Self {
+                target_count: self.target_count - first_target_index,
+                concatenated_sequence: Section::Owned(self.concatenated_sequence.to_mut().split_off(seq_start)),
+                sequence_index: self.sequence_index[first_target_index..].iter().map(|v| v - seq_start).collect(),
+                concatenated_label: self.concatenated_label.split_off(label_start),
+                label_index: self.label_index[first_target_index..].iter().map(|v| v - label_start).collect(),
+            };
+            self.target_count = first_target_index;
+            self.concatenated_sequence.to_mut().shrink_to_fit();
+            self.sequence_index.truncate(first_target_index + 1);
+            self.concatenated_label.shrink_to_fit();
+            self.label_index.truncate(first_target_index + 1);
+            split_storages.push(storage);
+        }
+        split_storages.reverse();
+        split_storages
+    }
     pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
         if target_index as usize >= self.target_count {
             return None
@@ -174,8 +219,16 @@ impl InMemoryStorage {
         let seq = buffer.buffered_sequence().to_vec();
         Some(seq)
     }
-    pub fn get_total_length(&self) -> u32 {
-        self.concatenated_sequence.len() as u32
+    /// Whether the sequences are borrowed from the loaded file instead of being copied to the memory
+    pub fn is_sequence_shared(&self) -> bool {
+        self.concatenated_sequence.is_shared()
+    }
+    pub fn get_total_length(&self) -> u64 {
+        self.concatenated_sequence.len() as u64
+    }
+    /// Length of the longest target (0 if empty)
+    pub fn get_max_target_length(&self) -> u64 {
+        self.sequence_index.windows(2).map(|boundary| (boundary[1] - boundary[0]) as u64).max().unwrap_or(0)
     }
     /// Remove all labels
     /// !Cannot be undone
@@ -183,10 +236,32 @@ impl InMemoryStorage {
         self.concatenated_label = String::new();
         self.label_index = vec![0; self.target_count+1];
     }
+    /// Get the regions of lowercase bases of each target (half-open intervals)
+    pub fn get_lowercase_regions(&self) -> Vec<Vec<(u32, u32)>> {
+        self.sequence_index.windows(2).map(|boundary| {
+            let mut regions = Vec::new();
+            let mut region_start = None;
+            let sequence = &self.concatenated_sequence[boundary[0]..boundary[1]];
+            for (position, base) in sequence.iter().enumerate() {
+                match (base.is_ascii_lowercase(), region_start) {
+                    (true, None) => region_start = Some(position as u32),
+                    (false, Some(start)) => {
+                        regions.push((start, position as u32));
+                        region_start = None;
+                    },
+                    _ => {},
+                }
+            }
+            if let Some(start) = region_start {
+                regions.push((start, sequence.len() as u32));
+            }
+            regions
+        }).collect()
+    }
     /// Set sequence to uppercase
     /// !Cannot be undone
     pub fn set_sequences_to_uppercase(&mut self) {
-        self.concatenated_sequence.make_ascii_uppercase();
+        self.concatenated_sequence.to_mut().make_ascii_uppercase();
     }
     /// Make all designated bases to defined base
     /// !Cannot be undone
@@ -198,7 +273,7 @@ impl InMemoryStorage {
         bases_to_change.iter().for_each(|v| {
             byte_mapper[*v as usize] = target_base;
         });
-        self.concatenated_sequence.iter_mut().for_each(|v| {
+        self.concatenated_sequence.to_mut().iter_mut().for_each(|v| {
             *v = byte_mapper[*v as usize];
         });
     }
@@ -0,0 +1,103 @@
+use std::io::{Read, Write, Error, ErrorKind};
+use std::path::PathBuf;
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LabelStorage,
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::IndexedFastaStorage;
+
+//  - Serialize
+//    Only the path and the index are saved, not the sequences.
+//    Loading fails if the FASTA file is missing or shorter than the index.
+impl Serialize for IndexedFastaStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        let path = match self.fasta_file_path.to_str() {
+            Some(v) => v,
+            None => return Err(Error::new(ErrorKind::InvalidData, "The path of the FASTA file is not valid UTF-8")),
+        };
+        path.as_bytes().save_to(&mut writer)?;
+        writer.write_u64::<EndianType>(self.target_count as u64)?;
+        self.concatenated_label.as_bytes().save_to(&mut writer)?;
+        self.label_index.save_to(&mut writer)?;
+        self.sequence_lengths.save_to(&mut writer)?;
+        self.sequence_offsets.save_to(&mut writer)?;
+        self.line_bases.save_to(&mut writer)?;
+        self.line_widths.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let fasta_file_path = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => PathBuf::from(v),
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        let sequence_lengths = Vec::load_from(&mut reader)?;
+        let sequence_offsets = Vec::load_from(&mut reader)?;
+        let line_bases = Vec::load_from(&mut reader)?;
+        let line_widths = Vec::load_from(&mut reader)?;
+        let storage = Self {
+            fasta_file_path,
+            target_count,
+            concatenated_label,
+            label_index,
+            sequence_lengths,
+            sequence_offsets,
+            line_bases,
+            line_widths,
+        };
+        // The FASTA file is read lazily, so it is checked here.
+        storage.check_fasta_file()?;
+        Ok(storage)
+    }
+}
+
+//  - EstimateSize
+impl EstimateSize for IndexedFastaStorage {
+    fn serialized_size(&self) -> usize {
+        // fasta_file_path
+        self.fasta_file_path.to_str().unwrap_or_default().as_bytes().to_be_saved_size()
+        // target_count
+        + std::mem::size_of::<u64>()
+        // concatenated_label
+        + self.concatenated_label.as_bytes().to_be_saved_size()
+        // label_index
+        + self.label_index.to_be_saved_size()
+        // records of the index
+        + self.sequence_lengths.to_be_saved_size()
+        + self.sequence_offsets.to_be_saved_size()
+        + self.line_bases.to_be_saved_size()
+        + self.line_widths.to_be_saved_size()
+    }
+}
+//  - Label Storage
+impl LabelStorage for IndexedFastaStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        self.concatenated_label[
+            self.label_index[target_index as usize]
+            ..self.label_index[target_index as usize +1]
+        ].to_string()
+    }
+}
+impl IndexedFastaStorage {
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        Some(self.label_of_target_unchecked(target_index))
+    }
+}
@@ -0,0 +1,104 @@
+use std::io::{BufRead, Error, ErrorKind};
+
+/// A line of the `.fai` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaiRecord {
+    pub name: String,
+    pub length: u64,
+    /// Offset of the first base in the FASTA file
+    pub offset: u64,
+    /// Number of bases in each line
+    pub line_bases: u64,
+    /// Number of bytes in each line (including the line terminator)
+    pub line_width: u64,
+}
+
+pub fn read_fai<R: BufRead>(reader: R) -> Result<Vec<FaiRecord>, Error> {
+    let mut fai_records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 5 {
+            return Err(invalid_data("The `.fai` line has less than 5 fields"))
+        }
+        let parse = |field: &str| field.parse::<u64>().map_err(|_| invalid_data("The `.fai` field is not a number"));
+        let fai_record = FaiRecord {
+            name: fields[0].to_string(),
+            length: parse(fields[1])?,
+            offset: parse(fields[2])?,
+            line_bases: parse(fields[3])?,
+            line_width: parse(fields[4])?,
+        };
+        if fai_record.length != 0 && (fai_record.line_bases == 0 || fai_record.line_width < fai_record.line_bases) {
+            return Err(invalid_data(&format!("The `.fai` line of the record {} has invalid line lengths", fai_record.name)))
+        }
+        fai_records.push(fai_record);
+    }
+    Ok(fai_records)
+}
+
+/// Index the FASTA file as `samtools faidx`.
+///  - All lines of a record except the last one must have the same length.
+pub fn index_fasta<R: BufRead>(mut reader: R) -> Result<Vec<FaiRecord>, Error> {
+    let mut fai_records: Vec<FaiRecord> = Vec::new();
+    let mut line = Vec::new();
+    let mut position = 0_u64;
+    // Whether the previous line of the record is shorter than the others
+    let mut ended_with_short_line = false;
+    loop {
+        line.clear();
+        let line_width = reader.read_until(b'\n', &mut line)? as u64;
+        if line_width == 0 {
+            break
+        }
+        let line_start = position;
+        position += line_width;
+
+        if line[0] == b'>' {
+            let header = String::from_utf8_lossy(&line[1..]);
+            let name = header.split_whitespace().next().unwrap_or_default().to_string();
+            fai_records.push(FaiRecord {
+                name,
+                length: 0,
+                offset: position,
+                line_bases: 0,
+                line_width: 0,
+            });
+            ended_with_short_line = false;
+            continue
+        }
+        let line_bases = line.iter().filter(|&&v| v != b'\n' && v != b'\r').count() as u64;
+        let Some(record) = fai_records.last_mut() else {
+            if line_bases == 0 {
+                continue
+            }
+            return Err(invalid_data("The FASTA file does not start with a header"))
+        };
+        if line_bases == 0 {
+            ended_with_short_line = true;
+            continue
+        }
+        if ended_with_short_line {
+            return Err(invalid_data(&format!("Lines of the record {} have different lengths", record.name)))
+        }
+        if record.length == 0 {
+            record.offset = line_start;
+            record.line_bases = line_bases;
+            record.line_width = line_width;
+        } else if line_bases > record.line_bases {
+            return Err(invalid_data(&format!("Lines of the record {} have different lengths", record.name)))
+        }
+        if line_bases < record.line_bases || line_width < record.line_width {
+            ended_with_short_line = true;
+        }
+        record.length += line_bases;
+    }
+    Ok(fai_records)
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,198 @@
+use std::{
+    fs::File,
+    io::{BufReader, Error, ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    SequenceBuffer,
+};
+
+mod fai;
+use fai::FaiRecord;
+
+/// `SequenceStorage` reading the targets from the indexed FASTA file (with `.fai`)
+///  - Only the path and the index of the FASTA file are kept in the memory,
+///    and each target is read from the file when the buffer is filled.
+///  - The FASTA file must not be compressed, moved, or modified after the storage is made.
+///    The file is checked when the storage is made or loaded,
+///    but `get_buffer` and `fill_buffer` panic if it cannot be read afterward.
+///  - Only for the `Reference` of "sigalign-core", not for the `Reference` of "sigalign" (`DynamicSequenceStorage`).
+///    The bases are read from the file as they are, so the preprocessing of the `ReferenceBuilder`
+///    (e.g., to uppercase or to ignore the bases) cannot be applied to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedFastaStorage {
+    fasta_file_path: PathBuf,
+    target_count: usize,
+    concatenated_label: String,
+    label_index: Vec<usize>,
+    // Records of the `.fai`
+    sequence_lengths: Vec<u64>,
+    sequence_offsets: Vec<u64>,
+    line_bases: Vec<u64>,
+    line_widths: Vec<u64>,
+}
+/// Buffer of `IndexedFastaStorage` with the opened FASTA file.
+pub struct IndexedFastaBuffer {
+    fasta_file: File,
+    raw_buffer: Vec<u8>,
+    sequence: Vec<u8>,
+}
+
+// Sequence Storage
+impl SequenceStorage for IndexedFastaStorage {
+    type Buffer = IndexedFastaBuffer;
+
+    fn num_targets(&self) -> u32 {
+        self.target_count as u32
+    }
+    /// ⚠️ Panics if the FASTA file cannot be opened.
+    fn get_buffer(&self) -> Self::Buffer {
+        let fasta_file = File::open(&self.fasta_file_path).unwrap_or_else(|err| {
+            panic!("Failed to open the indexed FASTA file {:?}: {}", self.fasta_file_path, err)
+        });
+        IndexedFastaBuffer {
+            fasta_file,
+            raw_buffer: Vec::new(),
+            sequence: Vec::new(),
+        }
+    }
+    /// ⚠️ Panics if the FASTA file cannot be read.
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        self.read_sequence(target_index as usize, buffer).unwrap_or_else(|err| {
+            panic!("Failed to read the target {} from {:?}: {}", target_index, self.fasta_file_path, err)
+        });
+    }
+}
+
+impl SequenceBuffer for IndexedFastaBuffer {
+    fn buffered_sequence(&self) -> &[u8] {
+        &self.sequence
+    }
+}
+
+impl IndexedFastaStorage {
+    /// Make the storage from the FASTA file.
+    ///  - The index is read from the `.fai` next to the FASTA file (e.g., `ref.fa.fai` for `ref.fa`) if it exists.
+    ///  - Otherwise, the FASTA file is scanned to index it. The `.fai` file is not written.
+    ///  - The absolute path of the FASTA file is kept, so the storage does not depend on the working directory.
+    pub fn new<P>(fasta_file_path: P) -> Result<Self, Error> where
+        P: AsRef<Path>,
+    {
+        let fasta_file_path = std::fs::canonicalize(fasta_file_path)?;
+        let mut fai_file_path = fasta_file_path.clone().into_os_string();
+        fai_file_path.push(".fai");
+        let fai_file_path = PathBuf::from(fai_file_path);
+
+        let fai_records = if fai_file_path.exists() {
+            fai::read_fai(BufReader::new(File::open(&fai_file_path)?))?
+        } else {
+            fai::index_fasta(BufReader::new(File::open(&fasta_file_path)?))?
+        };
+        let storage = Self::from_fai_records(fasta_file_path, fai_records);
+        storage.check_fasta_file()?;
+        Ok(storage)
+    }
+    fn from_fai_records(fasta_file_path: PathBuf, fai_records: Vec<FaiRecord>) -> Self {
+        let target_count = fai_records.len();
+        let mut concatenated_label = String::new();
+        let mut label_index = Vec::with_capacity(target_count + 1);
+        label_index.push(0);
+        let mut sequence_lengths = Vec::with_capacity(target_count);
+        let mut sequence_offsets = Vec::with_capacity(target_count);
+        let mut line_bases = Vec::with_capacity(target_count);
+        let mut line_widths = Vec::with_capacity(target_count);
+        for record in fai_records {
+            concatenated_label.push_str(&record.name);
+            label_index.push(concatenated_label.len());
+            sequence_lengths.push(record.length);
+            sequence_offsets.push(record.offset);
+            line_bases.push(record.line_bases);
+            line_widths.push(record.line_width);
+        }
+        Self {
+            fasta_file_path,
+            target_count,
+            concatenated_label,
+            label_index,
+            sequence_lengths,
+            sequence_offsets,
+            line_bases,
+            line_widths,
+        }
+    }
+    pub fn get_fasta_file_path(&self) -> &Path {
+        &self.fasta_file_path
+    }
+    pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        let mut buffer = self.get_buffer();
+        self.fill_buffer(target_index, &mut buffer);
+        Some(buffer.sequence)
+    }
+    pub fn get_total_length(&self) -> u64 {
+        self.sequence_lengths.iter().sum()
+    }
+    /// Remove all labels
+    /// !Cannot be undone
+    pub fn remove_labels(&mut self) {
+        self.concatenated_label = String::new();
+        self.label_index = vec![0; self.target_count+1];
+    }
+
+    // Check that the index is consistent, and that the FASTA file is long enough for all records.
+    fn check_fasta_file(&self) -> Result<(), Error> {
+        let index_is_consistent = self.label_index.len() == self.target_count + 1
+            && [&self.sequence_lengths, &self.sequence_offsets, &self.line_bases, &self.line_widths]
+                .iter().all(|records| records.len() == self.target_count)
+            && (0..self.target_count).all(|target_index| {
+                self.sequence_lengths[target_index] == 0 || self.line_bases[target_index] != 0
+            });
+        if !index_is_consistent {
+            return Err(Error::new(ErrorKind::InvalidData, "The index of the FASTA file is invalid"))
+        }
+        let file_length = std::fs::metadata(&self.fasta_file_path)?.len();
+        for target_index in 0..self.target_count {
+            let end = self.sequence_offsets[target_index].saturating_add(self.span_of_target(target_index));
+            if end > file_length {
+                return Err(Error::new(ErrorKind::InvalidData, "The FASTA file does not match the index"))
+            }
+        }
+        Ok(())
+    }
+    // Bytes from the first base to the last base (including the line terminators between them)
+    fn span_of_target(&self, target_index: usize) -> u64 {
+        let sequence_length = self.sequence_lengths[target_index];
+        if sequence_length == 0 {
+            return 0
+        }
+        let line_bases = self.line_bases[target_index];
+        let line_width = self.line_widths[target_index];
+        let last_base = sequence_length - 1;
+        (last_base / line_bases).saturating_mul(line_width).saturating_add(last_base % line_bases + 1)
+    }
+    fn read_sequence(&self, target_index: usize, buffer: &mut IndexedFastaBuffer) -> Result<(), Error> {
+        let sequence_length = self.sequence_lengths[target_index];
+        buffer.sequence.clear();
+        if sequence_length == 0 {
+            return Ok(())
+        }
+        let span = self.span_of_target(target_index);
+
+        buffer.raw_buffer.resize(span as usize, 0);
+        buffer.fasta_file.seek(SeekFrom::Start(self.sequence_offsets[target_index]))?;
+        buffer.fasta_file.read_exact(&mut buffer.raw_buffer)?;
+        buffer.sequence.extend(
+            buffer.raw_buffer.iter().filter(|&&v| v != b'\n' && v != b'\r')
+        );
+        if buffer.sequence.len() as u64 != sequence_length {
+            return Err(Error::new(ErrorKind::InvalidData, "The FASTA file does not match the index"))
+        }
+        Ok(())
+    }
+}
+
+mod extensions;
@@ -1 +1,4 @@
-pub mod in_memory;
\ No newline at end of file
+pub mod in_memory;
+pub mod packed_nucleotide;
+pub mod indexed_fasta;
+pub mod block_compressed;pub mod dynamic_sequence_storage;
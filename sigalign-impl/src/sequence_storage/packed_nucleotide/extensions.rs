@@ -0,0 +1,102 @@
+use std::io::{Read, Write, Error, ErrorKind};
+
+use capwriter::{Save, Load};
+
+use sigalign_core::reference::extensions::{
+    Serialize,
+    EstimateSize,
+    LabelStorage,
+};
+use crate::core::{EndianType, ReadBytesExt, WriteBytesExt};
+use super::PackedNucleotideStorage;
+
+//  - Serialize
+impl Serialize for PackedNucleotideStorage {
+    fn save_to<W>(&self, mut writer: W) -> Result<(), Error> where
+        W: Write
+    {
+        writer.write_u64::<EndianType>(self.target_count as u64)?;
+        writer.write_u64::<EndianType>(self.total_length as u64)?;
+        self.packed_sequence.save_to(&mut writer)?;
+        self.sequence_index.save_to(&mut writer)?;
+        self.exception_positions.save_to(&mut writer)?;
+        self.exception_bases.save_to(&mut writer)?;
+        self.lowercase_run_starts.save_to(&mut writer)?;
+        self.lowercase_run_ends.save_to(&mut writer)?;
+        self.concatenated_label.as_bytes().save_to(&mut writer)?;
+        self.label_index.save_to(&mut writer)?;
+        Ok(())
+    }
+    fn load_from<R>(mut reader: R) -> Result<Self, Error> where
+        R: Read,
+        Self: Sized,
+    {
+        let target_count = reader.read_u64::<EndianType>()? as usize;
+        let total_length = reader.read_u64::<EndianType>()? as usize;
+        let packed_sequence = Vec::load_from(&mut reader)?;
+        let sequence_index = Vec::load_from(&mut reader)?;
+        let exception_positions = Vec::load_from(&mut reader)?;
+        let exception_bases = Vec::load_from(&mut reader)?;
+        let lowercase_run_starts: Vec<u64> = Vec::load_from(&mut reader)?;
+        let lowercase_run_ends: Vec<u64> = Vec::load_from(&mut reader)?;
+        if lowercase_run_starts.len() != lowercase_run_ends.len() {
+            return Err(ErrorKind::InvalidData.into())
+        }
+        let concatenated_label = match String::from_utf8(Vec::<u8>::load_from(&mut reader)?) {
+            Ok(v) => v,
+            Err(_) => return Err(ErrorKind::InvalidData.into()),
+        };
+        let label_index = Vec::load_from(&mut reader)?;
+        Ok(Self {
+            target_count,
+            packed_sequence,
+            total_length,
+            sequence_index,
+            exception_positions,
+            exception_bases,
+            lowercase_run_starts,
+            lowercase_run_ends,
+            concatenated_label,
+            label_index,
+        })
+    }
+}
+
+//  - EstimateSize
+impl EstimateSize for PackedNucleotideStorage {
+    fn serialized_size(&self) -> usize {
+        // target_count, total_length
+        2 * std::mem::size_of::<u64>()
+        // packed_sequence
+        + self.packed_sequence.to_be_saved_size()
+        // sequence_index
+        + self.sequence_index.to_be_saved_size()
+        // exceptions
+        + self.exception_positions.to_be_saved_size()
+        + self.exception_bases.to_be_saved_size()
+        // case mask
+        + self.lowercase_run_starts.to_be_saved_size()
+        + self.lowercase_run_ends.to_be_saved_size()
+        // concatenated_label
+        + self.concatenated_label.as_bytes().to_be_saved_size()
+        // label_index
+        + self.label_index.to_be_saved_size()
+    }
+}
+//  - Label Storage
+impl LabelStorage for PackedNucleotideStorage {
+    fn label_of_target_unchecked(&self, target_index: u32) -> String {
+        self.concatenated_label[
+            self.label_index[target_index as usize]
+            ..self.label_index[target_index as usize +1]
+        ].to_string()
+    }
+}
+impl PackedNucleotideStorage {
+    pub fn get_label_safely(&self, target_index: u32) -> Option<String> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        Some(self.label_of_target_unchecked(target_index))
+    }
+}
@@ -0,0 +1,239 @@
+use std::{borrow::Cow, io::Read, str::Utf8Error};
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    SequenceBuffer,
+    extensions::LabelStorage,
+};
+use sigalign_utils::sequence_reader::{
+    SeqRecord, IdRecord,
+    fasta::FastaReader,
+    decompress::get_gzip_decoder,
+};
+use super::in_memory::InMemoryStorage;
+
+/// `SequenceStorage` packing the nucleotides into 2 bits
+///  - Uses a quarter of the memory of `InMemoryStorage` for the references of A, C, G and T.
+///  - The lowercase bases are packed as the uppercase, and their case is kept as the runs of lowercase (case mask).
+///    Therefore, the soft-masked regions take 16 bytes per run, not per base.
+///  - The other bases (e.g., N, or IUPAC codes) are kept in the list of exceptions.
+///  - The sequences are restored as they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedNucleotideStorage {
+    target_count: usize,
+    // Four bases in one byte from the lowest bits
+    packed_sequence: Vec<u8>,
+    total_length: usize,
+    sequence_index: Vec<usize>,
+    // Sorted positions in the concatenated sequence
+    exception_positions: Vec<u64>,
+    exception_bases: Vec<u8>,
+    // Sorted and not overlapping runs of lowercase in the concatenated sequence ([start, end))
+    lowercase_run_starts: Vec<u64>,
+    lowercase_run_ends: Vec<u64>,
+    concatenated_label: String,
+    label_index: Vec<usize>,
+}
+/// Buffer of `PackedNucleotideStorage` holding the decoded sequence of a target.
+pub struct PackedNucleotideBuffer {
+    sequence: Vec<u8>,
+}
+
+const BASES_PER_BYTE: usize = 4;
+const BASE_OF_CODE: [u8; 4] = [b'A', b'C', b'G', b'T'];
+// Code of the exceptions in the packed sequence
+const EXCEPTION_CODE: u8 = 0;
+
+#[inline]
+fn code_of_base(base: u8) -> Option<u8> {
+    match base {
+        b'A' => Some(0),
+        b'C' => Some(1),
+        b'G' => Some(2),
+        b'T' => Some(3),
+        _ => None,
+    }
+}
+
+// Sequence Storage
+impl SequenceStorage for PackedNucleotideStorage {
+    type Buffer = PackedNucleotideBuffer;
+
+    fn num_targets(&self) -> u32 {
+        self.target_count as u32
+    }
+    fn get_buffer(&self) -> Self::Buffer {
+        PackedNucleotideBuffer::new()
+    }
+    fn fill_buffer(&self, target_index: u32, buffer: &mut Self::Buffer) {
+        let start = self.sequence_index[target_index as usize];
+        let end = self.sequence_index[target_index as usize + 1];
+        buffer.sequence.clear();
+        self.decode_into(start, end, &mut buffer.sequence);
+    }
+    fn get_concatenated_sequence_with_boundaries_of_targets(&self) -> (
+        Cow<'_, [u8]>,
+        Vec<u64>,
+    ) {
+        let mut concatenated_sequence = Vec::with_capacity(self.total_length);
+        self.decode_into(0, self.total_length, &mut concatenated_sequence);
+        let boundaries = self.sequence_index.iter().map(|x| *x as u64).collect();
+        (Cow::Owned(concatenated_sequence), boundaries)
+    }
+}
+
+impl SequenceBuffer for PackedNucleotideBuffer {
+    fn buffered_sequence(&self) -> &[u8] {
+        &self.sequence
+    }
+}
+
+impl PackedNucleotideStorage {
+    pub fn new() -> Self {
+        Self {
+            target_count: 0,
+            packed_sequence: Vec::new(),
+            total_length: 0,
+            sequence_index: vec![0],
+            exception_positions: Vec::new(),
+            exception_bases: Vec::new(),
+            lowercase_run_starts: Vec::new(),
+            lowercase_run_ends: Vec::new(),
+            concatenated_label: String::new(),
+            label_index: vec![0],
+        }
+    }
+    pub fn add_target(
+        &mut self,
+        label: &str,
+        sequence: &[u8],
+    ) {
+        self.target_count += 1;
+        self.push_sequence(sequence);
+        self.concatenated_label.push_str(label);
+        self.label_index.push(self.concatenated_label.len());
+    }
+    pub fn add_fasta<R: Read>(&mut self, reader: R) -> Result<(), Utf8Error> {
+        let mut fasta_reader = FastaReader::new(reader);
+        let mut seq_buffer = Vec::new();
+        while let Some(mut record) = fasta_reader.next() {
+            self.target_count += 1;
+            seq_buffer.clear();
+            record.extend_seq_buf(&mut seq_buffer);
+            self.push_sequence(&seq_buffer);
+            record.extend_id_string(&mut self.concatenated_label)?;
+            self.label_index.push(self.concatenated_label.len());
+        }
+        Ok(())
+    }
+    pub fn add_gzip_fasta<R: Read>(&mut self, reader: R) -> Result<(), Utf8Error> {
+        let decomp_reader = get_gzip_decoder(reader);
+        self.add_fasta(decomp_reader)
+    }
+    pub fn get_sequence_safely(&self, target_index: u32) -> Option<Vec<u8>> {
+        if target_index as usize >= self.target_count {
+            return None
+        }
+        let mut buffer = self.get_buffer();
+        self.fill_buffer(target_index, &mut buffer);
+        Some(buffer.sequence)
+    }
+    pub fn get_total_length(&self) -> u64 {
+        self.total_length as u64
+    }
+    /// Number of the bases that are not packed (other than A, C, G and T regardless of the case)
+    pub fn get_exception_count(&self) -> usize {
+        self.exception_positions.len()
+    }
+    /// Number of the runs of lowercase bases
+    pub fn get_lowercase_run_count(&self) -> usize {
+        self.lowercase_run_starts.len()
+    }
+    /// Remove all labels
+    /// !Cannot be undone
+    pub fn remove_labels(&mut self) {
+        self.concatenated_label = String::new();
+        self.label_index = vec![0; self.target_count+1];
+    }
+
+    fn push_sequence(&mut self, sequence: &[u8]) {
+        let mut position = self.total_length;
+        self.packed_sequence.resize((position + sequence.len()).div_ceil(BASES_PER_BYTE), 0);
+        for &base in sequence {
+            if base.is_ascii_lowercase() {
+                self.push_lowercase_position(position as u64);
+            }
+            let base = base.to_ascii_uppercase();
+            let code = match code_of_base(base) {
+                Some(code) => code,
+                None => {
+                    self.exception_positions.push(position as u64);
+                    self.exception_bases.push(base);
+                    EXCEPTION_CODE
+                },
+            };
+            self.packed_sequence[position / BASES_PER_BYTE] |= code << (2 * (position % BASES_PER_BYTE));
+            position += 1;
+        }
+        self.total_length = position;
+        self.sequence_index.push(position);
+    }
+    fn push_lowercase_position(&mut self, position: u64) {
+        match self.lowercase_run_ends.last_mut() {
+            Some(run_end) if *run_end == position => *run_end += 1,
+            _ => {
+                self.lowercase_run_starts.push(position);
+                self.lowercase_run_ends.push(position + 1);
+            },
+        }
+    }
+    /// Decode `[start, end)` of the concatenated sequence and append to the `buffer`.
+    fn decode_into(&self, start: usize, end: usize, buffer: &mut Vec<u8>) {
+        let offset = buffer.len();
+        buffer.extend((start..end).map(|position| {
+            let code = (self.packed_sequence[position / BASES_PER_BYTE] >> (2 * (position % BASES_PER_BYTE))) & 0b11;
+            BASE_OF_CODE[code as usize]
+        }));
+        // Restore the exceptions
+        let first_exception = self.exception_positions.partition_point(|&v| v < start as u64);
+        self.exception_positions[first_exception..].iter()
+            .zip(&self.exception_bases[first_exception..])
+            .take_while(|(&position, _)| position < end as u64)
+            .for_each(|(&position, &base)| {
+                buffer[offset + position as usize - start] = base;
+            });
+        // Restore the lowercase
+        let first_run = self.lowercase_run_ends.partition_point(|&v| v <= start as u64);
+        self.lowercase_run_starts[first_run..].iter()
+            .zip(&self.lowercase_run_ends[first_run..])
+            .take_while(|(&run_start, _)| run_start < end as u64)
+            .for_each(|(&run_start, &run_end)| {
+                let run_start = run_start.max(start as u64) as usize;
+                let run_end = run_end.min(end as u64) as usize;
+                buffer[offset + run_start - start..offset + run_end - start].make_ascii_lowercase();
+            });
+    }
+}
+
+impl PackedNucleotideBuffer {
+    pub fn new() -> Self {
+        Self {
+            sequence: Vec::new(),
+        }
+    }
+}
+
+mod extensions;
+
+impl From<InMemoryStorage> for PackedNucleotideStorage {
+    fn from(in_memory_storage: InMemoryStorage) -> Self {
+        let mut packed_storage = Self::new();
+        let mut buffer = in_memory_storage.get_buffer();
+        for target_index in 0..in_memory_storage.num_targets() {
+            in_memory_storage.fill_buffer(target_index, &mut buffer);
+            let label = in_memory_storage.label_of_target_unchecked(target_index);
+            packed_storage.add_target(&label, buffer.buffered_sequence());
+        }
+        packed_storage
+    }
+}
@@ -354,6 +354,7 @@ impl PyAlignment {
                     case: match v.operation {
                         AlignmentOperation::Match => "M".to_string(),
                         AlignmentOperation::Subst => "S".to_string(),
+                        AlignmentOperation::AmbiguousMatch => "A".to_string(),
                         AlignmentOperation::Insertion => "I".to_string(),
                         AlignmentOperation::Deletion => "D".to_string(),
                     },
@@ -0,0 +1,154 @@
+/// Genetic code tables of NCBI (https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GeneticCode {
+    /// Table 1
+    #[default]
+    Standard,
+    /// Table 2
+    VertebrateMitochondrial,
+    /// Table 3
+    YeastMitochondrial,
+    /// Table 4
+    MoldMitochondrial,
+    /// Table 5
+    InvertebrateMitochondrial,
+    /// Table 6
+    CiliateNuclear,
+    /// Table 9
+    EchinodermMitochondrial,
+    /// Table 10
+    EuplotidNuclear,
+    /// Table 11
+    BacterialAndPlastid,
+    /// Table 12
+    AlternativeYeastNuclear,
+    /// Table 13
+    AscidianMitochondrial,
+    /// Table 14
+    AlternativeFlatwormMitochondrial,
+}
+
+/// Reading frames in the order of the six-frame translation.
+///  - 1, 2, 3 for the forward strand and -1, -2, -3 for the reverse complementary.
+pub const FRAMES: [i8; 6] = [1, 2, 3, -1, -2, -3];
+
+/// Amino acid of the codon that is not translatable (e.g., containing `N`).
+pub const UNKNOWN_AMINO_ACID: u8 = b'X';
+
+impl GeneticCode {
+    /// Get the genetic code from the table ID of NCBI.
+    pub fn from_ncbi_table_id(table_id: u8) -> Option<Self> {
+        let genetic_code = match table_id {
+            1 => Self::Standard,
+            2 => Self::VertebrateMitochondrial,
+            3 => Self::YeastMitochondrial,
+            4 => Self::MoldMitochondrial,
+            5 => Self::InvertebrateMitochondrial,
+            6 => Self::CiliateNuclear,
+            9 => Self::EchinodermMitochondrial,
+            10 => Self::EuplotidNuclear,
+            11 => Self::BacterialAndPlastid,
+            12 => Self::AlternativeYeastNuclear,
+            13 => Self::AscidianMitochondrial,
+            14 => Self::AlternativeFlatwormMitochondrial,
+            _ => return None,
+        };
+        Some(genetic_code)
+    }
+    /// Table ID of NCBI
+    pub fn ncbi_table_id(&self) -> u8 {
+        match self {
+            Self::Standard => 1,
+            Self::VertebrateMitochondrial => 2,
+            Self::YeastMitochondrial => 3,
+            Self::MoldMitochondrial => 4,
+            Self::InvertebrateMitochondrial => 5,
+            Self::CiliateNuclear => 6,
+            Self::EchinodermMitochondrial => 9,
+            Self::EuplotidNuclear => 10,
+            Self::BacterialAndPlastid => 11,
+            Self::AlternativeYeastNuclear => 12,
+            Self::AscidianMitochondrial => 13,
+            Self::AlternativeFlatwormMitochondrial => 14,
+        }
+    }
+    /// Amino acids of 64 codons in the order of `TTT`, `TTC`, `TTA`, `TTG`, `TCT`, ..., `GGG`.
+    fn amino_acids(&self) -> &'static [u8; 64] {
+        match self {
+            Self::Standard | Self::BacterialAndPlastid => b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::VertebrateMitochondrial => b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+            Self::YeastMitochondrial => b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::MoldMitochondrial => b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::InvertebrateMitochondrial => b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+            Self::CiliateNuclear => b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::EchinodermMitochondrial => b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+            Self::EuplotidNuclear => b"FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::AlternativeYeastNuclear => b"FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+            Self::AscidianMitochondrial => b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+            Self::AlternativeFlatwormMitochondrial => b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        }
+    }
+    /// Translate a codon to the amino acid.
+    ///  - Case-insensitive, and `U` is treated as `T`.
+    ///  - The codon containing the other characters is translated to `UNKNOWN_AMINO_ACID`.
+    pub fn translate_codon(&self, codon: &[u8; 3]) -> u8 {
+        let mut index = 0;
+        for base in codon {
+            let base_index = match base {
+                b'T' | b't' | b'U' | b'u' => 0,
+                b'C' | b'c' => 1,
+                b'A' | b'a' => 2,
+                b'G' | b'g' => 3,
+                _ => return UNKNOWN_AMINO_ACID,
+            };
+            index = index * 4 + base_index;
+        }
+        self.amino_acids()[index]
+    }
+}
+
+/// Translate a DNA sequence from the first base.
+///  - The remained bases shorter than a codon are ignored.
+pub fn translate_dna_sequence(sequence: &[u8], genetic_code: GeneticCode) -> Vec<u8> {
+    sequence.chunks_exact(3).map(|codon| {
+        genetic_code.translate_codon(&[codon[0], codon[1], codon[2]])
+    }).collect()
+}
+
+/// Translate a DNA sequence in the reading frame (one of `FRAMES`).
+///  - The negative frames are translated from the reverse complement of the sequence.
+pub fn translate_dna_sequence_in_frame(sequence: &[u8], frame: i8, genetic_code: GeneticCode) -> Vec<u8> {
+    let offset = offset_of_frame(frame).min(sequence.len());
+    if frame > 0 {
+        translate_dna_sequence(&sequence[offset..], genetic_code)
+    } else {
+        let reverse_complement = super::reverse_complementary::reverse_complement_of_dna_sequence(sequence);
+        translate_dna_sequence(&reverse_complement[offset..], genetic_code)
+    }
+}
+
+/// Translate a DNA sequence in six reading frames (in the order of `FRAMES`).
+pub fn six_frame_translation_of_dna_sequence(sequence: &[u8], genetic_code: GeneticCode) -> Vec<(i8, Vec<u8>)> {
+    FRAMES.iter().map(|frame| {
+        (*frame, translate_dna_sequence_in_frame(sequence, *frame, genetic_code))
+    }).collect()
+}
+
+/// Transform the range of amino acids in the translated frame to the range of bases in the original DNA sequence.
+///  - Both ranges are half-open (start, end), and the returned range is counted from the start of the original sequence.
+pub fn nucleotide_range_of_frame(frame: i8, sequence_length: u32, amino_acid_range: (u32, u32)) -> (u32, u32) {
+    let offset = offset_of_frame(frame) as u32;
+    let start = offset + amino_acid_range.0 * 3;
+    let end = offset + amino_acid_range.1 * 3;
+    if frame > 0 {
+        (start, end)
+    } else {
+        (sequence_length - end, sequence_length - start)
+    }
+}
+
+#[inline]
+fn offset_of_frame(frame: i8) -> usize {
+    assert!(FRAMES.contains(&frame), "Frame must be one of 1, 2, 3, -1, -2, -3.");
+    frame.unsigned_abs() as usize - 1
+}
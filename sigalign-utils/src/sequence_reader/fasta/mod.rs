@@ -9,6 +9,8 @@ use super::{
     SeqRecord,
     IdRecord,
     IdRefRecord,
+    DescRefRecord,
+    SeqReader,
 };
 
 /// The reader of FASTA formatted file
@@ -46,6 +48,34 @@ impl FastaReader<File> {
     }
 }
 
+impl<R: Read> SeqReader for FastaReader<R> {
+    const HAS_QUALITY: bool = false;
+    fn fill_next_record(
+        &mut self,
+        id_buf: &mut String,
+        seq_buf: &mut Vec<u8>,
+        qual_buf: &mut Vec<u8>,
+    ) -> Option<Result<(), std::io::Error>> {
+        let record = match self.reader.next()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            )),
+        };
+        id_buf.clear();
+        match record.id() {
+            Ok(id) => id_buf.push_str(id),
+            Err(error) => return Some(Err(
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            )),
+        }
+        seq_buf.clear();
+        record.seq_lines().for_each(|s| seq_buf.extend_from_slice(s));
+        qual_buf.clear();
+        Some(Ok(()))
+    }
+}
+
 impl<'a> SeqRecord for FastaRecord<'a> {
     fn extend_seq_buf(&mut self, buf: &mut Vec<u8>) {
         self.record.seq_lines().for_each(|s| buf.extend_from_slice(s));
@@ -70,3 +100,12 @@ impl<'a> IdRefRecord for FastaRecord<'a> {
         self.record.id()
     }
 }
+
+impl<'a> DescRefRecord for FastaRecord<'a> {
+    fn desc(&self) -> Option<&[u8]> {
+        self.record.desc_bytes()
+    }
+    fn desc_str(&self) -> Option<Result<&str, Utf8Error>> {
+        self.record.desc()
+    }
+}
@@ -13,8 +13,10 @@ use seq_io::fastq::{
 use super::{
     SeqRecord,
     SeqRefRecord,
+    QualRefRecord,
     IdRecord,
     IdRefRecord,
+    SeqReader,
 };
 
 /// The reader of FASTA formatted file
@@ -57,6 +59,35 @@ impl<'a> FastqReader<&'a [u8]> {
     }
 }
 
+impl<R: Read> SeqReader for FastqReader<R> {
+    const HAS_QUALITY: bool = true;
+    fn fill_next_record(
+        &mut self,
+        id_buf: &mut String,
+        seq_buf: &mut Vec<u8>,
+        qual_buf: &mut Vec<u8>,
+    ) -> Option<Result<(), std::io::Error>> {
+        let record = match self.reader.next()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            )),
+        };
+        id_buf.clear();
+        match record.id() {
+            Ok(id) => id_buf.push_str(id),
+            Err(error) => return Some(Err(
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            )),
+        }
+        seq_buf.clear();
+        seq_buf.extend_from_slice(record.seq());
+        qual_buf.clear();
+        qual_buf.extend_from_slice(record.qual());
+        Some(Ok(()))
+    }
+}
+
 impl<'a> SeqRecord for FastqRecord<'a> {
     fn extend_seq_buf(&mut self, buf: &mut Vec<u8>) {
         buf.extend_from_slice(self.record.seq());
@@ -69,6 +100,12 @@ impl<'a> SeqRefRecord for FastqRecord<'a> {
     }
 }
 
+impl<'a> QualRefRecord for FastqRecord<'a> {
+    fn qual(&self) -> &[u8] {
+        self.record.qual()
+    }
+}
+
 impl<'a> IdRecord for FastqRecord<'a> {
     fn extend_id_buf(&mut self, buf: &mut Vec<u8>) {
         buf.extend(self.record.id_bytes());
@@ -1,6 +1,6 @@
 //! Sequence reader module
 //! This module provides a generic interface for reading sequence files.
-use std::str::Utf8Error;
+use std::{io, str::Utf8Error};
 
 pub mod fasta;
 pub mod fastq;
@@ -13,6 +13,9 @@ pub trait SeqRecord {
 pub trait SeqRefRecord {
     fn seq(&self) -> &[u8];
 }
+pub trait QualRefRecord {
+    fn qual(&self) -> &[u8];
+}
 pub trait IdRecord {
     fn extend_id_buf(&mut self, buf: &mut Vec<u8>);
     fn extend_id_string(&mut self, buf: &mut String) -> Result<(), Utf8Error>;
@@ -21,3 +24,25 @@ pub trait IdRefRecord {
     fn id(&self) -> &[u8];
     fn id_str(&self) -> Result<&str, Utf8Error>;
 }
+/// Description of the header after the ID. None if the header has only the ID.
+pub trait DescRefRecord {
+    fn desc(&self) -> Option<&[u8]>;
+    fn desc_str(&self) -> Option<Result<&str, Utf8Error>>;
+}
+
+/// Reader that fills the reusable buffers with the next record.
+///  - Implemented for both `FastaReader` and `FastqReader`.
+pub trait SeqReader {
+    /// Whether the records have the quality string (FASTQ).
+    const HAS_QUALITY: bool;
+    /// Clear and fill the buffers with the ID, sequence and quality of the next record.
+    ///  - Returns `None` at the end of the input.
+    ///  - Unlike the `next()` of the readers, the error in parsing is not skipped.
+    ///  - `qual_buf` is left empty if the records have no quality string.
+    fn fill_next_record(
+        &mut self,
+        id_buf: &mut String,
+        seq_buf: &mut Vec<u8>,
+        qual_buf: &mut Vec<u8>,
+    ) -> Option<Result<(), io::Error>>;
+}
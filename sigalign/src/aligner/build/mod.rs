@@ -1,9 +1,9 @@
 use thiserror::Error;
 
 use sigalign_core::aligner::{
-    AlignmentRegulator, RegulatorError,
+    AlignmentRegulator, RegulatorError, SubstitutionMatrix,
 };
-use super::{Aligner, DynamicAligner};
+use super::{Aligner, DynamicAligner, StrandMode};
 
 const MINIMUM_PATTERN_SIZE: u32 = 4;
 
@@ -14,6 +14,10 @@ pub enum AlignerBuildError {
     InvalidRegulator(#[from] RegulatorError),
     #[error("Cutoff is too low to detect the pattern.")]
     LowCutoff,
+    #[error("At least one cutoff is required.")]
+    EmptyCutoffs,
+    #[error("Cutoffs cannot be ordered from strict to lenient. Higher minimum length has to be paired with lower maximum penalty per length.")]
+    UnorderedCutoffs,
 }
 
 impl Aligner {
@@ -41,6 +45,198 @@ impl Aligner {
         Ok(Self {
             regulator,
             dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
+        })
+    }
+    /// Make a new `Aligner` using the substitution matrix instead of the single mismatch penalty.
+    pub fn new_with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        min_length: u32,
+        max_penalty_per_length: f32,
+    ) -> Result<Self, AlignerBuildError> {
+        let regulator = AlignmentRegulator::new_with_substitution_matrix(
+            substitution_matrix,
+            gap_open_penalty,
+            gap_extend_penalty,
+            min_length,
+            max_penalty_per_length,
+        )?;
+        if regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+            return Err(AlignerBuildError::LowCutoff);
+        }
+
+        let dynamic_aligner = DynamicAligner::new_local(regulator.clone());
+
+        Ok(Self {
+            regulator,
+            dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
+        })
+    }
+    /// Make a new `Aligner` using the two-piece affine gap penalty.
+    ///  - The penalty of a gap of length `L` is `min(o1 + e1*L, o2 + e2*L)`.
+    ///  - The second piece (`o2 > o1`, `e2 < e1`) makes long gaps cheaper.
+    pub fn new_with_two_piece_gap(
+        mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        second_gap_open_penalty: u32,
+        second_gap_extend_penalty: u32,
+        min_length: u32,
+        max_penalty_per_length: f32,
+    ) -> Result<Self, AlignerBuildError> {
+        let regulator = AlignmentRegulator::new_with_two_piece_gap(
+            mismatch_penalty,
+            gap_open_penalty,
+            gap_extend_penalty,
+            second_gap_open_penalty,
+            second_gap_extend_penalty,
+            min_length,
+            max_penalty_per_length,
+        )?;
+        if regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+            return Err(AlignerBuildError::LowCutoff);
+        }
+
+        let dynamic_aligner = DynamicAligner::new_local(regulator.clone());
+
+        Ok(Self {
+            regulator,
+            dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
+        })
+    }
+    /// Make a new `Aligner` with the mismatch penalty depending on the quality of the query base.
+    ///  - The mismatch at the base of Phred score `q` costs from `min_mismatch_penalty` (q=0) to `mismatch_penalty` (q>=40), linearly.
+    ///  - The qualities are given by `align_query_with_qualities`. Without them, `mismatch_penalty` is used.
+    pub fn new_with_quality_aware_mismatch(
+        mismatch_penalty: u32,
+        min_mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        min_length: u32,
+        max_penalty_per_length: f32,
+    ) -> Result<Self, AlignerBuildError> {
+        let regulator = AlignmentRegulator::new_with_quality_aware_mismatch(
+            mismatch_penalty,
+            min_mismatch_penalty,
+            gap_open_penalty,
+            gap_extend_penalty,
+            min_length,
+            max_penalty_per_length,
+        )?;
+        if regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+            return Err(AlignerBuildError::LowCutoff);
+        }
+
+        let dynamic_aligner = DynamicAligner::new_local(regulator.clone());
+
+        Ok(Self {
+            regulator,
+            dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
+        })
+    }
+    /// Make a new `Aligner` matching the IUPAC ambiguity codes (e.g., `R` matches `A` and `G`).
+    ///  - The ambiguous match costs `ambiguous_match_penalty` (smaller than `mismatch_penalty`, can be 0),
+    ///    and is recorded as `AlignmentOperation::AmbiguousMatch` in the result.
+    ///  - The patterns are still located by the exact match.
+    ///  - ⚠️ If `ambiguous_match_penalty` is 0, it is not guaranteed that all alignments satisfying the cutoff are found.
+    ///    The ambiguous matches of zero penalty do not count toward the pattern size,
+    ///    so the alignment whose every pattern has the ambiguous match can be missed.
+    ///  - The IUPAC codes are also complemented for the reverse complementary query (e.g., `R` <-> `Y`).
+    pub fn new_with_iupac_matching(
+        mismatch_penalty: u32,
+        ambiguous_match_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        min_length: u32,
+        max_penalty_per_length: f32,
+    ) -> Result<Self, AlignerBuildError> {
+        let regulator = AlignmentRegulator::new_with_iupac_matching(
+            mismatch_penalty,
+            ambiguous_match_penalty,
+            gap_open_penalty,
+            gap_extend_penalty,
+            min_length,
+            max_penalty_per_length,
+        )?;
+        if regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+            return Err(AlignerBuildError::LowCutoff);
+        }
+
+        let dynamic_aligner = DynamicAligner::new_local(regulator.clone());
+
+        Ok(Self {
+            regulator,
+            dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
+        })
+    }
+    /// Make a new `Aligner` that tries multiple cutoffs from strict to lenient.
+    ///  - `cutoffs` is a list of (minimum length, maximum penalty per length).
+    ///  - The cutoffs are sorted from strict (large minimum length and small maximum penalty per length) to lenient.
+    ///  - Each target is aligned with the strictest cutoff that produces alignments,
+    ///    and its index in the sorted cutoffs is recorded as `cutoff_tier` in the result.
+    ///  - The getters of cutoff (e.g., `get_minimum_aligned_length`) return the most lenient one.
+    pub fn new_chaining(
+        mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        cutoffs: &[(u32, f32)],
+    ) -> Result<Self, AlignerBuildError> {
+        if cutoffs.is_empty() {
+            return Err(AlignerBuildError::EmptyCutoffs);
+        }
+        let mut sorted_cutoffs = cutoffs.to_vec();
+        sorted_cutoffs.sort_by(|(a_min_length, a_max_ppl), (b_min_length, b_max_ppl)| {
+            if a_min_length == b_min_length {
+                a_max_ppl.partial_cmp(b_max_ppl).unwrap_or(std::cmp::Ordering::Equal)
+            } else {
+                b_min_length.cmp(a_min_length)
+            }
+        });
+        // The space for the alignment is allocated with the most lenient cutoff.
+        //  - Maximum penalty per length also has to be sorted.
+        if sorted_cutoffs.windows(2).any(|w| w[0].1 > w[1].1) {
+            return Err(AlignerBuildError::UnorderedCutoffs);
+        }
+
+        let sorted_regulators = sorted_cutoffs.into_iter().map(|(min_length, max_penalty_per_length)| {
+            let regulator = AlignmentRegulator::new(
+                mismatch_penalty,
+                gap_open_penalty,
+                gap_extend_penalty,
+                min_length,
+                max_penalty_per_length,
+            )?;
+            if regulator.get_pattern_size() < MINIMUM_PATTERN_SIZE {
+                return Err(AlignerBuildError::LowCutoff);
+            }
+            Ok(regulator)
+        }).collect::<Result<Vec<_>, AlignerBuildError>>()?;
+
+        let regulator = sorted_regulators.last().unwrap().clone();
+        let dynamic_aligner = DynamicAligner::new_local_chaining(sorted_regulators);
+
+        Ok(Self {
+            regulator,
+            dynamic_aligner,
+            strand_mode: StrandMode::default(),
+            metadata_in_results: false,
+            reverse_query_buffer: Vec::new(),
         })
     }
 }
@@ -0,0 +1,125 @@
+use sigalign_core::aligner::Aligner as RawAligner;
+use super::Aligner;
+use crate::{Reference, ReferenceCollection, ReferenceLoadError};
+use crate::results::{
+    AlignmentResult,
+    LabeledAlignmentResult,
+};
+
+impl Aligner {
+    /// Align a query to all shards of the `ReferenceCollection`.
+    ///  - The target indices of the result are the global indices of the collection.
+    ///  - The limit (`Aligner::set_limit`) is applied to the merged result of all shards.
+    ///  - Fails only if a lazy shard cannot be loaded.
+    ///  - Each lazy shard is loaded for each call. Use `align_queries_to_collection` to load it once for many queries.
+    pub fn align_query_to_collection<Q>(
+        &mut self,
+        collection: &ReferenceCollection,
+        query: Q,
+    ) -> Result<AlignmentResult, ReferenceLoadError> where
+        Q: AsRef<[u8]>,
+    {
+        let mut merged_alignment_results = self.align_queries_to_shards(collection, &[query], |_, alignment_result, _| {
+            alignment_result
+        })?;
+        Ok(merged_alignment_results.remove(0))
+    }
+    /// Align multiple queries to all shards of the `ReferenceCollection`.
+    ///  - Each lazy shard is loaded only once for all queries.
+    pub fn align_queries_to_collection<I>(
+        &mut self,
+        collection: &ReferenceCollection,
+        queries: &[I],
+    ) -> Result<Vec<AlignmentResult>, ReferenceLoadError> where
+        I: AsRef<[u8]>,
+    {
+        self.align_queries_to_shards(collection, queries, |_, alignment_result, _| {
+            alignment_result
+        })
+    }
+    /// Align a query to all shards of the `ReferenceCollection` and label the result.
+    ///  - The labels are taken from each shard, so the lazy shards are not loaded again.
+    pub fn align_query_labeled_to_collection<Q>(
+        &mut self,
+        collection: &ReferenceCollection,
+        query: Q,
+    ) -> Result<LabeledAlignmentResult, ReferenceLoadError> where
+        Q: AsRef<[u8]>,
+    {
+        let mut merged_alignment_results = self.align_queries_to_shards(collection, &[query], |aligner, alignment_result, reference| {
+            aligner.label_the_alignment_result(alignment_result, reference)
+        })?;
+        Ok(merged_alignment_results.remove(0))
+    }
+    // Align the queries to each shard in order, and merge the results of the shards.
+    //  - `to_result` converts the result of a shard while the shard is loaded.
+    fn align_queries_to_shards<I, R, F>(
+        &mut self,
+        collection: &ReferenceCollection,
+        queries: &[I],
+        mut to_result: F,
+    ) -> Result<Vec<R>, ReferenceLoadError> where
+        I: AsRef<[u8]>,
+        R: ResultOfShards,
+        F: FnMut(&Self, AlignmentResult, &Reference) -> R,
+    {
+        let mut merged_results: Vec<R> = queries.iter().map(|_| R::empty()).collect();
+        collection.try_for_each_shard(|reference, target_index_offset| {
+            let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
+            queries.iter().zip(merged_results.iter_mut()).for_each(|(query, merged_result)| {
+                let alignment_result = self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+                    dynamic_aligner.alignment(
+                        reference.as_ref(),
+                        &mut sequence_buffer,
+                        reference.get_full_sorted_target_indices(),
+                        query,
+                    )
+                });
+                let result = to_result(self, alignment_result, reference);
+                merged_result.merge_shard(result, target_index_offset);
+            });
+        })?;
+        if let Some(limit) = self.get_limit() {
+            merged_results.iter_mut().for_each(|merged_result| {
+                merged_result.truncate_alignments(limit as usize);
+            });
+        }
+        Ok(merged_results)
+    }
+}
+
+// Result that can be merged from the results of the shards.
+trait ResultOfShards {
+    fn empty() -> Self;
+    // Merge the result of the shard of which the first target has `target_index_offset`.
+    fn merge_shard(&mut self, result_of_shard: Self, target_index_offset: u32);
+    fn truncate_alignments(&mut self, limit: usize);
+}
+impl ResultOfShards for AlignmentResult {
+    fn empty() -> Self {
+        AlignmentResult(Vec::new())
+    }
+    fn merge_shard(&mut self, mut result_of_shard: Self, target_index_offset: u32) {
+        result_of_shard.0.iter_mut().for_each(|target_alignment_result| {
+            target_alignment_result.index += target_index_offset;
+        });
+        self.0.extend(result_of_shard.0);
+    }
+    fn truncate_alignments(&mut self, limit: usize) {
+        AlignmentResult::truncate_alignments(self, limit)
+    }
+}
+impl ResultOfShards for LabeledAlignmentResult {
+    fn empty() -> Self {
+        LabeledAlignmentResult(Vec::new())
+    }
+    fn merge_shard(&mut self, mut result_of_shard: Self, target_index_offset: u32) {
+        result_of_shard.0.iter_mut().for_each(|target_alignment_result| {
+            target_alignment_result.index += target_index_offset;
+        });
+        self.0.extend(result_of_shard.0);
+    }
+    fn truncate_alignments(&mut self, limit: usize) {
+        LabeledAlignmentResult::truncate_alignments(self, limit)
+    }
+}
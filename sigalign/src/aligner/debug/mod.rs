@@ -1,5 +1,7 @@
 use std::fmt::Debug;
 
+use sigalign_core::aligner::{AlignmentRegulator, SubstitutionMatrix};
+
 use super::{
     Aligner, DynamicAligner,
 };
@@ -9,6 +11,8 @@ impl Debug for Aligner {
         f.debug_struct("Aligner")
             .field("algorithm", &self.dynamic_aligner.algorithm_string())
             .field("regulator", &self.regulator)
+            .field("strand_mode", &self.strand_mode)
+            .field("metadata_in_results", &self.metadata_in_results)
             .finish()
     }
 }
@@ -26,15 +30,28 @@ impl DynamicAligner {
                 let limit = v.get_limit();
                 format!("SemiGlobalWithLimit({})", limit)
             },
+            Self::LocalChaining(v) => {
+                let tiers = v.get_regulators().len();
+                format!("LocalChaining({})", tiers)
+            },
+            Self::SemiGlobalChaining(v) => {
+                let tiers = v.get_regulators().len();
+                format!("SemiGlobalChaining({})", tiers)
+            },
         }
     }
 }
 
 impl Aligner {
     /// Get mismatch penalty
+    ///  - If the substitution matrix is used, the minimum penalty of the matrix.
     pub fn get_mismatch_penalty(&self) -> u32 {
         self.regulator.get_mismatch_penalty()
     }
+    /// Get substitution matrix
+    pub fn get_substitution_matrix(&self) -> Option<SubstitutionMatrix> {
+        self.regulator.get_substitution_matrix()
+    }
     /// Get gap-open penalty
     pub fn get_gap_open_penalty(&self) -> u32 {
         self.regulator.get_gap_open_penalty()
@@ -43,6 +60,18 @@ impl Aligner {
     pub fn get_gap_extend_penalty(&self) -> u32 {
         self.regulator.get_gap_extend_penalty()
     }
+    /// Get (gap-open, gap-extend) penalties of the second piece of the two-piece gap penalty
+    pub fn get_second_gap_penalties(&self) -> Option<(u32, u32)> {
+        self.regulator.get_second_gap_penalties()
+    }
+    /// Get mismatch penalty of the lowest quality (if the quality-aware mismatch penalty is used)
+    pub fn get_minimum_mismatch_penalty(&self) -> Option<u32> {
+        self.regulator.get_minimum_mismatch_penalty()
+    }
+    /// Get penalty of the ambiguous match (if the IUPAC matching is used)
+    pub fn get_ambiguous_match_penalty(&self) -> Option<u32> {
+        self.regulator.get_ambiguous_match_penalty()
+    }
     /// Get minimum aligned length
     pub fn get_minimum_aligned_length(&self) -> u32 {
         self.regulator.get_minimum_aligned_length()
@@ -55,4 +84,22 @@ impl Aligner {
     pub fn get_pattern_size(&self) -> u32 {
         self.regulator.get_pattern_size()
     }
+    /// Get the maximum occurrences of a pattern in the reference
+    pub fn get_pattern_occurrence_cap(&self) -> Option<u32> {
+        self.regulator.get_pattern_occurrence_cap()
+    }
+    /// Get cutoffs (minimum aligned length, maximum penalty per length) in the order of `cutoff_tier`
+    pub fn get_cutoffs(&self) -> Vec<(u32, f32)> {
+        match &self.dynamic_aligner {
+            DynamicAligner::LocalChaining(v) => regulators_to_cutoffs(v.get_regulators()),
+            DynamicAligner::SemiGlobalChaining(v) => regulators_to_cutoffs(v.get_regulators()),
+            _ => regulators_to_cutoffs(std::slice::from_ref(&self.regulator)),
+        }
+    }
+}
+
+fn regulators_to_cutoffs(regulators: &[AlignmentRegulator]) -> Vec<(u32, f32)> {
+    regulators.iter().map(|regulator| {
+        (regulator.get_minimum_aligned_length(), regulator.get_maximum_penalty_per_length())
+    }).collect()
 }
@@ -6,9 +6,11 @@ use sigalign_core::{
     aligner::{
         Aligner as RawAligner,
         LocalAligner, LocalWithLimitAligner,
-        SemiGlobalAligner, SemiGlobalWithLimitAligner, AlignmentRegulator,
+        SemiGlobalAligner, SemiGlobalWithLimitAligner,
+        LocalChainingAligner, SemiGlobalChainingAligner,
+        AlignmentRegulator,
     },
-    results::AlignmentResult,
+    results::{AlignmentResult, AlignmentStats},
 };
 use sigalign_impl::allocation_strategy::LinearStrategy;
 
@@ -18,6 +20,8 @@ pub enum DynamicAligner {
     LocalWithLimit(LocalWithLimitAligner<LinearStrategy>),
     SemiGlobal(SemiGlobalAligner<LinearStrategy>),
     SemiGlobalWithLimit(SemiGlobalWithLimitAligner<LinearStrategy>),
+    LocalChaining(LocalChainingAligner<LinearStrategy>),
+    SemiGlobalChaining(SemiGlobalChainingAligner<LinearStrategy>),
 }
 
 impl RawAligner for DynamicAligner {
@@ -33,6 +37,42 @@ impl RawAligner for DynamicAligner {
             Self::LocalWithLimit(v) => v.alignment(reference, sequence_buffer, sorted_target_indices, query),
             Self::SemiGlobal(v) => v.alignment(reference, sequence_buffer, sorted_target_indices, query),
             Self::SemiGlobalWithLimit(v) => v.alignment(reference, sequence_buffer, sorted_target_indices, query),
+            Self::LocalChaining(v) => v.alignment(reference, sequence_buffer, sorted_target_indices, query),
+            Self::SemiGlobalChaining(v) => v.alignment(reference, sequence_buffer, sorted_target_indices, query),
+        }
+    }
+    fn alignment_with_stats<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &RawReference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        stats: &mut AlignmentStats,
+    ) -> AlignmentResult {
+        match self {
+            Self::Local(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+            Self::LocalWithLimit(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+            Self::SemiGlobal(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+            Self::SemiGlobalWithLimit(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+            Self::LocalChaining(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+            Self::SemiGlobalChaining(v) => v.alignment_with_stats(reference, sequence_buffer, sorted_target_indices, query, stats),
+        }
+    }
+    fn alignment_with_qualities<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &RawReference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: &[u8],
+    ) -> AlignmentResult {
+        match self {
+            Self::Local(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
+            Self::LocalWithLimit(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
+            Self::SemiGlobal(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
+            Self::SemiGlobalWithLimit(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
+            Self::LocalChaining(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
+            Self::SemiGlobalChaining(v) => v.alignment_with_qualities(reference, sequence_buffer, sorted_target_indices, query, query_qualities),
         }
     }
 }
@@ -42,4 +82,8 @@ impl DynamicAligner {
         let local_aligner = LocalAligner::new(regulator);
         Self::Local(local_aligner)
     }
+    pub fn new_local_chaining(sorted_regulators: Vec<AlignmentRegulator>) -> Self {
+        let local_chaining_aligner = LocalChainingAligner::new(sorted_regulators);
+        Self::LocalChaining(local_chaining_aligner)
+    }
 }
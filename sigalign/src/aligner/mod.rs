@@ -1,4 +1,6 @@
 use sigalign_core::aligner::AlignmentRegulator;
+pub use sigalign_core::aligner::SubstitutionMatrix;
+pub use sigalign_utils::sequence_manipulation::translation::GeneticCode;
 
 mod dynamic_aligner;
 use dynamic_aligner::DynamicAligner;
@@ -6,7 +8,14 @@ use dynamic_aligner::DynamicAligner;
 mod build;
 pub use build::AlignerBuildError;
 mod perform_alignments;
+pub use perform_alignments::QueryQualitiesError;
+mod parallel_alignments;
+mod streaming_alignments;
 mod switch_algorithm;
+mod strand;
+pub use strand::StrandMode;
+mod translated_search;
+mod collection_alignments;
 mod debug;
 
 /// An alignment executor.
@@ -14,4 +23,7 @@ mod debug;
 pub struct Aligner {
     regulator: AlignmentRegulator,
     dynamic_aligner: DynamicAligner,
+    strand_mode: StrandMode,
+    metadata_in_results: bool,
+    reverse_query_buffer: Vec<u8>,
 }
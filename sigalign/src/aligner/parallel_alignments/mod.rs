@@ -0,0 +1,171 @@
+use std::io::Read;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord, IdRefRecord,
+};
+use super::Aligner;
+use crate::Reference;
+use crate::results::*;
+
+// Number of records sent to a worker at once when aligning FASTA.
+const FASTA_BATCH_SIZE: usize = 256;
+
+impl Aligner {
+    /// Align multiple queries to the reference using `num_threads` workers.
+    ///
+    /// Each worker uses its own clone of the `Aligner`, and all workers share the `Reference`.
+    /// The results are returned in the same order as the input queries.
+    /// If `num_threads` is 0, the number of available CPUs is used.
+    pub fn align_queries_with_threads<Q>(
+        &self,
+        reference: &Reference,
+        queries: &[Q],
+        num_threads: usize,
+    ) -> Vec<AlignmentResult>
+    where
+        Q: AsRef<[u8]> + Sync,
+    {
+        let num_threads = get_num_threads(num_threads).min(queries.len().max(1));
+        let next_query_index = AtomicUsize::new(0);
+
+        let mut indexed_results: Vec<(usize, AlignmentResult)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..num_threads).map(|_| {
+                let mut aligner = self.clone();
+                let next_query_index = &next_query_index;
+                scope.spawn(move || {
+                    let mut sequence_buffer = Reference::get_sequence_buffer();
+                    let mut results = Vec::new();
+                    loop {
+                        let query_index = next_query_index.fetch_add(1, Ordering::Relaxed);
+                        if query_index >= queries.len() {
+                            break;
+                        }
+                        let result = aligner.align_query_with_sequence_buffer(
+                            reference,
+                            &mut sequence_buffer,
+                            &queries[query_index],
+                        );
+                        results.push((query_index, result));
+                    }
+                    results
+                })
+            }).collect();
+            handles.into_iter().flat_map(join_worker).collect()
+        });
+
+        indexed_results.sort_unstable_by_key(|(query_index, _)| *query_index);
+        indexed_results.into_iter().map(|(_, result)| result).collect()
+    }
+    /// Align a FASTA file (can be read from any `Read`) to the reference using `num_threads` workers.
+    ///
+    /// Records are read on the calling thread and aligned in batches by the workers.
+    /// The results are returned in the order of the records in the FASTA.
+    /// If `num_threads` is 0, the number of available CPUs is used.
+    pub fn align_fasta_with_threads<R>(
+        &self,
+        reference: &Reference,
+        fasta: R,
+        num_threads: usize,
+    ) -> FastaAlignmentResult where
+        R: Read,
+    {
+        let num_threads = get_num_threads(num_threads);
+        let (result_sender, result_receiver) = mpsc::channel::<(usize, Vec<ReadAlignmentResult>)>();
+
+        thread::scope(|scope| {
+            let (batch_sender, batch_receiver) = mpsc::sync_channel::<(usize, Vec<QueryRecord>)>(num_threads * 2);
+            // The receiver is owned only by the workers,
+            //  so sending fails instead of blocking when all workers are stopped (e.g., by a panic).
+            let batch_receiver = Arc::new(Mutex::new(batch_receiver));
+            let handles: Vec<_> = (0..num_threads).map(|_| {
+                let mut aligner = self.clone();
+                let batch_receiver = Arc::clone(&batch_receiver);
+                let result_sender = result_sender.clone();
+                scope.spawn(move || {
+                    let mut sequence_buffer = Reference::get_sequence_buffer();
+                    loop {
+                        let message = batch_receiver.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).recv();
+                        let (batch_index, batched_records) = match message {
+                            Ok(v) => v,
+                            Err(_) => break,
+                        };
+                        let read_alignment_results = batched_records.into_iter().filter_map(|QueryRecord(read, query)| {
+                            let alignment_result = aligner.align_query_with_sequence_buffer(
+                                reference,
+                                &mut sequence_buffer,
+                                &query,
+                            );
+                            if alignment_result.count_alignments() != 0 {
+                                Some(ReadAlignmentResult {
+                                    read,
+                                    is_forward: true,
+                                    result: aligner.label_the_alignment_result(alignment_result, reference),
+                                    quality: None,
+                                })
+                            } else {
+                                None
+                            }
+                        }).collect();
+                        // The receiver lives until all workers are joined.
+                        let _ = result_sender.send((batch_index, read_alignment_results));
+                    }
+                })
+            }).collect();
+            drop(batch_receiver);
+            drop(result_sender);
+
+            let mut fasta_reader = FastaReader::new(fasta);
+            let mut batch_index = 0;
+            let mut batched_records = Vec::with_capacity(FASTA_BATCH_SIZE);
+            while let Some(mut record) = fasta_reader.next() {
+                let mut query = Vec::new();
+                record.extend_seq_buf(&mut query);
+                let read = record.id_str().unwrap_or_default().to_string();
+                batched_records.push(QueryRecord(read, query));
+
+                if batched_records.len() == FASTA_BATCH_SIZE {
+                    let batch = std::mem::replace(&mut batched_records, Vec::with_capacity(FASTA_BATCH_SIZE));
+                    if batch_sender.send((batch_index, batch)).is_err() {
+                        // All workers are stopped; the panic is propagated below.
+                        break;
+                    }
+                    batch_index += 1;
+                }
+            }
+            if !batched_records.is_empty() {
+                let _ = batch_sender.send((batch_index, batched_records));
+            }
+            drop(batch_sender);
+
+            handles.into_iter().for_each(join_worker);
+        });
+
+        let mut indexed_results: Vec<(usize, Vec<ReadAlignmentResult>)> = result_receiver.into_iter().collect();
+        indexed_results.sort_unstable_by_key(|(batch_index, _)| *batch_index);
+        FastaAlignmentResult(
+            indexed_results.into_iter().flat_map(|(_, results)| results).collect()
+        )
+    }
+}
+
+struct QueryRecord(String, Vec<u8>);
+
+/// Join the worker, resuming the panic of the worker on the calling thread.
+fn join_worker<T>(handle: thread::ScopedJoinHandle<'_, T>) -> T {
+    match handle.join() {
+        Ok(v) => v,
+        Err(panic_payload) => std::panic::resume_unwind(panic_payload),
+    }
+}
+
+fn get_num_threads(num_threads: usize) -> usize {
+    if num_threads == 0 {
+        thread::available_parallelism().map(|v| v.get()).unwrap_or(1)
+    } else {
+        num_threads
+    }
+}
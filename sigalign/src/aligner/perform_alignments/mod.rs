@@ -1,18 +1,32 @@
 use std::io::Read;
+use thiserror::Error;
 
 use sigalign_core::{
     reference::{Reference as RawReference, PatternIndex, SequenceStorage},
-    aligner::Aligner as RawAligner,
+    aligner::{Aligner as RawAligner, PHRED_OFFSET},
 };
 use sigalign_utils::sequence_reader::{
     fasta::FastaReader,
-    SeqRecord, IdRefRecord,
+    fastq::FastqReader,
+    SeqRecord, IdRefRecord, QualRefRecord,
 };
-use sigalign_impl::sequence_storage::in_memory::InMemoryBuffer;
+use sigalign_impl::sequence_storage::dynamic_sequence_storage::DynamicSequenceBuffer;
 use super::Aligner;
 use crate::Reference;
 use crate::results::*;
 
+/// Error for aligning a query with the qualities of its bases.
+#[derive(Debug, Error)]
+pub enum QueryQualitiesError {
+    #[error("The length of the query ({query}) and the qualities ({qualities}) are different.")]
+    LengthMismatch {
+        query: usize,
+        qualities: usize,
+    },
+    #[error("The qualities cannot be applied with the substitution matrix (including the IUPAC matching).")]
+    WithSubstitutionMatrix,
+}
+
 impl RawAligner for Aligner {
     fn alignment<I: PatternIndex, S: SequenceStorage> (
         &mut self,
@@ -28,22 +42,56 @@ impl RawAligner for Aligner {
             query,
         )
     }
+    fn alignment_with_stats<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &RawReference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        stats: &mut AlignmentStats,
+    ) -> AlignmentResult {
+        self.dynamic_aligner.alignment_with_stats(
+            reference,
+            sequence_buffer,
+            sorted_target_indices,
+            query,
+            stats,
+        )
+    }
+    fn alignment_with_qualities<I: PatternIndex, S: SequenceStorage> (
+        &mut self,
+        reference: &RawReference<I, S>,
+        sequence_buffer: &mut S::Buffer,
+        sorted_target_indices: &[u32],
+        query: &[u8],
+        query_qualities: &[u8],
+    ) -> AlignmentResult {
+        self.dynamic_aligner.alignment_with_qualities(
+            reference,
+            sequence_buffer,
+            sorted_target_indices,
+            query,
+            query_qualities,
+        )
+    }
 }
 
 impl Aligner {
     /* For one query */
     /// Align a query to the reference with a sequence buffer.
     /// ⚠️ This is lowest-level executor for `Aligner`, assuming that users have already known about "sigalign-core" and "sigalign-impl" crates.
-    pub fn align_query_with_sequence_buffer<Q>(&mut self, reference: &Reference, sequence_buffer: &mut InMemoryBuffer, query: Q) -> AlignmentResult
+    pub fn align_query_with_sequence_buffer<Q>(&mut self, reference: &Reference, sequence_buffer: &mut DynamicSequenceBuffer, query: Q) -> AlignmentResult
     where
         Q: AsRef<[u8]>,
     {
-        self.dynamic_aligner.alignment(
-            reference.as_ref(),
-            sequence_buffer,
-            reference.get_full_sorted_target_indices(),
-            query.as_ref(),
-        )
+        self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+            dynamic_aligner.alignment(
+                reference.as_ref(),
+                sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                query,
+            )
+        })
     }
     /// Align a query to the reference.
     pub fn align_query<Q>(&mut self, reference: &Reference, query: Q) -> AlignmentResult
@@ -51,25 +99,87 @@ impl Aligner {
         Q: AsRef<[u8]>,
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
-        self.dynamic_aligner.alignment(
-            reference.as_ref(),
-            &mut sequence_buffer,
-            reference.get_full_sorted_target_indices(),
-            query.as_ref(),
-        )
+        self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+            dynamic_aligner.alignment(
+                reference.as_ref(),
+                &mut sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                query,
+            )
+        })
+    }
+    /// Align a query to the reference and report the diagnostics of the alignment.
+    ///  - Use this to find out why a query is slow or has no result.
+    ///  - `align_query` is not affected by the collection of the statistics.
+    pub fn align_query_with_stats<Q>(&mut self, reference: &Reference, query: Q) -> (AlignmentResult, AlignmentStats)
+    where
+        Q: AsRef<[u8]>,
+    {
+        let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
+        let mut stats = AlignmentStats::default();
+        let alignment_result = self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+            dynamic_aligner.alignment_with_stats(
+                reference.as_ref(),
+                &mut sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                query,
+                &mut stats,
+            )
+        });
+        (alignment_result, stats)
+    }
+    /// Align a query with the qualities of its bases (Phred+33 encoded) to the reference.
+    ///  - The qualities change the result only if the `Aligner` is made with `new_with_quality_aware_mismatch`.
+    ///  - The reverse strand (by `StrandMode`) uses the reversed qualities.
+    ///  - Fails if the lengths of the query and the qualities are different,
+    ///    or if the `Aligner` uses the substitution matrix, which would ignore the qualities.
+    pub fn align_query_with_qualities<Q, P>(&mut self, reference: &Reference, query: Q, qualities: P) -> Result<AlignmentResult, QueryQualitiesError>
+    where
+        Q: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        let query = query.as_ref();
+        let qualities = qualities.as_ref();
+        if query.len() != qualities.len() {
+            return Err(QueryQualitiesError::LengthMismatch { query: query.len(), qualities: qualities.len() });
+        }
+        if self.regulator.get_substitution_matrix().is_some() {
+            return Err(QueryQualitiesError::WithSubstitutionMatrix);
+        }
+        let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
+        let mut reversed_qualities = Vec::new();
+        let alignment_result = self.align_strands(query, |dynamic_aligner, strand_query, strand| {
+            let strand_qualities = match strand {
+                Strand::Forward => qualities,
+                Strand::Reverse => {
+                    reversed_qualities.extend(qualities.iter().rev());
+                    &reversed_qualities
+                },
+            };
+            dynamic_aligner.alignment_with_qualities(
+                reference.as_ref(),
+                &mut sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                strand_query,
+                strand_qualities,
+            )
+        });
+        Ok(alignment_result)
     }
     /// Align a query to the reference and label the result.
     pub fn align_query_labeled<Q>(&mut self, reference: &Reference, query: Q) -> LabeledAlignmentResult where
         Q: AsRef<[u8]>,
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
-        let alignment_result = self.dynamic_aligner.alignment(
-            reference.as_ref(),
-            &mut sequence_buffer,
-            reference.get_full_sorted_target_indices(),
-            query.as_ref(),
-        );
-        label_the_alignment_result(alignment_result, reference)
+        let alignment_result = self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+            dynamic_aligner.alignment(
+                reference.as_ref(),
+                &mut sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                query,
+            )
+        });
+        self.label_the_alignment_result(alignment_result, reference)
     }
 
     /* For multiple query */
@@ -81,12 +191,14 @@ impl Aligner {
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
         queries.into_iter().map(|query| {
-            self.dynamic_aligner.alignment(
-                reference.as_ref(),
-                &mut sequence_buffer,
-                reference.get_full_sorted_target_indices(),
-                query.as_ref(),
-            )
+            self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+                dynamic_aligner.alignment(
+                    reference.as_ref(),
+                    &mut sequence_buffer,
+                    reference.get_full_sorted_target_indices(),
+                    query,
+                )
+            })
         }).collect()
     }
     /// Align multiple queries to the reference and label the results.
@@ -96,19 +208,54 @@ impl Aligner {
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
         queries.into_iter().map(|query| {
-            let alignment_result = self.dynamic_aligner.alignment(
-                reference.as_ref(),
-                &mut sequence_buffer,
-                reference.get_full_sorted_target_indices(),
-                query.as_ref(),
-            );
-            label_the_alignment_result(alignment_result, reference)
+            let alignment_result = self.align_strands(query.as_ref(), |dynamic_aligner, query, _| {
+                dynamic_aligner.alignment(
+                    reference.as_ref(),
+                    &mut sequence_buffer,
+                    reference.get_full_sorted_target_indices(),
+                    query,
+                )
+            });
+            self.label_the_alignment_result(alignment_result, reference)
         }).collect()
     }
     /* For fasta */
     /// Align a FASTA file (can be read from any `Read`) to the reference.
     pub fn align_fasta<R>(&mut self, reference: &Reference, fasta: R) -> FastaAlignmentResult where
         R: Read,
+    {
+        self.align_fasta_records(reference, fasta, false)
+    }
+    /// Align a FASTA file (can be read from any `Read`) to the reference with reverse complementary.
+    /// E.g. if the reference is `ATCG`, then `CGAT` will be aligned to the reference.
+    /// The result will be labeled as `is_forward = false`.
+    /// The position of the alignment result will be the position of the reverse complementary sequence.
+    pub fn align_fasta_with_reverse_complementary<R>(&mut self, reference: &Reference, fasta: R) -> FastaAlignmentResult where
+        R: Read,
+    {
+        self.align_fasta_records(reference, fasta, true)
+    }
+    /* For fastq */
+    /// Align a FASTQ file (can be read from any `Read`) to the reference.
+    ///  - If `min_quality` is specified, the bases with lower Phred quality score (Phred+33 encoded) are masked
+    ///    to the symbol that matches nothing, so that they are never used as anchors.
+    ///  - The quality string of the read is kept in the result.
+    pub fn align_fastq<R>(&mut self, reference: &Reference, fastq: R, min_quality: Option<u8>) -> FastaAlignmentResult where
+        R: Read,
+    {
+        self.align_fastq_records(reference, fastq, min_quality, false)
+    }
+    /// Align a FASTQ file (can be read from any `Read`) to the reference with reverse complementary.
+    ///  - The masking of low-quality bases is the same as `align_fastq`.
+    ///  - For the results of reverse complementary, the quality string is also reversed.
+    pub fn align_fastq_with_reverse_complementary<R>(&mut self, reference: &Reference, fastq: R, min_quality: Option<u8>) -> FastaAlignmentResult where
+        R: Read,
+    {
+        self.align_fastq_records(reference, fastq, min_quality, true)
+    }
+
+    fn align_fasta_records<R>(&mut self, reference: &Reference, fasta: R, with_reverse_complementary: bool) -> FastaAlignmentResult where
+        R: Read,
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
         let mut fasta_reader = FastaReader::new(fasta);
@@ -117,108 +264,159 @@ impl Aligner {
         while let Some(mut record) =  fasta_reader.next() {
             query_buffer.clear();
             record.extend_seq_buf(&mut query_buffer);
-
-            let alignment_result = self.dynamic_aligner.alignment(
-                reference.as_ref(),
+            self.align_record(
+                reference,
                 &mut sequence_buffer,
-                reference.get_full_sorted_target_indices(),
-                &query_buffer,
+                &mut query_buffer,
+                record.id_str().unwrap_or_default(),
+                None,
+                with_reverse_complementary,
+                &mut read_alignment_results,
             );
-            if alignment_result.count_alignments() != 0 {
-                let labeled_alignment_result = label_the_alignment_result(alignment_result, reference);
-                let read = record.id_str().unwrap_or_default().to_string();
-                let read_alignment_result = ReadAlignmentResult {
-                    read,
-                    is_forward: true,
-                    result: labeled_alignment_result,
-                };
-                read_alignment_results.push(read_alignment_result);
-            }
         }
         FastaAlignmentResult(read_alignment_results)
     }
-    /// Align a FASTA file (can be read from any `Read`) to the reference with reverse complementary.
-    /// E.g. if the reference is `ATCG`, then `CGAT` will be aligned to the reference.
-    /// The result will be labeled as `is_forward = false`.
-    /// The position of the alignment result will be the position of the reverse complementary sequence.
-    pub fn align_fasta_with_reverse_complementary<R>(&mut self, reference: &Reference, fasta: R) -> FastaAlignmentResult where
+    fn align_fastq_records<R>(&mut self, reference: &Reference, fastq: R, min_quality: Option<u8>, with_reverse_complementary: bool) -> FastaAlignmentResult where
         R: Read,
     {
         let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
-        let mut fasta_reader = FastaReader::new(fasta);
+        let mut fastq_reader = FastqReader::new(fastq);
         let mut query_buffer = Vec::new();
         let mut read_alignment_results = Vec::new();
-        while let Some(mut record) =  fasta_reader.next() {
+        while let Some(mut record) =  fastq_reader.next() {
             query_buffer.clear();
             record.extend_seq_buf(&mut query_buffer);
-            // Forward
-            let alignment_result = self.dynamic_aligner.alignment(
-                reference.as_ref(),
-                &mut sequence_buffer,
-                reference.get_full_sorted_target_indices(),
-                &query_buffer,
-            );
-            if alignment_result.count_alignments() != 0 {
-                let labeled_alignment_result = label_the_alignment_result(alignment_result, reference);
-                let read = record.id_str().unwrap_or_default().to_string();
-                let read_alignment_result = ReadAlignmentResult {
-                    read,
-                    is_forward: true,
-                    result: labeled_alignment_result,
-                };
-                read_alignment_results.push(read_alignment_result);
+            if let Some(min_quality) = min_quality {
+                mask_low_quality_bases(&mut query_buffer, record.qual(), min_quality);
             }
-            // Reverse
-            transform_query_to_reverse_complementary_query(&mut query_buffer);
-            let alignment_result = self.dynamic_aligner.alignment(
-                reference.as_ref(),
+            self.align_record(
+                reference,
                 &mut sequence_buffer,
-                reference.get_full_sorted_target_indices(),
-                &query_buffer,
+                &mut query_buffer,
+                record.id_str().unwrap_or_default(),
+                Some(record.qual()),
+                with_reverse_complementary,
+                &mut read_alignment_results,
             );
-            if alignment_result.count_alignments() != 0 {
-                let labeled_alignment_result = label_the_alignment_result(alignment_result, reference);
-                let read = record.id_str().unwrap_or_default().to_string();
-                let read_alignment_result = ReadAlignmentResult {
-                    read,
-                    is_forward: false,
-                    result: labeled_alignment_result,
-                };
-                read_alignment_results.push(read_alignment_result);
-            }
         }
         FastaAlignmentResult(read_alignment_results)
     }
+    // Align one record of FASTA or FASTQ and push the non-empty results.
+    //  - Without reverse complementary, the query is aligned by the `StrandMode`.
+    //  - With reverse complementary, the query in `query_buffer` is transformed in place.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn align_record(
+        &mut self,
+        reference: &Reference,
+        sequence_buffer: &mut DynamicSequenceBuffer,
+        query_buffer: &mut Vec<u8>,
+        read: &str,
+        quality: Option<&[u8]>,
+        with_reverse_complementary: bool,
+        read_alignment_results: &mut Vec<ReadAlignmentResult>,
+    ) {
+        // Forward
+        let alignment_result = if with_reverse_complementary {
+            self.dynamic_aligner.alignment(
+                reference.as_ref(),
+                sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                query_buffer,
+            )
+        } else {
+            self.align_strands(query_buffer, |dynamic_aligner, query, _| {
+                dynamic_aligner.alignment(
+                    reference.as_ref(),
+                    sequence_buffer,
+                    reference.get_full_sorted_target_indices(),
+                    query,
+                )
+            })
+        };
+        if alignment_result.count_alignments() != 0 {
+            read_alignment_results.push(ReadAlignmentResult {
+                read: read.to_string(),
+                is_forward: true,
+                result: self.label_the_alignment_result(alignment_result, reference),
+                quality: quality.map(|x| x.iter().map(|x| *x as char).collect()),
+            });
+        }
+        if !with_reverse_complementary {
+            return
+        }
+        // Reverse
+        transform_query_to_reverse_complementary_query(query_buffer, self.complements_iupac_codes());
+        let alignment_result = self.dynamic_aligner.alignment(
+            reference.as_ref(),
+            sequence_buffer,
+            reference.get_full_sorted_target_indices(),
+            query_buffer,
+        );
+        if alignment_result.count_alignments() != 0 {
+            read_alignment_results.push(ReadAlignmentResult {
+                read: read.to_string(),
+                is_forward: false,
+                result: self.label_the_alignment_result(alignment_result, reference),
+                quality: quality.map(|x| x.iter().rev().map(|x| *x as char).collect()),
+            });
+        }
+    }
 }
 
 /* For label the results */
-#[inline(always)]
-fn label_the_alignment_result (
-    alignment_result: AlignmentResult,
-    reference: &Reference,
-) -> LabeledAlignmentResult {
-    LabeledAlignmentResult(
-        alignment_result.0.into_iter().map(
-            |x| label_the_target_alignment_result(x, reference)
-        ).collect()
-    )
-}
-#[inline(always)]
-fn label_the_target_alignment_result (
-    target_result: TargetAlignmentResult,
-    reference: &Reference,
-) -> LabeledTargetAlignmentResult {
-    let target_index = target_result.index;
-    let label = reference.get_label(target_index).unwrap_or_default();
-    LabeledTargetAlignmentResult {
-        index: target_index,
-        label,
-        alignments: target_result.alignments,
+impl Aligner {
+    /// Copy the `TargetMetadata` of the targets into the labeled results (`false` by default).
+    ///  - Applied to all methods returning the labeled results.
+    pub fn set_metadata_in_results(&mut self, metadata_in_results: bool) {
+        self.metadata_in_results = metadata_in_results;
+    }
+    pub fn get_metadata_in_results(&self) -> bool {
+        self.metadata_in_results
+    }
+    #[inline(always)]
+    pub(super) fn label_the_alignment_result(
+        &self,
+        alignment_result: AlignmentResult,
+        reference: &Reference,
+    ) -> LabeledAlignmentResult {
+        LabeledAlignmentResult(
+            alignment_result.0.into_iter().map(
+                |x| self.label_the_target_alignment_result(x, reference)
+            ).collect()
+        )
+    }
+    #[inline(always)]
+    fn label_the_target_alignment_result(
+        &self,
+        target_result: TargetAlignmentResult,
+        reference: &Reference,
+    ) -> LabeledTargetAlignmentResult {
+        let target_index = target_result.index;
+        let label = reference.get_label(target_index).unwrap_or_default();
+        let metadata = if self.metadata_in_results {
+            reference.get_metadata(target_index)
+        } else {
+            None
+        };
+        LabeledTargetAlignmentResult {
+            index: target_index,
+            label,
+            alignments: target_result.alignments,
+            cutoff_tier: target_result.cutoff_tier,
+            metadata,
+        }
     }
 }
 
 /* For reverse complementary sequence */
-fn transform_query_to_reverse_complementary_query(query_buffer: &mut Vec<u8>) {
+impl Aligner {
+    // The IUPAC ambiguity codes are complemented only by the aligner matching them.
+    pub(super) fn complements_iupac_codes(&self) -> bool {
+        self.regulator.get_ambiguous_match_penalty().is_some()
+    }
+}
+//  - If `complement_iupac_codes`, the IUPAC ambiguity codes are also complemented (e.g., R(A/G) <-> Y(C/T)).
+pub(super) fn transform_query_to_reverse_complementary_query(query_buffer: &mut Vec<u8>, complement_iupac_codes: bool) {
     query_buffer.reverse();
     query_buffer.iter_mut().for_each(|x| {
         *x = match x {
@@ -226,7 +424,46 @@ fn transform_query_to_reverse_complementary_query(query_buffer: &mut Vec<u8>) {
             b'T' => b'A',
             b'G' => b'C',
             b'C' => b'G',
+            _ if !complement_iupac_codes => *x,
+            b'R' => b'Y',
+            b'Y' => b'R',
+            b'K' => b'M',
+            b'M' => b'K',
+            b'B' => b'V',
+            b'V' => b'B',
+            b'D' => b'H',
+            b'H' => b'D',
             _ => *x,
         }
     });
 }
+
+/* For masking low-quality bases */
+// The base that does not match any base of the reference.
+//  - This relies on the pattern index built with `use_safe_guard: true` (always set by `ReferenceBuilder`),
+//    so that a character absent from the reference is never located.
+//    Without the safe guard, the last indexed character also matches the unknown characters.
+const MASKED_BASE: u8 = 0;
+fn mask_low_quality_bases(query_buffer: &mut [u8], quality: &[u8], min_quality: u8) {
+    query_buffer.iter_mut().zip(quality.iter()).for_each(|(base, qual)| {
+        if qual.saturating_sub(PHRED_OFFSET) < min_quality {
+            *base = MASKED_BASE;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iupac_codes_are_complemented_only_if_requested() {
+        let mut query = b"ACGTRYKMBVDHN".to_vec();
+        transform_query_to_reverse_complementary_query(&mut query, false);
+        assert_eq!(query, b"NHDVBMKYRACGT");
+
+        let mut query = b"ACGTRYKMBVDHN".to_vec();
+        transform_query_to_reverse_complementary_query(&mut query, true);
+        assert_eq!(query, b"NDHBVKMRYACGT");
+    }
+}
@@ -0,0 +1,66 @@
+use super::{
+    Aligner,
+    DynamicAligner,
+};
+use super::perform_alignments::transform_query_to_reverse_complementary_query;
+use crate::results::{AlignmentResult, Strand};
+
+/// Strands of the query to align.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StrandMode {
+    /// Align the query as it is (default).
+    #[default]
+    Forward,
+    /// Align both the query and its reverse complementary in one call.
+    ///  - ⚠️ The reverse complementary is aligned as another query, so the cost is about twice of `Forward`.
+    ///    The pattern locations are shared between the strands only by the pattern location cache
+    ///    (`Reference::set_pattern_location_cache_capacity`): the pattern of the reverse complementary
+    ///    that is the same as a pattern of the original query is not located again.
+    ///  - The alignments of the reverse complementary are marked as `Strand::Reverse`.
+    ///  - The limit (`Aligner::set_limit`) is applied to the merged result of both strands.
+    ///  - Their query positions and operations are in the reverse complementary query.
+    Both,
+    /// Same as `Both`, but the query positions and operations of `Strand::Reverse`
+    /// are normalized to the orientation of the original query.
+    ///  - The query position is counted from the start of the original query.
+    ///  - The operations are ordered from the start of the original query (the end of the target).
+    BothNormalized,
+}
+
+impl Aligner {
+    /// Set the strands of the query to align.
+    ///  - Applied to all `align_*` methods, except for the `*_with_reverse_complementary`.
+    pub fn set_strand_mode(&mut self, strand_mode: StrandMode) {
+        self.strand_mode = strand_mode;
+    }
+    pub fn get_strand_mode(&self) -> StrandMode {
+        self.strand_mode
+    }
+    /// Align the strands of the query by `StrandMode`.
+    ///  - The buffer for the reverse complementary query is kept in the `Aligner`.
+    pub(super) fn align_strands<F>(&mut self, query: &[u8], mut align: F) -> AlignmentResult where
+        F: FnMut(&mut DynamicAligner, &[u8], Strand) -> AlignmentResult,
+    {
+        let mut alignment_result = align(&mut self.dynamic_aligner, query, Strand::Forward);
+        if let StrandMode::Forward = self.strand_mode {
+            return alignment_result;
+        }
+
+        let mut reverse_query = std::mem::take(&mut self.reverse_query_buffer);
+        reverse_query.clear();
+        reverse_query.extend_from_slice(query);
+        transform_query_to_reverse_complementary_query(&mut reverse_query, self.complements_iupac_codes());
+        let mut reverse_alignment_result = align(&mut self.dynamic_aligner, &reverse_query, Strand::Reverse);
+        self.reverse_query_buffer = reverse_query;
+
+        reverse_alignment_result.set_strand(Strand::Reverse);
+        if let StrandMode::BothNormalized = self.strand_mode {
+            reverse_alignment_result.flip_query_orientation_of_reverse_strand(query.len() as u32);
+        }
+        alignment_result.merge(reverse_alignment_result);
+        if let Some(limit) = self.get_limit() {
+            alignment_result.truncate_alignments(limit as usize);
+        }
+        alignment_result
+    }
+}
@@ -0,0 +1,51 @@
+use std::io::Error;
+
+use sigalign_utils::sequence_reader::SeqReader;
+use super::Aligner;
+use crate::Reference;
+use crate::results::ReadAlignmentResult;
+
+impl Aligner {
+    /// Align the reads from a reader (`FastaReader` or `FastqReader`) one by one.
+    ///
+    /// Unlike `align_fasta`, the results are not collected in memory,
+    /// so that the caller can write each result as soon as it is yielded.
+    ///  - The buffers for the sequence and query are reused for all reads.
+    ///  - As with `align_fasta` and `align_fastq`, the reads are aligned by the `StrandMode`,
+    ///    the quality string of the FASTQ is kept in the result,
+    ///    and the reads without any alignment are not yielded.
+    ///  - The iteration continues after an error in parsing a record.
+    pub fn align_reads<'a, R>(
+        &'a mut self,
+        reference: &'a Reference,
+        mut reader: R,
+    ) -> impl Iterator<Item = Result<ReadAlignmentResult, Error>> + 'a where
+        R: SeqReader + 'a,
+    {
+        let mut sequence_buffer = Reference::get_sequence_buffer();
+        let mut id_buffer = String::new();
+        let mut query_buffer = Vec::new();
+        let mut quality_buffer = Vec::new();
+        let mut read_alignment_results = Vec::new();
+        std::iter::from_fn(move || {
+            loop {
+                if let Err(error) = reader.fill_next_record(&mut id_buffer, &mut query_buffer, &mut quality_buffer)? {
+                    return Some(Err(error));
+                }
+                self.align_record(
+                    reference,
+                    &mut sequence_buffer,
+                    &mut query_buffer,
+                    &id_buffer,
+                    R::HAS_QUALITY.then_some(&quality_buffer[..]),
+                    false,
+                    &mut read_alignment_results,
+                );
+                // Without reverse complementary, at most one result is pushed for a read.
+                if let Some(read_alignment_result) = read_alignment_results.pop() {
+                    return Some(Ok(read_alignment_result));
+                }
+            }
+        })
+    }
+}
@@ -3,15 +3,30 @@ use super::{
     DynamicAligner,
 };
 use sigalign_core::aligner::{
+    AlignmentRegulator,
     LocalAligner, LocalWithLimitAligner,
     SemiGlobalAligner, SemiGlobalWithLimitAligner,
+    LocalChainingAligner, SemiGlobalChainingAligner,
 };
 
 impl Aligner {
     /// Set the limit of the number of alignments for each query.
+    ///  - The limit is ignored by the chaining aligner (made with `new_chaining`).
     pub fn set_limit(&mut self, limit: Option<u32>) {
         self.dynamic_aligner.set_limit(limit);
     }
+    pub fn get_limit(&self) -> Option<u32> {
+        self.dynamic_aligner.get_limit()
+    }
+    /// Set the maximum occurrences of a pattern in the reference (`None` by default).
+    ///  - The pattern occurring more than the cap is not used as the anchor.
+    ///    This prevents the explosion of runtime in the repetitive regions of the reference.
+    ///  - ⚠️ With the cap, it is not guaranteed that all alignments satisfying the cutoff are found.
+    ///  - The number of dropped patterns is counted in `AlignmentStats` (`align_query_with_stats`).
+    pub fn set_pattern_occurrence_cap(&mut self, cap: Option<u32>) {
+        self.regulator.set_pattern_occurrence_cap(cap);
+        self.dynamic_aligner.set_pattern_occurrence_cap(cap);
+    }
     /// Change the algorithm to semi-global.
     /// Returns `false` if the algorithm is already semi-global.
     pub fn change_to_semi_global(&mut self) -> bool {
@@ -24,6 +39,13 @@ impl Aligner {
     }
 }
 impl DynamicAligner {
+    fn get_limit(&self) -> Option<u32> {
+        match self {
+            Self::LocalWithLimit(v) => Some(v.get_limit()),
+            Self::SemiGlobalWithLimit(v) => Some(v.get_limit()),
+            _ => None,
+        }
+    }
     fn set_limit(&mut self, limit: Option<u32>) {
         match limit {
             Some(limit) => {
@@ -45,6 +67,7 @@ impl DynamicAligner {
                     Self::SemiGlobalWithLimit(v) => {
                         v.set_limit(limit);
                     },
+                    Self::LocalChaining(_) | Self::SemiGlobalChaining(_) => {},
                 }
             },
             None => {
@@ -64,6 +87,33 @@ impl DynamicAligner {
             },
         }
     }
+    fn set_pattern_occurrence_cap(&mut self, cap: Option<u32>) {
+        let with_cap = |regulator: &AlignmentRegulator| {
+            let mut regulator = regulator.clone();
+            regulator.set_pattern_occurrence_cap(cap);
+            regulator
+        };
+        match self {
+            Self::Local(v) => {
+                *v = LocalAligner::new(with_cap(v.get_regulator()));
+            },
+            Self::LocalWithLimit(v) => {
+                *v = LocalWithLimitAligner::new(with_cap(v.get_regulator()), v.get_limit());
+            },
+            Self::SemiGlobal(v) => {
+                *v = SemiGlobalAligner::new(with_cap(v.get_regulator()));
+            },
+            Self::SemiGlobalWithLimit(v) => {
+                *v = SemiGlobalWithLimitAligner::new(with_cap(v.get_regulator()), v.get_limit());
+            },
+            Self::LocalChaining(v) => {
+                *v = LocalChainingAligner::new(v.get_regulators().iter().map(with_cap).collect());
+            },
+            Self::SemiGlobalChaining(v) => {
+                *v = SemiGlobalChainingAligner::new(v.get_regulators().iter().map(with_cap).collect());
+            },
+        }
+    }
     fn change_to_semi_global(&mut self) -> bool {
         match self {
             Self::Local(v) => {
@@ -76,6 +126,11 @@ impl DynamicAligner {
                 *self = Self::SemiGlobalWithLimit(SemiGlobalWithLimitAligner::new(regulator, v.get_limit()));
                 true
             },
+            Self::LocalChaining(v) => {
+                let sorted_regulators = v.get_regulators().to_vec();
+                *self = Self::SemiGlobalChaining(SemiGlobalChainingAligner::new(sorted_regulators));
+                true
+            },
             _ => false,
         }
     }
@@ -91,6 +146,11 @@ impl DynamicAligner {
                 *self = Self::LocalWithLimit(LocalWithLimitAligner::new(regulator, v.get_limit()));
                 true
             },
+            Self::SemiGlobalChaining(v) => {
+                let sorted_regulators = v.get_regulators().to_vec();
+                *self = Self::LocalChaining(LocalChainingAligner::new(sorted_regulators));
+                true
+            },
             _ => false,
         }
     }
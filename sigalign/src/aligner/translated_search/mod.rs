@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use sigalign_core::aligner::Aligner as RawAligner;
+use sigalign_utils::sequence_manipulation::translation::{
+    GeneticCode,
+    FRAMES,
+    translate_dna_sequence_in_frame,
+    nucleotide_range_of_frame,
+};
+use super::Aligner;
+use crate::Reference;
+use crate::results::{
+    Strand,
+    TranslatedAlignmentResult,
+    TranslatedTargetAlignmentResult,
+    TranslatedAnchorAlignmentResult,
+};
+
+impl Aligner {
+    /// Align a DNA query to the protein reference in six reading frames (like `blastx`).
+    ///  - Each frame of the query is translated by the `genetic_code` and aligned as a protein sequence.
+    ///  - `Aligner` should be made with the penalties for amino acids (e.g., `SubstitutionMatrix::blosum62()`).
+    ///  - `StrandMode` is not applied, since the frames of the reverse complementary are always aligned.
+    pub fn align_query_translated<Q>(
+        &mut self,
+        reference: &Reference,
+        query: Q,
+        genetic_code: GeneticCode,
+    ) -> TranslatedAlignmentResult where
+        Q: AsRef<[u8]>,
+    {
+        let query = query.as_ref();
+        let mut sequence_buffer = reference.as_ref().get_sequence_buffer();
+        let mut alignments_by_target_index: BTreeMap<u32, Vec<TranslatedAnchorAlignmentResult>> = BTreeMap::new();
+        for frame in FRAMES {
+            let translated_query = translate_dna_sequence_in_frame(query, frame, genetic_code);
+            let alignment_result = self.dynamic_aligner.alignment(
+                reference.as_ref(),
+                &mut sequence_buffer,
+                reference.get_full_sorted_target_indices(),
+                &translated_query,
+            );
+            for target_alignment_result in alignment_result.0 {
+                let alignments = alignments_by_target_index.entry(target_alignment_result.index).or_default();
+                alignments.extend(target_alignment_result.alignments.into_iter().map(|mut alignment| {
+                    if frame < 0 {
+                        alignment.strand = Strand::Reverse;
+                    }
+                    TranslatedAnchorAlignmentResult {
+                        frame,
+                        nucleotide_query_position: nucleotide_range_of_frame(
+                            frame,
+                            query.len() as u32,
+                            alignment.position.query,
+                        ),
+                        alignment,
+                    }
+                }));
+            }
+        }
+        TranslatedAlignmentResult(
+            alignments_by_target_index.into_iter().map(|(index, alignments)| {
+                TranslatedTargetAlignmentResult { index, alignments }
+            }).collect()
+        )
+    }
+}
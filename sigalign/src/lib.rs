@@ -49,9 +49,10 @@ The core of SigAlign is built around two central structures: `Reference` and `Al
     - Query sequence
 - Regulators
     - Penalties
-        - Mismatch penalty
+        - Mismatch penalty (or substitution matrix)
         - Gap-open penalty
         - Gap-extend penalty
+        - (Optional) Second gap-open and gap-extend penalties for the two-piece affine gap
     - Cutoffs
         - Minimum alignment length (MinL)
         - Maximum penalty per alignment length (MaxP)
@@ -71,10 +72,17 @@ pub use reference::{
     ReferenceBuilder,
     ReferenceBuildError,
     ReferenceLoadError,
+    ReferenceCollection,
+    PatternLocationCacheStats,
+    TargetMetadata,
 };
 
 mod aligner;
 pub use aligner::{
     Aligner,
     AlignerBuildError,
+    QueryQualitiesError,
+    SubstitutionMatrix,
+    StrandMode,
+    GeneticCode,
 };
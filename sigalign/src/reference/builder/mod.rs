@@ -2,33 +2,70 @@ use std::{io::Read, fs::File};
 
 use thiserror::Error;
 
-use sigalign_core::reference::Reference as RawReference;
+use sigalign_core::reference::{
+    Reference as RawReference,
+    MaskedRegions,
+};
 use sigalign_impl::{
-    pattern_index::dynamic_lfi::{
-        DynamicLfiOption, LfiBuildError,
+    pattern_index::{
+        dynamic_lfi::DynamicLfiOption,
+        kmer_index::KmerIndexOption,
+        dynamic_pattern_index::{DynamicPatternIndexOption, PatternIndexBuildError},
+    },
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        dynamic_sequence_storage::DynamicSequenceStorage,
     },
-    sequence_storage::in_memory::InMemoryStorage,
 };
 use super::Reference;
 
+mod sharded_build;
+mod target_metadata;
+use target_metadata::{MetadataOfTarget, TsvMetadataByLabel, make_target_metadata_store};
+
 /// Builder for `Reference`.
 pub struct ReferenceBuilder {
     ignore_case: bool,
+    soft_mask_lowercase: bool,
     to_ignore_bases: Vec<u8>,
+    kmer_size: Option<u32>,
+    sequence_storage_type: SequenceStorageType,
+    memory_budget: Option<u64>,
+    num_threads: usize,
+    keep_descriptions: bool,
     sequence_storage: InMemoryStorage,
+    descriptions: Vec<String>,
+    tsv_metadata: TsvMetadataByLabel,
+}
+
+// Storage of the sequences in the built `Reference`
+#[derive(Debug, Clone, Copy)]
+enum SequenceStorageType {
+    InMemory,
+    PackedNucleotide,
+    BlockCompressed,
 }
 
 /// Error for building `Reference`.
 #[derive(Error, Debug)]
 pub enum ReferenceBuildError {
     #[error(transparent)]
-    PatternIndexError(#[from] LfiBuildError),
+    PatternIndexError(#[from] PatternIndexBuildError),
     #[error("Invalid input: {0}")]
     InvalidSequence(String),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[error("Invalid metadata: {0}")]
+    InvalidMetadata(String),
     #[error("Sequence is empty")]
     EmptySequence,
+    #[error("Target length ({0}) exceeds the maximum of u32")]
+    TargetLengthOver(u64),
+    #[error("Estimated peak memory ({estimated} bytes) exceeds the memory budget ({budget} bytes)")]
+    MemoryBudgetExceeded {
+        estimated: u64,
+        budget: u64,
+    },
 }
 
 
@@ -37,8 +74,16 @@ impl ReferenceBuilder {
     pub fn new() -> Self {
         Self {
             ignore_case: true,
+            soft_mask_lowercase: false,
             to_ignore_bases: Vec::new(),
+            kmer_size: None,
+            sequence_storage_type: SequenceStorageType::InMemory,
+            memory_budget: None,
+            num_threads: 1,
+            keep_descriptions: false,
             sequence_storage: InMemoryStorage::new(),
+            descriptions: Vec::new(),
+            tsv_metadata: TsvMetadataByLabel::new(),
         }
     }
     /* Configuration */
@@ -47,6 +92,14 @@ impl ReferenceBuilder {
         self.ignore_case = ignore_case;
         self
     }
+    /// Treat the lowercase bases as soft-masked regions (e.g., repeats).
+    ///  - The patterns overlapping the soft-masked regions are not located, so they never seed anchors.
+    ///  - The soft-masked bases are still aligned when the alignment is extended over them.
+    ///  - The lowercase bases are set to uppercase regardless of `ignore_case`.
+    pub fn soft_mask_lowercase(mut self, soft_mask_lowercase: bool) -> Self {
+        self.soft_mask_lowercase = soft_mask_lowercase;
+        self
+    }
     /// Set the base that never match to any other bases.
     pub fn ignore_base(mut self, base: u8) -> Self {
         self.to_ignore_bases.push(base);
@@ -62,45 +115,174 @@ impl ReferenceBuilder {
         self.to_ignore_bases.clear();
         self
     }
+    /// Use the hash table of k-mers as the pattern index, instead of the FM-index (default).
+    ///  - Much faster to build, but uses more memory.
+    ///  - The `kmer_size` should not be larger than the pattern size of `Aligner`.
+    ///    The pattern shorter than `kmer_size` is located by the range of the k-mers starting with it, which is slower.
+    pub fn use_kmer_index(mut self, kmer_size: u32) -> Self {
+        self.kmer_size = Some(kmer_size);
+        self
+    }
+    /// Use the FM-index as the pattern index (default).
+    pub fn use_fm_index(mut self) -> Self {
+        self.kmer_size = None;
+        self
+    }
+    /// Save the sequences in 2 bits per base (`PackedNucleotideStorage`), instead of 1 byte per base (default).
+    ///  - About 4 times smaller for the nucleotide sequences, but slower to align, since the targets are decoded for each alignment.
+    ///  - The bases other than `A`, `C`, `G`, and `T` are kept as exceptions, so the sequences are the same as those of the default storage.
+    ///  - The sequences are copied into memory when the `Reference` is memory-mapped (`Reference::open_mmap`).
+    pub fn use_packed_nucleotide_storage(mut self) -> Self {
+        self.sequence_storage_type = SequenceStorageType::PackedNucleotide;
+        self
+    }
+    /// Save the sequences in the independently compressed blocks (`BlockCompressedStorage`), instead of 1 byte per base (default).
+    ///  - Smaller for the large references, but slower to align, since the blocks of the targets are decompressed for each alignment.
+    ///  - The sequences are copied into memory when the `Reference` is memory-mapped (`Reference::open_mmap`).
+    pub fn use_block_compressed_storage(mut self) -> Self {
+        self.sequence_storage_type = SequenceStorageType::BlockCompressed;
+        self
+    }
+    /// Save the sequences in 1 byte per base (default).
+    pub fn use_in_memory_storage(mut self) -> Self {
+        self.sequence_storage_type = SequenceStorageType::InMemory;
+        self
+    }
+    /// Set the maximum memory (in bytes) to use while building.
+    ///  - `build` fails if the estimated peak memory (`estimate_peak_memory`) exceeds the budget.
+    ///    It never splits the targets.
+    ///  - `build_collection` splits the targets into the shards of `ReferenceCollection`, so that building them fits in the budget.
+    pub fn memory_budget(mut self, bytes: u64) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+    /// Set the number of threads to build the shards in `build_collection` (default: 1).
+    ///  - If 0, the number of available CPUs is used.
+    ///  - Each index (including its suffix array and BWT) is built in a single thread, so `build` is not affected.
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+    /// Keep the description of the FASTA header (after the ID) in the metadata of the target.
+    ///  - Applied to the FASTA added after this option is set.
+    ///  - The metadata can be retrieved by `Reference::get_metadata`.
+    pub fn keep_descriptions(mut self, keep_descriptions: bool) -> Self {
+        self.keep_descriptions = keep_descriptions;
+        self
+    }
     /* Add Sequences */
     pub fn add_fasta<R: Read>(mut self, reader: R) -> Result<Self, ReferenceBuildError> {
-        self.sequence_storage.add_fasta(reader).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
+        if self.keep_descriptions {
+            self.add_fasta_with_descriptions(reader)?;
+        } else {
+            self.sequence_storage.add_fasta(reader).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
+        }
         Ok(self)
     }
-    pub fn add_fasta_file<P>(mut self, path: P) -> Result<Self, ReferenceBuildError> where
+    pub fn add_fasta_file<P>(self, path: P) -> Result<Self, ReferenceBuildError> where
         P: AsRef<std::path::Path> + std::fmt::Debug,
     {
         let file = File::open(path)?;
-        self.sequence_storage.add_fasta(file).map_err(|_| ReferenceBuildError::invalid_fasta_record())?;
+        self.add_fasta(file)
+    }
+    /* Add Metadata */
+    /// Add the metadata of the targets from the TSV (tab-separated values) sidecar.
+    ///  - The first line is the header. The first column is the label of the target,
+    ///    and the others are the names of the metadata.
+    ///  - The column named `taxonomy_id` is parsed as the taxonomy ID.
+    ///    The other columns are kept as the key and value pairs (`TargetMetadata::attributes`).
+    ///  - The empty values are skipped, and the rows of the labels not in the reference are ignored.
+    ///  - Can be added before or after the sequences.
+    pub fn add_metadata_tsv<R: Read>(mut self, reader: R) -> Result<Self, ReferenceBuildError> {
+        self.add_tsv_metadata(reader)?;
         Ok(self)
     }
+    pub fn add_metadata_tsv_file<P>(self, path: P) -> Result<Self, ReferenceBuildError> where
+        P: AsRef<std::path::Path> + std::fmt::Debug,
+    {
+        let file = File::open(path)?;
+        self.add_metadata_tsv(file)
+    }
 
     /// Finish building `Reference`.
+    ///  - The index is built in a single thread. Use `build_collection` to build the shards in parallel.
     pub fn build(mut self) -> Result<Reference, ReferenceBuildError> {
-        // Sequence Storage
-        if self.ignore_case {
+        if let Some(budget) = self.memory_budget {
+            let estimated = self.estimate_peak_memory();
+            if estimated > budget {
+                return Err(ReferenceBuildError::MemoryBudgetExceeded { estimated, budget });
+            }
+        }
+        self.check_target_lengths()?;
+        let lowercase_regions = self.preprocess_sequence_storage();
+        let metadata_of_targets = self.take_metadata_of_targets();
+        Self::build_from_storage(self.sequence_storage, lowercase_regions, metadata_of_targets, self.kmer_size, self.sequence_storage_type)
+    }
+
+    // The positions in a target are `u32`, so the longer target is rejected.
+    fn check_target_lengths(&self) -> Result<(), ReferenceBuildError> {
+        let max_target_length = self.sequence_storage.get_max_target_length();
+        if max_target_length > u32::MAX as u64 {
+            return Err(ReferenceBuildError::TargetLengthOver(max_target_length));
+        }
+        Ok(())
+    }
+    // Returns the lowercase regions of each target, if they are soft-masked.
+    fn preprocess_sequence_storage(&mut self) -> Option<Vec<Vec<(u32, u32)>>> {
+        let lowercase_regions = if self.soft_mask_lowercase {
+            Some(self.sequence_storage.get_lowercase_regions())
+        } else {
+            None
+        };
+        if self.ignore_case || self.soft_mask_lowercase {
             self.sequence_storage.set_sequences_to_uppercase()
         }
         if !self.to_ignore_bases.is_empty() {
             self.sequence_storage.change_bases_to(&self.to_ignore_bases, b'?');
         }
-
-        // Pattern index option
-        let dynamic_lfi_option = Self::get_option_for_dynamic_lfi(&self.sequence_storage);
-        let raw_reference = RawReference::new(
-            self.sequence_storage,
-            dynamic_lfi_option,
+        lowercase_regions
+    }
+    fn build_from_storage(
+        sequence_storage: InMemoryStorage,
+        lowercase_regions: Option<Vec<Vec<(u32, u32)>>>,
+        metadata_of_targets: Option<Vec<MetadataOfTarget>>,
+        kmer_size: Option<u32>,
+        sequence_storage_type: SequenceStorageType,
+    ) -> Result<Reference, ReferenceBuildError> {
+        let pattern_index_option = Self::get_pattern_index_option(
+            kmer_size,
+            sequence_storage.get_total_length(),
+        );
+        let sequence_storage = match sequence_storage_type {
+            SequenceStorageType::InMemory => DynamicSequenceStorage::InMemory(sequence_storage),
+            SequenceStorageType::PackedNucleotide => DynamicSequenceStorage::PackedNucleotide(sequence_storage.into()),
+            SequenceStorageType::BlockCompressed => DynamicSequenceStorage::BlockCompressed(sequence_storage.into()),
+        };
+        let mut raw_reference = RawReference::new(
+            sequence_storage,
+            pattern_index_option,
         )?;
+        if let Some(lowercase_regions) = lowercase_regions {
+            raw_reference.set_masked_regions(MaskedRegions::new(lowercase_regions));
+        }
+        if let Some(metadata_of_targets) = metadata_of_targets {
+            raw_reference.set_target_metadata(make_target_metadata_store(metadata_of_targets));
+        }
         Ok(Reference::from_raw(raw_reference))
     }
 
-    fn get_option_for_dynamic_lfi(sequence_storage: &InMemoryStorage) -> DynamicLfiOption {
-        let total_length = sequence_storage.get_total_length();
+    fn get_pattern_index_option(kmer_size: Option<u32>, total_length: u64) -> DynamicPatternIndexOption {
+        match kmer_size {
+            Some(kmer_size) => DynamicPatternIndexOption::Kmer(KmerIndexOption::new(kmer_size)),
+            None => DynamicPatternIndexOption::Lfi(Self::get_option_for_dynamic_lfi(total_length)),
+        }
+    }
+    fn get_option_for_dynamic_lfi(total_length: u64) -> DynamicLfiOption {
         // Use 1/8 of total length as the maximum size of lookup table.
         // Maximum: 200 MiB
         let lookup_table_max_bytes_size = u64::min(
             200 * 1024 * 1024,
-            (total_length / 8) as u64,
+            total_length / 8,
         );
         DynamicLfiOption {
             suffix_array_sampling_ratio: 1,
@@ -0,0 +1,209 @@
+/*!
+Build the `ReferenceCollection` of shards in parallel.
+
+This is not a parallel construction of one index: the suffix array and the BWT
+of each shard are built in a single thread, because `lt-fm-index` has no parallel construction.
+The threads build the indices of the different shards, so the result is a `ReferenceCollection`, not a `Reference`.
+*/
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use sigalign_core::reference::{
+    SequenceStorage,
+    extensions::EstimateSize,
+};
+use sigalign_impl::sequence_storage::in_memory::InMemoryStorage;
+use super::{ReferenceBuilder, ReferenceBuildError, MetadataOfTarget};
+use crate::reference::{Reference, ReferenceCollection};
+
+impl ReferenceBuilder {
+    /// Estimate the peak memory (in bytes) to `build` the `Reference` from the added sequences.
+    ///  - The sequences are kept in the `Reference`, and the FM-index copies them to build.
+    ///  - The working memory of the pattern index is its estimated upper bound.
+    pub fn estimate_peak_memory(&self) -> u64 {
+        let total_length = self.sequence_storage.get_total_length();
+        self.sequence_storage.serialized_size() as u64
+        + Self::estimate_peak_memory_of_pattern_index(self.kmer_size, total_length)
+    }
+    /// Finish building the `ReferenceCollection` of shards, of which indices are built in parallel.
+    ///  - Returns the `ReferenceCollection` instead of the `Reference`, to be aligned by `Aligner::align_queries_to_collection`.
+    ///  - The targets are split into shards in order, so the target indices are the same as in the `Reference` of `build`.
+    ///  - A shard is not larger than the total length divided by `num_threads`.
+    ///  - With the `memory_budget`:
+    ///    - The shards are split further, so that the indices built in the threads at once fit in the budget.
+    ///    - A shard starts to be built only if the sequences, the indices already built,
+    ///      and the working memory of the shards being built fit in the budget. Otherwise, it waits for the other shards.
+    ///    - `MemoryBudgetExceeded` is returned if a shard does not fit even when no other shard is being built
+    ///      (e.g., a target is too long, or the built indices take the budget).
+    pub fn build_collection(mut self) -> Result<ReferenceCollection, ReferenceBuildError> {
+        let num_threads = get_num_threads(self.num_threads);
+        let storage_size = self.sequence_storage.serialized_size() as u64;
+        let total_length = self.sequence_storage.get_total_length();
+        let budget = self.memory_budget.unwrap_or(u64::MAX);
+
+        // Maximum length of a shard
+        let mut max_shard_length = total_length.div_ceil(num_threads as u64).max(1);
+        if self.memory_budget.is_some() {
+            let memory_per_thread = budget.saturating_sub(storage_size) / num_threads as u64;
+            max_shard_length = max_shard_length.min(
+                Self::max_length_in_memory(self.kmer_size, memory_per_thread)
+            );
+        }
+
+        // Split
+        self.check_target_lengths()?;
+        let mut lowercase_regions = self.preprocess_sequence_storage();
+        let mut metadata_of_targets = self.take_metadata_of_targets();
+        let sequence_storage = std::mem::replace(&mut self.sequence_storage, InMemoryStorage::new());
+        let shards = sequence_storage.split_by_max_length(max_shard_length);
+        let largest_shard_length = shards.iter().map(|shard| shard.get_total_length()).max().unwrap_or(0);
+        let estimated = storage_size + Self::estimate_peak_memory_of_pattern_index(self.kmer_size, largest_shard_length);
+        if estimated > budget {
+            return Err(ReferenceBuildError::MemoryBudgetExceeded { estimated, budget });
+        }
+        let num_shards = shards.len();
+        let jobs: VecDeque<ShardJob> = shards.into_iter().enumerate().map(|(shard_index, shard)| {
+            let lowercase_regions = lowercase_regions.as_mut().map(|regions| {
+                let rest = regions.split_off(shard.num_targets() as usize);
+                std::mem::replace(regions, rest)
+            });
+            let metadata_of_targets = metadata_of_targets.as_mut().map(|metadata| {
+                let rest = metadata.split_off(shard.num_targets() as usize);
+                std::mem::replace(metadata, rest)
+            });
+            ShardJob { shard_index, shard, lowercase_regions, metadata_of_targets }
+        }).collect();
+
+        // Build in parallel
+        let kmer_size = self.kmer_size;
+        let sequence_storage_type = self.sequence_storage_type;
+        let schedule = Schedule::new(jobs, storage_size, budget);
+        let mut indexed_references: Vec<(usize, Result<Reference, ReferenceBuildError>)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..num_threads.min(num_shards)).map(|_| {
+                let schedule = &schedule;
+                scope.spawn(move || {
+                    let mut references = Vec::new();
+                    while let Some((job, working_memory)) = schedule.next_job(|length| {
+                        Self::estimate_peak_memory_of_pattern_index(kmer_size, length)
+                    }) {
+                        let reference = Self::build_from_storage(job.shard, job.lowercase_regions, job.metadata_of_targets, kmer_size, sequence_storage_type);
+                        let index_size = reference.as_ref().map_or(0, |reference| {
+                            reference.as_ref().get_pattern_index().size_in_memory() as u64
+                        });
+                        schedule.finish_job(working_memory, index_size);
+                        references.push((job.shard_index, reference));
+                    }
+                    references
+                })
+            }).collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        if let Some(estimated) = schedule.exceeded_memory() {
+            return Err(ReferenceBuildError::MemoryBudgetExceeded { estimated, budget });
+        }
+
+        indexed_references.sort_unstable_by_key(|(shard_index, _)| *shard_index);
+        let mut collection = ReferenceCollection::new();
+        for (_, reference) in indexed_references {
+            collection.add_reference(reference?);
+        }
+        Ok(collection)
+    }
+
+    fn estimate_peak_memory_of_pattern_index(kmer_size: Option<u32>, sequence_length: u64) -> u64 {
+        Self::get_pattern_index_option(kmer_size, sequence_length).estimate_peak_build_memory(sequence_length)
+    }
+    // The longest sequence of which the pattern index can be built in the `memory`.
+    fn max_length_in_memory(kmer_size: Option<u32>, memory: u64) -> u64 {
+        // The estimation increases with the length.
+        let (mut low, mut high) = (0, memory);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if Self::estimate_peak_memory_of_pattern_index(kmer_size, mid) <= memory {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low.max(1)
+    }
+}
+
+struct ShardJob {
+    shard_index: usize,
+    shard: InMemoryStorage,
+    lowercase_regions: Option<Vec<Vec<(u32, u32)>>>,
+    metadata_of_targets: Option<Vec<MetadataOfTarget>>,
+}
+
+// Jobs to be built in the memory budget.
+struct Schedule {
+    state: Mutex<ScheduleState>,
+    job_finished: Condvar,
+    budget: u64,
+}
+struct ScheduleState {
+    jobs: VecDeque<ShardJob>,
+    // The sequences, the built indices (measured), and the working memory of the building indices (estimated).
+    memory_in_use: u64,
+    num_building_jobs: usize,
+    // Memory required for the next job, when it does not fit in the budget alone.
+    exceeded_memory: Option<u64>,
+}
+impl Schedule {
+    fn new(jobs: VecDeque<ShardJob>, storage_size: u64, budget: u64) -> Self {
+        Self {
+            state: Mutex::new(ScheduleState {
+                jobs,
+                memory_in_use: storage_size,
+                num_building_jobs: 0,
+                exceeded_memory: None,
+            }),
+            job_finished: Condvar::new(),
+            budget,
+        }
+    }
+    // Returns the next job with its working memory, waiting until it fits in the budget.
+    //  - `None` if there is no job left, or the next job never fits.
+    fn next_job<F: Fn(u64) -> u64>(&self, working_memory_of_length: F) -> Option<(ShardJob, u64)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.exceeded_memory.is_some() {
+                return None
+            }
+            let job = state.jobs.front()?;
+            let working_memory = working_memory_of_length(job.shard.get_total_length());
+            let required_memory = state.memory_in_use + working_memory;
+            if required_memory <= self.budget {
+                let job = state.jobs.pop_front().unwrap();
+                state.memory_in_use = required_memory;
+                state.num_building_jobs += 1;
+                return Some((job, working_memory))
+            } else if state.num_building_jobs == 0 {
+                state.exceeded_memory = Some(required_memory);
+                self.job_finished.notify_all();
+                return None
+            }
+            state = self.job_finished.wait(state).unwrap();
+        }
+    }
+    // The working memory is released, and the built index is kept.
+    fn finish_job(&self, working_memory: u64, index_size: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.memory_in_use = state.memory_in_use - working_memory + index_size;
+        state.num_building_jobs -= 1;
+        self.job_finished.notify_all();
+    }
+    fn exceeded_memory(&self) -> Option<u64> {
+        self.state.lock().unwrap().exceeded_memory
+    }
+}
+
+fn get_num_threads(num_threads: usize) -> usize {
+    if num_threads == 0 {
+        thread::available_parallelism().map(|v| v.get()).unwrap_or(1)
+    } else {
+        num_threads
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+
+use sigalign_core::reference::{SequenceStorage, TargetMetadataStore};
+use sigalign_utils::sequence_reader::{
+    SeqRecord, IdRefRecord, DescRefRecord,
+    fasta::FastaReader,
+};
+use super::{ReferenceBuilder, ReferenceBuildError};
+
+const TAXONOMY_ID_COLUMN: &str = "taxonomy_id";
+
+/// Metadata of a row in the TSV sidecar
+#[derive(Debug, Clone, Default)]
+pub(super) struct TsvMetadata {
+    taxonomy_id: Option<u64>,
+    attributes: Vec<(String, String)>,
+}
+
+/// Metadata of a target to build the `TargetMetadataStore`
+#[derive(Debug, Clone, Default)]
+pub(super) struct MetadataOfTarget {
+    description: String,
+    tsv_metadata: TsvMetadata,
+}
+
+impl ReferenceBuilder {
+    // Same as `InMemoryStorage::add_fasta`, but keeps the descriptions of the headers.
+    pub(super) fn add_fasta_with_descriptions<R: Read>(&mut self, reader: R) -> Result<(), ReferenceBuildError> {
+        // The targets added before have no description.
+        self.descriptions.resize(self.sequence_storage.num_targets() as usize, String::new());
+        let mut fasta_reader = FastaReader::new(reader);
+        let mut sequence_buffer = Vec::new();
+        while let Some(mut record) = fasta_reader.next() {
+            let label = record.id_str().map_err(|_| ReferenceBuildError::invalid_fasta_record())?.to_string();
+            let description = match record.desc_str() {
+                Some(Ok(description)) => description.to_string(),
+                Some(Err(_)) => return Err(ReferenceBuildError::invalid_fasta_record()),
+                None => String::new(),
+            };
+            sequence_buffer.clear();
+            record.extend_seq_buf(&mut sequence_buffer);
+            self.sequence_storage.add_target(&label, &sequence_buffer);
+            self.descriptions.push(description);
+        }
+        Ok(())
+    }
+    pub(super) fn add_tsv_metadata<R: Read>(&mut self, reader: R) -> Result<(), ReferenceBuildError> {
+        let mut lines = BufReader::new(reader).lines().filter(|line| {
+            !matches!(line, Ok(line) if line.trim_end_matches('\r').is_empty())
+        });
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let column_names: Vec<String> = header.trim_end_matches('\r').trim_start_matches('#')
+            .split('\t').skip(1).map(|name| name.to_string()).collect();
+
+        for line in lines {
+            let line = line?;
+            let mut fields = line.trim_end_matches('\r').split('\t');
+            let label = fields.next().unwrap_or_default();
+            if label.is_empty() {
+                return Err(ReferenceBuildError::InvalidMetadata("Label of the TSV row is empty".to_string()));
+            }
+            let values: Vec<&str> = fields.collect();
+            if values.len() > column_names.len() {
+                return Err(ReferenceBuildError::InvalidMetadata(
+                    format!("Row of {} has more fields than the header", label)
+                ));
+            }
+            let tsv_metadata = self.tsv_metadata.entry(label.to_string()).or_default();
+            for (column_name, value) in column_names.iter().zip(values) {
+                if value.is_empty() {
+                    continue
+                }
+                if column_name == TAXONOMY_ID_COLUMN {
+                    let taxonomy_id = value.parse::<u64>().ok().filter(|&v| v != u64::MAX).ok_or_else(|| {
+                        ReferenceBuildError::InvalidMetadata(format!("Taxonomy ID of {} is invalid: {}", label, value))
+                    })?;
+                    tsv_metadata.taxonomy_id = Some(taxonomy_id);
+                } else {
+                    tsv_metadata.attributes.push((column_name.clone(), value.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+    // Returns the metadata of each target, if any metadata is added.
+    pub(super) fn take_metadata_of_targets(&mut self) -> Option<Vec<MetadataOfTarget>> {
+        if self.descriptions.is_empty() && self.tsv_metadata.is_empty() {
+            return None
+        }
+        let mut descriptions = std::mem::take(&mut self.descriptions).into_iter();
+        let tsv_metadata = std::mem::take(&mut self.tsv_metadata);
+        let metadata_of_targets = (0..self.sequence_storage.num_targets()).map(|target_index| {
+            let label = self.sequence_storage.get_label_safely(target_index).unwrap_or_default();
+            MetadataOfTarget {
+                description: descriptions.next().unwrap_or_default(),
+                tsv_metadata: tsv_metadata.get(&label).cloned().unwrap_or_default(),
+            }
+        }).collect();
+        Some(metadata_of_targets)
+    }
+}
+
+pub(super) fn make_target_metadata_store(metadata_of_targets: Vec<MetadataOfTarget>) -> TargetMetadataStore {
+    let mut target_metadata_store = TargetMetadataStore::new();
+    for metadata in metadata_of_targets {
+        target_metadata_store.push(
+            &metadata.description,
+            metadata.tsv_metadata.taxonomy_id,
+            &metadata.tsv_metadata.attributes,
+        );
+    }
+    target_metadata_store
+}
+
+pub(super) type TsvMetadataByLabel = HashMap<String, TsvMetadata>;
@@ -0,0 +1,163 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use super::{Reference, ReferenceLoadError, TargetMetadata};
+
+/// Multiple `Reference`s (shards) that are aligned as one database.
+///  - The targets are indexed globally: the targets of a shard follow those of the previous shards.
+///  - A shard from the file can be loaded lazily.
+///    The last loaded lazy shard is kept until another lazy shard is loaded,
+///    so that only one lazy shard is in memory at once, and is not loaded again by the consecutive uses.
+#[derive(Default)]
+pub struct ReferenceCollection {
+    shards: Vec<ReferenceShard>,
+    target_index_offsets: Vec<u32>, // Global index of the first target of each shard
+    num_targets: u32,
+    last_loaded_shard: Mutex<Option<(usize, Arc<Reference>)>>, // (index of shard, lazy shard)
+}
+
+enum ReferenceShard {
+    InMemory(Box<Reference>),
+    Lazy(PathBuf),
+}
+
+impl ReferenceCollection {
+    /// Make a new empty `ReferenceCollection`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Make a new `ReferenceCollection` from the files of the saved `Reference`s.
+    pub fn from_reference_files<P, I>(paths: I, lazy: bool) -> Result<Self, ReferenceLoadError> where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+    {
+        let mut collection = Self::new();
+        for path in paths {
+            collection.add_reference_file(path, lazy)?;
+        }
+        Ok(collection)
+    }
+
+    /* Add shards */
+    /// Add a `Reference` in memory as the last shard.
+    pub fn add_reference(&mut self, reference: Reference) {
+        let num_targets = reference.get_num_targets();
+        self.push_shard(ReferenceShard::InMemory(Box::new(reference)), num_targets);
+    }
+    /// Add the file of the saved `Reference` as the last shard.
+    ///  - If `lazy` is `true`, only the number of targets is read now.
+    pub fn add_reference_file<P>(&mut self, path: P, lazy: bool) -> Result<(), ReferenceLoadError> where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        if lazy {
+            let num_targets = Reference::read_num_targets_from(file)?;
+            self.push_shard(ReferenceShard::Lazy(path.to_path_buf()), num_targets);
+        } else {
+            self.add_reference(Reference::load_from(file)?);
+        }
+        Ok(())
+    }
+    fn push_shard(&mut self, shard: ReferenceShard, num_targets: u32) {
+        self.shards.push(shard);
+        self.target_index_offsets.push(self.num_targets);
+        self.num_targets += num_targets;
+    }
+
+    /* Get Information */
+    /// Get the number of shards.
+    pub fn get_num_shards(&self) -> usize {
+        self.shards.len()
+    }
+    /// Get the number of targets of all shards.
+    pub fn get_num_targets(&self) -> u32 {
+        self.num_targets
+    }
+    /// Get the global index of the first target of the shard.
+    pub fn get_target_index_offset(&self, shard_index: usize) -> Option<u32> {
+        self.target_index_offsets.get(shard_index).copied()
+    }
+    /// Get (index of shard, index of target in the shard) of the global target index.
+    pub fn locate_target(&self, target_index: u32) -> Option<(usize, u32)> {
+        if target_index >= self.num_targets {
+            return None;
+        }
+        let shard_index = self.target_index_offsets.partition_point(|offset| *offset <= target_index) - 1;
+        Some((shard_index, target_index - self.target_index_offsets[shard_index]))
+    }
+    /// Get the sequence of the target by the global index.
+    ///  - The lazy shard is loaded to get the sequence.
+    pub fn get_sequence(&self, target_index: u32) -> Result<Option<Vec<u8>>, ReferenceLoadError> {
+        match self.locate_target(target_index) {
+            Some((shard_index, local_target_index)) => self.with_shard(shard_index, |reference| {
+                reference.get_sequence(local_target_index)
+            }),
+            None => Ok(None),
+        }
+    }
+    /// Get the label of the target by the global index.
+    ///  - The lazy shard is loaded to get the label.
+    pub fn get_label(&self, target_index: u32) -> Result<Option<String>, ReferenceLoadError> {
+        match self.locate_target(target_index) {
+            Some((shard_index, local_target_index)) => self.with_shard(shard_index, |reference| {
+                reference.get_label(local_target_index)
+            }),
+            None => Ok(None),
+        }
+    }
+    /// Get the metadata of the target by the global index.
+    ///  - The lazy shard is loaded to get the metadata.
+    pub fn get_metadata(&self, target_index: u32) -> Result<Option<TargetMetadata>, ReferenceLoadError> {
+        match self.locate_target(target_index) {
+            Some((shard_index, local_target_index)) => self.with_shard(shard_index, |reference| {
+                reference.get_metadata(local_target_index)
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /* Access to shards */
+    /// Call `f` with each shard and the global index of its first target, in order.
+    pub(crate) fn try_for_each_shard<F>(&self, mut f: F) -> Result<(), ReferenceLoadError> where
+        F: FnMut(&Reference, u32),
+    {
+        for shard_index in 0..self.shards.len() {
+            let offset = self.target_index_offsets[shard_index];
+            self.with_shard(shard_index, |reference| f(reference, offset))?;
+        }
+        Ok(())
+    }
+    fn with_shard<F, T>(&self, shard_index: usize, f: F) -> Result<T, ReferenceLoadError> where
+        F: FnOnce(&Reference) -> T,
+    {
+        match &self.shards[shard_index] {
+            ReferenceShard::InMemory(reference) => Ok(f(reference)),
+            ReferenceShard::Lazy(path) => {
+                let reference = self.load_lazy_shard(shard_index, path)?;
+                Ok(f(&reference))
+            },
+        }
+    }
+    fn load_lazy_shard(&self, shard_index: usize, path: &Path) -> Result<Arc<Reference>, ReferenceLoadError> {
+        let mut last_loaded_shard = self.last_loaded_shard.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((last_shard_index, reference)) = last_loaded_shard.as_ref() {
+            if *last_shard_index == shard_index {
+                return Ok(reference.clone())
+            }
+        }
+        // Drop the previous shard before loading, not to hold two shards.
+        *last_loaded_shard = None;
+        let reference = Arc::new(Reference::load_from(BufReader::new(File::open(path)?))?);
+        *last_loaded_shard = Some((shard_index, reference.clone()));
+        Ok(reference)
+    }
+    /// Drop the lazy shard kept in memory after its last use.
+    pub fn release_loaded_shard(&self) {
+        *self.last_loaded_shard.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
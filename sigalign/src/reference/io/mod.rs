@@ -1,17 +1,27 @@
 use std::io::{Write, Read};
+use std::{fs::File, path::Path, sync::Arc};
 
 use base64::{Engine as _, engine::{general_purpose, GeneralPurpose}};
 use thiserror::Error;
 use capwriter::{Save, Load};
 
+use memmap2::Mmap;
 use sigalign_core::reference::{
-    Reference as RawReference, extensions::Serialize,
+    Reference as RawReference, extensions::{Serialize, LoadShared, SharedBytes, write_padding, skip_padding},
 };
 use super::Reference;
 
 const PREFIX: &str = "SIGALIGN_REFERENCE";
 const LOWEST_COMPARABLE_WRAPPER_VERSION: &str = "0.4.0-alpha";
-const CORE_VERSION: &str = "0.1.0";
+// Version of the saved format of the raw reference.
+//  - The reference saved with a different version cannot be loaded (`ReferenceLoadError::IncompatibleVersion`),
+//    so the reference has to be rebuilt from the FASTA files.
+//  - 0.2.0: The target boundaries and the positions of the pattern index can be 64-bit.
+//  - 0.3.0: The masked regions (e.g., soft-masked lowercase) are saved.
+//  - 0.4.0: The metadata of the targets are saved. The k-mer index does not keep the sequence.
+//           The sequences are saved aligned, to be memory-mapped.
+//           The type of the sequence storage is saved before it.
+const CORE_VERSION: &str = "0.4.0";
 const DELIMITER: &str = ":";
 
 impl Reference {
@@ -21,21 +31,73 @@ impl Reference {
     {
         let signature = Self::get_base64_encoded_signature_of_current_version();
         signature.as_bytes().save_to(&mut writer)?;
+        // The raw reference starts at the aligned offset, so that its sequences can be borrowed by `open_mmap`.
+        write_padding(&mut writer, signature.as_bytes().to_be_saved_size())?;
         self.raw_reference.save_to(writer)?;
         Ok(())
     }
     /// Load `Reference` from a reader.
+    ///  - The reference saved by the version of SigAlign with a different save format cannot be loaded,
+    ///    and fails with `ReferenceLoadError::IncompatibleVersion`.
     pub fn load_from<R>(mut reader: R) -> Result<Self, ReferenceLoadError> where
         R: Read,
         Self: Sized
     {
-        let encoded_signature: Vec<u8> = Vec::load_from(&mut reader)?;
+        Self::check_signature(&mut reader)?;
+        let raw_reference = RawReference::load_from(reader)?;
+        Ok(Self::from_raw(raw_reference))
+    }
+    /// Open the saved `Reference` file by memory-mapping it.
+    ///  - The sequences of the targets are not copied to the memory, but read from the mapped file.
+    ///    Therefore, the processes opening the same file share the page cache.
+    ///  - The pattern index is still loaded to the memory.
+    ///  - The packed sequences (`ReferenceBuilder::use_packed_nucleotide_storage`)
+    ///    and the compressed blocks (`ReferenceBuilder::use_block_compressed_storage`) are also loaded to the memory.
+    ///  - ⚠️ The file must not be modified while the `Reference` is alive.
+    pub fn open_mmap<P>(path: P) -> Result<Self, ReferenceLoadError> where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        // SAFETY: The mapped file is only read, and is assumed not to be modified (as documented).
+        let mmap = unsafe { Mmap::map(&file)? };
+        let bytes: SharedBytes = Arc::new(mmap);
+        let all_bytes: &[u8] = (*bytes).as_ref();
+        let mut reader = all_bytes;
+        Self::check_signature(&mut reader)?;
+        let mut offset = all_bytes.len() - reader.len();
+        let raw_reference = RawReference::load_shared(&bytes, &mut offset)?;
+        Ok(Self::from_raw(raw_reference))
+    }
+    /// Whether the sequences are read from the memory-mapped file (see `open_mmap`).
+    pub fn is_memory_mapped(&self) -> bool {
+        self.raw_reference.get_sequence_storage().is_sequence_shared()
+    }
+    /// Read only the number of targets from the saved `Reference`.
+    ///  - The target boundaries are saved first, so the rest is not read.
+    pub(crate) fn read_num_targets_from<R>(mut reader: R) -> Result<u32, ReferenceLoadError> where
+        R: Read,
+    {
+        Self::check_signature(&mut reader)?;
+        let target_boundaries: Vec<u64> = Vec::load_from(&mut reader)?;
+        Ok(target_boundaries.len().saturating_sub(1) as u32)
+    }
+    fn check_signature<R>(reader: &mut R) -> Result<(), ReferenceLoadError> where
+        R: Read,
+    {
+        let encoded_signature: Vec<u8> = Vec::load_from(&mut *reader)?;
+        skip_padding(&mut *reader, encoded_signature.to_be_saved_size())?;
         let signatures = Self::get_base64_decoded_signature(&encoded_signature)?;
-        if signatures[0] == PREFIX && signatures[1] == LOWEST_COMPARABLE_WRAPPER_VERSION && signatures[2] == CORE_VERSION {
-            let raw_reference = RawReference::load_from(reader)?;
-            Ok(Self::from_raw(raw_reference))
+        if signatures.first().map(String::as_str) != Some(PREFIX) {
+            return Err(ReferenceLoadError::UnknownFile)
+        }
+        let wrapper_version = signatures.get(1).map(String::as_str).unwrap_or_default();
+        let core_version = signatures.get(2).map(String::as_str).unwrap_or_default();
+        if wrapper_version == LOWEST_COMPARABLE_WRAPPER_VERSION && core_version == CORE_VERSION {
+            Ok(())
         } else {
-            Err(ReferenceLoadError::IncompatibleVersion(signatures[1].clone()))
+            Err(ReferenceLoadError::IncompatibleVersion(
+                [wrapper_version, DELIMITER, core_version].concat()
+            ))
         }
     }
     fn get_base64_encoded_signature_of_current_version() -> String {
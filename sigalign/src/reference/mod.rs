@@ -1,7 +1,11 @@
-use sigalign_core::reference::Reference as RawReference;
+use sigalign_core::reference::{
+    Reference as RawReference,
+    PatternLocationCache,
+};
+pub use sigalign_core::reference::{PatternLocationCacheStats, TargetMetadata};
 use sigalign_impl::{
-    pattern_index::dynamic_lfi::DynamicLfi,
-    sequence_storage::in_memory::{InMemoryStorage, InMemoryBuffer},
+    pattern_index::dynamic_pattern_index::DynamicPatternIndex,
+    sequence_storage::dynamic_sequence_storage::{DynamicSequenceStorage, DynamicSequenceBuffer},
 };
 
 mod io;
@@ -9,15 +13,17 @@ pub use io::ReferenceLoadError;
 mod debug;
 mod builder;
 pub use builder::{ReferenceBuilder, ReferenceBuildError};
+mod collection;
+pub use collection::ReferenceCollection;
 
 /// A database for multiple target sequences.
 pub struct Reference {
-    raw_reference: RawReference<DynamicLfi, InMemoryStorage>,
+    raw_reference: RawReference<DynamicPatternIndex, DynamicSequenceStorage>,
     full_sorted_target_indices: Vec<u32>,
 }
 
-impl AsRef<RawReference<DynamicLfi, InMemoryStorage>> for Reference {
-    fn as_ref(&self) -> &RawReference<DynamicLfi, InMemoryStorage> {
+impl AsRef<RawReference<DynamicPatternIndex, DynamicSequenceStorage>> for Reference {
+    fn as_ref(&self) -> &RawReference<DynamicPatternIndex, DynamicSequenceStorage> {
         &self.raw_reference
     }
 }
@@ -25,7 +31,11 @@ impl AsRef<RawReference<DynamicLfi, InMemoryStorage>> for Reference {
 impl Reference {
     /* Building Reference */
     /// ⚠️ This is lowest-level generator for `Reference`, assuming that users have already known about "sigalign-core" and "sigalign-impl" crates.
-    pub fn from_raw(reference: RawReference<DynamicLfi, InMemoryStorage>) -> Self {
+    ///  - The pattern index is `DynamicPatternIndex` (previously `DynamicLfi`).
+    ///    The `DynamicLfi` can be wrapped by `DynamicPatternIndex::Lfi(Box::new(dynamic_lfi))`.
+    ///  - The sequence storage is `DynamicSequenceStorage` (previously `InMemoryStorage`).
+    ///    The `InMemoryStorage` can be wrapped by `DynamicSequenceStorage::InMemory(in_memory_storage)`.
+    pub fn from_raw(reference: RawReference<DynamicPatternIndex, DynamicSequenceStorage>) -> Self {
         let full_sorted_search_range = (0..reference.num_targets()).collect();
         Self { raw_reference: reference, full_sorted_target_indices: full_sorted_search_range }
     }
@@ -39,18 +49,45 @@ impl Reference {
     pub fn get_label(&self, target_index: u32) -> Option<String> {
         self.as_ref().get_sequence_storage().get_label_safely(target_index)
     }
+    /// Get the metadata of the target. None if the target index is out of range.
+    ///  - The description, taxonomy ID, and attributes are empty, unless they are added by the `ReferenceBuilder`.
+    pub fn get_metadata(&self, target_index: u32) -> Option<TargetMetadata> {
+        self.as_ref().get_metadata(target_index)
+    }
     /// Get the number of targets.
     pub fn get_num_targets(&self) -> u32 {
         self.as_ref().num_targets()
     }
     /// Get the total length of all targets (in base pairs).
-    pub fn get_total_length(&self) -> u32 {
+    pub fn get_total_length(&self) -> u64 {
         self.as_ref().get_sequence_storage().get_total_length()
     }
+    /// Count the occurrences of the pattern in the reference without locating them.
+    ///  - The reference is searched as the concatenation of all targets,
+    ///    so an occurrence spanning two adjacent targets is also counted.
+    pub fn count_pattern(&self, pattern: &[u8]) -> u64 {
+        self.as_ref().count_pattern(pattern)
+    }
+
+    /* Pattern location cache */
+    /// Cache the located patterns to reuse them for the other queries sharing the same patterns.
+    ///  - Useful when many queries are from the same regions (e.g., amplicon or targeted panels).
+    ///  - At most `capacity` bytes are cached (the patterns, their locations, and the searched targets);
+    ///    the oldest pattern is evicted first.
+    ///  - `None` to disable the cache (default). The results are the same with or without the cache.
+    pub fn set_pattern_location_cache_capacity(&mut self, capacity: Option<usize>) {
+        let pattern_location_cache = capacity.map(PatternLocationCache::new);
+        self.raw_reference.set_pattern_location_cache(pattern_location_cache);
+    }
+    /// Get the hit and miss counts of the pattern location cache. None if the cache is disabled.
+    pub fn get_pattern_location_cache_stats(&self) -> Option<PatternLocationCacheStats> {
+        self.as_ref().get_pattern_location_cache().map(|cache| cache.get_stats())
+    }
 
     /// Get sequence buffer for alignment.
-    pub fn get_sequence_buffer() -> InMemoryBuffer {
-        InMemoryBuffer::new()
+    ///  - The buffer is replaced by that of the storage of the `Reference` at the first alignment, if it is different.
+    pub fn get_sequence_buffer() -> DynamicSequenceBuffer {
+        DynamicSequenceBuffer::new()
     }
     /// Get the full sorted target indices
     pub fn get_full_sorted_target_indices(&self) -> &[u32] {
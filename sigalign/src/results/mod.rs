@@ -6,9 +6,10 @@ The structures for representing alignment outcomes.
     read: String,
     is_forward: bool,
     result: LabeledAlignmentResult,
+    quality: Option<String>, // Only for FASTQ
     ```
 
-- `FastaAlignmentResult`: Collection of `ReadAlignmentResult` from a FASTA file.
+- `FastaAlignmentResult`: Collection of `ReadAlignmentResult` from a FASTA (or FASTQ) file.
     ```rust
     Vec<ReadAlignmentResult>,
     ```
@@ -22,6 +23,7 @@ The structures for representing alignment outcomes.
     ```rust
     index: u32,
     alignments: Vec<AnchorAlignmentResult>,
+    cutoff_tier: u32,
     ```
 
 - `AnchorAlignmentResult`: Details alignment's penalty, length, position, and operations.
@@ -30,6 +32,7 @@ The structures for representing alignment outcomes.
     length: u32,
     position: AlignmentPosition,
     operations: Vec<AlignmentOperations>,
+    strand: Strand,
     ```
 
 - `AlignmentPosition`: Specifies alignment positions in query and target sequences.
@@ -46,7 +49,39 @@ The structures for representing alignment outcomes.
 
 - `AlignmentOperation`: Enumerates types of alignment operations.
     ```rust
-    Match, Subst, Insertion, Deletion,
+    Match, Subst, Insertion, Deletion, AmbiguousMatch,
+    ```
+
+- `TranslatedAlignmentResult`: Alignments of the DNA query translated in six frames (from `Aligner::align_query_translated`).
+    ```rust
+    Vec<TranslatedTargetAlignmentResult>, // index, alignments
+    // TranslatedAnchorAlignmentResult
+    frame: i8,
+    nucleotide_query_position: (u32, u32),
+    alignment: AnchorAlignmentResult,
+    ```
+
+- `Strand`: Strand of the aligned query (see `StrandMode` of `Aligner`).
+    ```rust
+    Forward, Reverse,
+    ```
+
+- `AlignmentStats`: Diagnostics of the alignment (from `Aligner::align_query_with_stats`).
+    ```rust
+    pattern_count: u64,
+    located_pattern_count: u64,
+    over_cap_pattern_count: u64,
+    pattern_occurrence_count: u64,
+    pattern_cache_hit_count: u64,
+    pattern_cache_miss_count: u64,
+    anchor_count_before_merge: u64,
+    anchor_count_after_merge: u64,
+    extended_anchor_count: u64,
+    skipped_anchor_count: u64,
+    filled_wave_front_cell_count: u64,
+    pattern_locating_time: Duration,
+    anchor_merging_time: Duration,
+    extension_time: Duration,
     ```
 */
 
@@ -60,6 +95,8 @@ pub use sigalign_core::results::{
     AlignmentPosition,
     AlignmentOperations,
     AlignmentOperation,
+    AlignmentStats,
+    Strand,
     labeled::{
         LabeledAlignmentResult,
         LabeledTargetAlignmentResult,
@@ -76,6 +113,11 @@ pub struct ReadAlignmentResult {
     pub is_forward: bool,
     #[cfg_attr(feature = "short_key", serde(rename = "res"))]
     pub result: LabeledAlignmentResult,
+    /// Quality string of the read (in the same direction as the aligned query).
+    ///  - `None` if the read is from FASTA.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "short_key", serde(rename = "qual"))]
+    pub quality: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +126,13 @@ pub struct FastaAlignmentResult(
     pub Vec<ReadAlignmentResult>
 );
 
+mod translated;
+pub use translated::{
+    TranslatedAlignmentResult,
+    TranslatedTargetAlignmentResult,
+    TranslatedAnchorAlignmentResult,
+};
+
 mod to_json;
 //TODO: pub mod to_sam;
 
@@ -40,7 +40,7 @@ impl AlignmentOperation {
     #[inline]
     fn to_cigar_code(&self) -> u8 {
         match self {
-            AlignmentOperation::Match | AlignmentOperation::Subst => b'M',
+            AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => b'M',
             AlignmentOperation::Insertion => b'I',
             AlignmentOperation::Deletion => b'D',
         }
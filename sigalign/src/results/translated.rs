@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use super::AnchorAlignmentResult;
+
+/// Alignments of the DNA query translated in six frames (from `Aligner::align_query_translated`).
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct TranslatedAlignmentResult(pub Vec<TranslatedTargetAlignmentResult>);
+
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "TrTgtAln"))]
+pub struct TranslatedTargetAlignmentResult {
+    #[cfg_attr(feature = "short_key", serde(rename = "idx"))]
+    pub index: u32,
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignments: Vec<TranslatedAnchorAlignmentResult>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "short_key", serde(rename = "TrAncAln"))]
+pub struct TranslatedAnchorAlignmentResult {
+    /// Reading frame of the query: 1, 2, 3 for the forward strand and -1, -2, -3 for the reverse complementary.
+    #[cfg_attr(feature = "short_key", serde(rename = "frm"))]
+    pub frame: i8,
+    /// Position of the aligned bases in the original DNA query (counted from the start of the query).
+    #[cfg_attr(feature = "short_key", serde(rename = "nqry"))]
+    pub nucleotide_query_position: (u32, u32),
+    /// Alignment of the translated frame.
+    ///  - The query position is in the amino acids of the frame.
+    ///  - The strand is `Reverse` for the negative frames.
+    #[cfg_attr(feature = "short_key", serde(rename = "aln"))]
+    pub alignment: AnchorAlignmentResult,
+}
+
+impl TranslatedAlignmentResult {
+    pub fn count_alignments(&self) -> usize {
+        self.0.iter().map(|target| target.alignments.len()).sum()
+    }
+}
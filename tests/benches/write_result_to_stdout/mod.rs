@@ -71,6 +71,7 @@ fn unlocked_stdout(result: &FastaAlignmentResult) {
         alignment_result.0.iter().for_each(|TargetAlignmentResult {
             index: target_index,
             alignments: anchor_results,
+            ..
         }| {
             anchor_results.iter().for_each(|anchor_result| {
                 let line = format!(
@@ -96,6 +97,7 @@ fn locked_stdout(result: &FastaAlignmentResult) {
         alignment_result.0.iter().for_each(|TargetAlignmentResult {
             index: target_index,
             alignments: anchor_results,
+            ..
         }| {
             anchor_results.iter().for_each(|anchor_result| {
                 let line = format!(
@@ -126,6 +128,7 @@ fn use_bufwriter(result: &FastaAlignmentResult) {
         alignment_result.0.iter().for_each(|TargetAlignmentResult {
             index: target_index,
             alignments: anchor_results,
+            ..
         }| {
             anchor_results.iter().for_each(|anchor_result| {
                 let line = format!(
@@ -157,6 +160,7 @@ fn use_bufwriter_directly_with_itoa(result: &FastaAlignmentResult) {
         alignment_result.0.iter().for_each(|TargetAlignmentResult {
             index: target_index,
             alignments: anchor_results,
+            ..
         }| {
             anchor_results.iter().for_each(|anchor_result| {
                 let _ = writer.write(label.as_bytes()).unwrap();
@@ -198,6 +202,7 @@ fn use_bufwriter_directly_ops_with_itoa(result: &FastaAlignmentResult) {
         alignment_result.0.iter().for_each(|TargetAlignmentResult {
             index: target_index,
             alignments: anchor_results,
+            ..
         }| {
             anchor_results.iter().for_each(|anchor_result| {
                 let _ = writer.write(label.as_bytes()).unwrap();
@@ -224,6 +229,7 @@ fn use_bufwriter_directly_ops_with_itoa(result: &FastaAlignmentResult) {
                         match operation {
                             AlignmentOperation::Match => b"M",
                             AlignmentOperation::Subst => b"S",
+                            AlignmentOperation::AmbiguousMatch => b"A",
                             AlignmentOperation::Insertion => b"I",
                             AlignmentOperation::Deletion => b"D",
                         }
@@ -246,6 +252,7 @@ fn operations_to_string(operations: &Vec<AlignmentOperations>) -> String {
             match op.operation {
                 AlignmentOperation::Match => 'M',
                 AlignmentOperation::Subst => 'S',
+                AlignmentOperation::AmbiguousMatch => 'A',
                 AlignmentOperation::Insertion => 'I',
                 AlignmentOperation::Deletion => 'D',
             },
@@ -1,41 +1,23 @@
 use super::{
-    DpMatrix,
+    DpMatrix, DpPenalties,
     target_indices_having_matched_pattern,
 };
 use sigalign::{
+    Reference,
     results::{AlignmentResult, AlignmentOperation, AnchorAlignmentResult, TargetAlignmentResult},
-    wrapper::{
-        DefaultReference, DefaultAligner,
-    },
-    utils::{FastaReader, calculate_max_pattern_size},
-    reference::{
-        Reference,
-        sequence_storage::SequenceBuffer,
-    },
 };
 use ahash::AHashSet;
-use std::path::PathBuf;
 
 pub fn local_all_substring_with_dpm_only_to_pattern_matched_targets(
     query: &[u8],
-    sig_reference: &DefaultReference,
-    mismatch_penalty: u32,
-    gap_open_penalty: u32,
-    gap_extend_penalty: u32,
+    sig_reference: &Reference,
+    pattern_size: u32,
+    penalties: &DpPenalties,
     min_length: u32,
     max_penalty_per_length: f32,
 ) -> AlignmentResult {
     // Init
-    let mut buffer = sig_reference.get_sequence_buffer();
     let mut target_alignment_results = Vec::new();
-    // Cal pattern size
-    let pattern_size = calculate_max_pattern_size(
-        mismatch_penalty, 
-        gap_open_penalty,
-        gap_extend_penalty,
-        min_length,
-        max_penalty_per_length,
-    ).unwrap();
     // Fetch target indices
     let target_indices = target_indices_having_matched_pattern(
         query,
@@ -44,22 +26,20 @@ pub fn local_all_substring_with_dpm_only_to_pattern_matched_targets(
     );
     // Align
     for target_index in target_indices {
-        sig_reference.fill_sequence_buffer(target_index, &mut buffer);
-        let target = buffer.buffered_sequence();
+        let target = sig_reference.get_sequence(target_index).unwrap();
 
         // Get anchor alignment results
         let mut all_anchor_alignment_results = Vec::new();
         let query_length = query.len();
-        for substring_length in (1..=query_length).rev() {
+        let min_substring_length = min_substring_length_of_valid_alignment(penalties, min_length, max_penalty_per_length);
+        for substring_length in (min_substring_length.max(1)..=query_length).rev() {
             for query_start_index in 0..(query_length+1-substring_length) {
                 let query_last_index = query_start_index + substring_length;
                 let substring = query[query_start_index..query_last_index].to_vec();
-                let dp_matrix = DpMatrix::new(
+                let dp_matrix = DpMatrix::new_with_penalties(
                     substring,
-                    target.to_vec(),
-                    mismatch_penalty,
-                    gap_open_penalty,
-                    gap_extend_penalty,
+                    target.clone(),
+                    penalties,
                 );
                 
                 let mut anchor_alignment_results = dp_matrix.parse_valid_semi_global_result(min_length, max_penalty_per_length);
@@ -87,6 +67,7 @@ pub fn local_all_substring_with_dpm_only_to_pattern_matched_targets(
         let mut target_alignment_result: TargetAlignmentResult = TargetAlignmentResult {
             index: target_index,
             alignments: Vec::new(),
+            cutoff_tier: 0,
         };
         let mut paths: AHashSet<(u32, u32)> = AHashSet::new();
         all_anchor_alignment_results.into_iter().for_each(|x| {
@@ -98,7 +79,7 @@ pub fn local_all_substring_with_dpm_only_to_pattern_matched_targets(
             }
         });
 
-        if target_alignment_result.alignments.len() != 0 {
+        if !target_alignment_result.alignments.is_empty() {
             target_alignment_results.push(target_alignment_result)
         }
     }
@@ -106,66 +87,21 @@ pub fn local_all_substring_with_dpm_only_to_pattern_matched_targets(
     AlignmentResult(target_alignment_results)
 }
 
-pub fn local_all_substring_with_dpm_using_all_target(
-    query: &[u8],
-    ref_file: &PathBuf,
-    mismatch_penalty: u32,
-    gap_open_penalty: u32,
-    gap_extend_penalty: u32,
+// The substring shorter than this cannot have a valid alignment.
+//  - The deletions are at most `penalty / gap_extend_penalty` long,
+//    and the penalty is at most `max_penalty_per_length * length`.
+fn min_substring_length_of_valid_alignment(
+    penalties: &DpPenalties,
     min_length: u32,
     max_penalty_per_length: f32,
-) -> AlignmentResult {
-    let ref_reader = FastaReader::from_path(ref_file).unwrap();
-    let mut target_alignment_results = Vec::new();
-
-    ref_reader.into_iter().enumerate().for_each(|(index, (_, target))| {
-        let mut target_alignment_result: TargetAlignmentResult = TargetAlignmentResult {
-            index: index as u32,
-            alignments: Vec::new(),
-        };
-        let mut paths: AHashSet<(u32, u32)> = AHashSet::new();
-
-        let query_length = query.len();
-        for substring_length in (1..=query_length).rev() {
-            for query_start_index in 0..(query_length+1-substring_length) {
-                let query_last_index = query_start_index + substring_length;
-                let substring = query[query_start_index..query_last_index].to_vec();
-                let dp_matrix = DpMatrix::new(
-                    substring,
-                    target.clone(),
-                    mismatch_penalty,
-                    gap_open_penalty,
-                    gap_extend_penalty,
-                );
-
-                let mut anchor_alignment_results = dp_matrix.parse_valid_semi_global_result(min_length, max_penalty_per_length);
-
-                adjust_position_of_alignments(
-                    &mut anchor_alignment_results,
-                    query_start_index,
-                );
-
-                for anchor_alignment_result in anchor_alignment_results {
-                    let current_paths: AHashSet<(u32, u32)> = get_alignment_paths(&anchor_alignment_result);
-
-                    if paths.is_disjoint(&current_paths) {
-                        paths.extend(&current_paths);
-                        target_alignment_result.alignments.push(anchor_alignment_result);
-                    }
-                }
-            }
-        };
-
-        if target_alignment_result.alignments.len() != 0 {
-            target_alignment_results.push(target_alignment_result)
-        }
-    });
-
-    AlignmentResult(target_alignment_results)
+) -> usize {
+    let min_gap_extend_penalty = penalties.gap_penalties.iter().map(|(_, extend)| *extend).min().unwrap();
+    let ratio = 1.0 - max_penalty_per_length / min_gap_extend_penalty as f32;
+    (min_length as f32 * ratio).floor().max(0.0) as usize
 }
 
 fn adjust_position_of_alignments(
-    anchor_alignment_results: &mut Vec<AnchorAlignmentResult>,
+    anchor_alignment_results: &mut [AnchorAlignmentResult],
     query_start_index: usize,
 ) {
     anchor_alignment_results.iter_mut().for_each(|result| {
@@ -185,17 +121,17 @@ fn get_alignment_paths(
     let mut paths = AHashSet::new();
     anchor_alignment_result.operations.iter().for_each(|operation| {
         match operation.operation {
-            AlignmentOperation::Match | AlignmentOperation::Subst => {
+            AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => {
                 for _ in 0..operation.count {
                     paths.insert((query_index, target_index));
                     query_index += 1;
                     target_index += 1;
                 }
             },
-            AlignmentOperation::Insertion => {
+            AlignmentOperation::Deletion => {
                 target_index += operation.count;
             },
-            AlignmentOperation::Deletion => {
+            AlignmentOperation::Insertion => {
                 query_index += operation.count;
             },
         }
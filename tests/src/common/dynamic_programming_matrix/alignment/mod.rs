@@ -1,13 +1,4 @@
-use super::DpMatrix;
-use sigalign::{
-    results::{
-        AlignmentResult,
-        TargetAlignmentResult, AnchorAlignmentResult, AlignmentOperation,
-    },
-    utils::FastaReader,
-};
-use std::path::PathBuf;
-use ahash::AHashSet;
+use super::{DpMatrix, DpPenalties};
 
 mod reference_for_dp_matrix;
 use reference_for_dp_matrix::{
@@ -16,11 +7,11 @@ use reference_for_dp_matrix::{
 
 mod semi_global;
 mod local_with_all_substring;
-mod local_with_one_matrix;
 
 pub use local_with_all_substring::{
     local_all_substring_with_dpm_only_to_pattern_matched_targets,
 };
 pub use semi_global::{
     semi_global_with_dpm,
+    semi_global_with_dpm_only_to_pattern_matched_targets,
 };
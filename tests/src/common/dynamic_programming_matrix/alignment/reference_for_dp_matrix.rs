@@ -1,22 +1,25 @@
 use ahash::AHashSet;
-use sigalign::{wrapper::DefaultReference};
+use sigalign::Reference;
 
 pub fn target_indices_having_matched_pattern(
     query: &[u8],
-    sig_reference: &DefaultReference,
+    sig_reference: &Reference,
     pattern_size: u32,
 ) -> Vec<u32> {
     let mut target_index_set = AHashSet::new();
     let pattern_count = query.len() / pattern_size as usize;
+    let sorted_target_indices = sig_reference.get_full_sorted_target_indices();
     for pattern_index in 0..pattern_count {
         let start_index = pattern_size as usize * pattern_index;
         let last_index = start_index + pattern_size as usize;
         let pattern = &query[start_index..last_index];
-        let pattern_location = sig_reference.locate_pattern(pattern);
+        let pattern_location = sig_reference.as_ref().locate_pattern(pattern, sorted_target_indices);
         for v in pattern_location {
             target_index_set.insert(v.target_index);
         }
     }
 
-    target_index_set.into_iter().collect()
-}
\ No newline at end of file
+    let mut target_indices: Vec<u32> = target_index_set.into_iter().collect();
+    target_indices.sort_unstable();
+    target_indices
+}
@@ -1,43 +1,90 @@
-use super::DpMatrix;
+use super::{
+    DpMatrix, DpPenalties,
+    target_indices_having_matched_pattern,
+};
 use sigalign::{
+    Reference,
     results::{
         AlignmentResult,
-        TargetAlignmentResult, AnchorAlignmentResult, AlignmentOperation,
+        TargetAlignmentResult,
     },
-    utils::FastaReader,
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
 };
 use std::path::PathBuf;
-use ahash::AHashSet;
 
 pub fn semi_global_with_dpm(
     query: &[u8],
     ref_file: &PathBuf,
-    mismatch_penalty: u32,
-    gap_open_penalty: u32,
-    gap_extend_penalty: u32,
+    penalties: &DpPenalties,
     min_length: u32,
     max_penalty_per_length: f32,
 ) -> AlignmentResult {
-    let ref_reader = FastaReader::from_path(ref_file).unwrap();
-    let result = ref_reader.into_iter().enumerate().filter_map(|(index, (_, target))| {
-        let dp_matrix = DpMatrix::new(
-            query.to_vec(),
-            target,
-            mismatch_penalty,
-            gap_open_penalty,
-            gap_extend_penalty,
-        );
-        let alignments = dp_matrix.parse_valid_semi_global_result(min_length, max_penalty_per_length);
-
-        if alignments.len() != 0 {
-            Some(TargetAlignmentResult {
-                index: index as u32,
-                alignments,
-            })
-        } else {
-            None
+    let mut ref_reader = FastaReader::from_path(ref_file).unwrap();
+    let mut result = Vec::new();
+    let mut index = 0;
+    while let Some(mut record) = ref_reader.next() {
+        let mut target = Vec::new();
+        record.extend_seq_buf(&mut target);
+        if let Some(target_alignment_result) = semi_global_to_target_with_dpm(
+            query, target, index, penalties, min_length, max_penalty_per_length,
+        ) {
+            result.push(target_alignment_result);
         }
+        index += 1;
+    }
+
+    AlignmentResult(result)
+}
+
+/// Only the targets having the exact match of any pattern can have the alignments.
+pub fn semi_global_with_dpm_only_to_pattern_matched_targets(
+    query: &[u8],
+    sig_reference: &Reference,
+    pattern_size: u32,
+    penalties: &DpPenalties,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> AlignmentResult {
+    let target_indices = target_indices_having_matched_pattern(
+        query,
+        sig_reference,
+        pattern_size,
+    );
+    let result = target_indices.into_iter().filter_map(|index| {
+        let target = sig_reference.get_sequence(index).unwrap();
+        semi_global_to_target_with_dpm(
+            query, target, index, penalties, min_length, max_penalty_per_length,
+        )
     }).collect();
 
     AlignmentResult(result)
 }
+
+fn semi_global_to_target_with_dpm(
+    query: &[u8],
+    target: Vec<u8>,
+    index: u32,
+    penalties: &DpPenalties,
+    min_length: u32,
+    max_penalty_per_length: f32,
+) -> Option<TargetAlignmentResult> {
+    let dp_matrix = DpMatrix::new_with_penalties(
+        query.to_vec(),
+        target,
+        penalties,
+    );
+    let alignments = dp_matrix.parse_valid_semi_global_result(min_length, max_penalty_per_length);
+
+    if !alignments.is_empty() {
+        Some(TargetAlignmentResult {
+            index,
+            alignments,
+            cutoff_tier: 0,
+        })
+    } else {
+        None
+    }
+}
@@ -1,7 +1,6 @@
-use super::{DpMatrix, Cell, BacktraceMarker};
+use super::{DpMatrix, BacktraceMarker};
 use ahash::AHashSet;
-use sigalign::results::{AlignmentOperation, AlignmentOperations, AlignmentPosition, AnchorAlignmentResult};
-use std::cmp;
+use sigalign::results::{AlignmentOperation, AlignmentOperations, AlignmentPosition, AnchorAlignmentResult, Strand};
 
 pub fn parse_the_unique_alignments_and_its_path(
     dp_matrix: &DpMatrix,
@@ -69,6 +68,7 @@ pub fn parse_the_unique_alignments_and_its_path(
                     length,
                     position,
                     operations,
+                    strand: Strand::Forward,
                 },
                 path,
             ))
@@ -109,40 +109,31 @@ pub fn backtrace_from_the_indices(
                         i -= 1;
                         j -= 1;
                     },
-                    BacktraceMarker::FromDel => {
-                        cell_type = BacktraceMarker::FromDel;
-                    },
-                    BacktraceMarker::FromIns => {
-                        cell_type = BacktraceMarker::FromIns;
+                    _ => {
+                        cell_type = btm;
                     },
                 }
             },
-            BacktraceMarker::FromDel => {
-                let btm = dp_matrix.del_mat[i][j].btm;
+            BacktraceMarker::FromDel(piece) => {
+                let btm = dp_matrix.del_mats[piece][i][j].btm;
                 reversed_operation.push(AlignmentOperation::Deletion);
-                i -= 1;
+                j -= 1;
 
                 match btm {
-                    BacktraceMarker::FromDiag => {
-                        cell_type = BacktraceMarker::FromDiag;
-                    },
-                    BacktraceMarker::FromDel => {
-                        cell_type = BacktraceMarker::FromDel;
+                    BacktraceMarker::FromDiag | BacktraceMarker::FromDel(_) => {
+                        cell_type = btm;
                     },
                     _ => panic!(""),
                 }
             },
-            BacktraceMarker::FromIns => {
-                let btm = dp_matrix.ins_mat[i][j].btm;
+            BacktraceMarker::FromIns(piece) => {
+                let btm = dp_matrix.ins_mats[piece][i][j].btm;
                 reversed_operation.push(AlignmentOperation::Insertion);
-                j -= 1;
+                i -= 1;
 
                 match btm {
-                    BacktraceMarker::FromDiag => {
-                        cell_type = BacktraceMarker::FromDiag;
-                    },
-                    BacktraceMarker::FromIns => {
-                        cell_type = BacktraceMarker::FromIns;
+                    BacktraceMarker::FromDiag | BacktraceMarker::FromIns(_) => {
+                        cell_type = btm;
                     },
                     _ => panic!(""),
                 }
@@ -181,22 +172,22 @@ pub fn concat_ops(
 pub fn get_alignment_position(
     last_query_index: usize,
     last_target_index: usize,
-    operations: &Vec<AlignmentOperations>,
+    operations: &[AlignmentOperations],
 ) -> AlignmentPosition {
     let mut query_length = 0;
     let mut target_length = 0;
 
     operations.iter().for_each(|AlignmentOperations { operation, count }| {
         match operation {
-            AlignmentOperation::Match | AlignmentOperation::Subst => {
+            AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => {
                 query_length += count;
                 target_length += count;
             },
             AlignmentOperation::Deletion => {
-                query_length += count;
+                target_length += count;
             },
             AlignmentOperation::Insertion => {
-                target_length += count;
+                query_length += count;
             },
         }
     });
@@ -4,8 +4,6 @@ use sigalign::results::AnchorAlignmentResult;
 use super::{
     DpMatrix,
     parse_the_unique_alignments_and_its_path,
-    concat_ops,
-    get_alignment_position,
 };
 
 pub fn parse_valid_local_result(
@@ -1,16 +1,9 @@
-use super::{DpMatrix, Cell, BacktraceMarker};
-use sigalign::results::{
-    AlignmentOperations,
-    AlignmentOperation, AnchorAlignmentResult, AlignmentPosition,
-};
-use std::cmp;
-use ahash::AHashSet;
+use super::{DpMatrix, BacktraceMarker};
+use sigalign::results::AnchorAlignmentResult;
 
 mod common;
 use common::{
     parse_the_unique_alignments_and_its_path,
-    concat_ops,
-    get_alignment_position,
 };
 mod local;
 use local::parse_valid_local_result;
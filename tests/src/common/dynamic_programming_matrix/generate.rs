@@ -1,7 +1,6 @@
 use super::{
-    DpMatrix, Cell, BacktraceMarker,
+    DpMatrix, DpPenalties, Cell, BacktraceMarker,
 };
-use std::cmp;
 impl Cell {
     fn new() -> Self {
         Self { penalty: 0, btm: BacktraceMarker::FromDiag }
@@ -14,68 +13,92 @@ impl DpMatrix {
         mismatch_penalty: u32,
         gap_open_penalty: u32,
         gap_extend_penalty: u32,
+    ) -> Self {
+        Self::new_with_penalties(
+            query,
+            target,
+            &DpPenalties::new(mismatch_penalty, gap_open_penalty, gap_extend_penalty),
+        )
+    }
+    pub fn new_with_penalties(
+        query: Vec<u8>,
+        target: Vec<u8>,
+        penalties: &DpPenalties,
     ) -> Self {
         let len1 = query.len();
         let len2 = target.len();
-        
+        let gap_piece_count = penalties.gap_penalties.len();
+
         // Initialize
         let mut dp_mat = vec![vec![Cell::new(); len2+1]; len1+1];
-        let mut del_mat = vec![vec![Cell::new(); len2+1]; len1+1];
-        let mut ins_mat = vec![vec![Cell::new(); len2+1]; len1+1];
-        for i in 0..=len1 {
-            ins_mat[i][0].penalty = u32::MAX >> 1;
-            del_mat[i][0].penalty = u32::MAX >> 1;
-        }
-        for j in 0..=len2 {
-            del_mat[0][j].penalty = u32::MAX >> 1;
-            ins_mat[0][j].penalty = u32::MAX >> 1;
+        let mut del_mats = vec![vec![vec![Cell::new(); len2+1]; len1+1]; gap_piece_count];
+        let mut ins_mats = vec![vec![vec![Cell::new(); len2+1]; len1+1]; gap_piece_count];
+        for piece in 0..gap_piece_count {
+            for i in 0..=len1 {
+                ins_mats[piece][i][0].penalty = u32::MAX >> 1;
+                del_mats[piece][i][0].penalty = u32::MAX >> 1;
+            }
+            for j in 0..=len2 {
+                del_mats[piece][0][j].penalty = u32::MAX >> 1;
+                ins_mats[piece][0][j].penalty = u32::MAX >> 1;
+            }
         }
-        
+
         // Fill matrices
         for i in 1..=len1 {
             for j in 1..=len2 {
-                // Deletion
-                let p_from_del = {
-                    let p_from_dp = dp_mat[i-1][j].penalty + gap_open_penalty + gap_extend_penalty;
-                    let p_from_del = del_mat[i-1][j].penalty + gap_extend_penalty;
-                    
-                    if p_from_dp < p_from_del {
-                        del_mat[i][j] = Cell { penalty: p_from_dp, btm: BacktraceMarker::FromDiag };
-                        p_from_dp
-                    } else {
-                        del_mat[i][j] = Cell { penalty: p_from_del, btm: BacktraceMarker::FromDel };
-                        p_from_del
+                let mut min_p_from_del = (u32::MAX, 0);
+                let mut min_p_from_ins = (u32::MAX, 0);
+                for (piece, &(gap_open_penalty, gap_extend_penalty)) in penalties.gap_penalties.iter().enumerate() {
+                    // Deletion (gap in the target)
+                    let p_from_del = {
+                        let p_from_dp = dp_mat[i][j-1].penalty + gap_open_penalty + gap_extend_penalty;
+                        let p_from_del = del_mats[piece][i][j-1].penalty + gap_extend_penalty;
+
+                        if p_from_dp < p_from_del {
+                            del_mats[piece][i][j] = Cell { penalty: p_from_dp, btm: BacktraceMarker::FromDiag };
+                            p_from_dp
+                        } else {
+                            del_mats[piece][i][j] = Cell { penalty: p_from_del, btm: BacktraceMarker::FromDel(piece) };
+                            p_from_del
+                        }
+                    };
+                    if p_from_del < min_p_from_del.0 {
+                        min_p_from_del = (p_from_del, piece);
                     }
-                };
-                // Insertion
-                let p_from_ins = {
-                    let p_from_dp = dp_mat[i][j-1].penalty + gap_open_penalty + gap_extend_penalty;
-                    let p_from_ins = ins_mat[i][j-1].penalty + gap_extend_penalty;
-                    
-                    if p_from_dp < p_from_ins {
-                        ins_mat[i][j] = Cell { penalty: p_from_dp, btm: BacktraceMarker::FromDiag };
-                        p_from_dp
-                    } else {
-                        ins_mat[i][j] = Cell { penalty: p_from_ins, btm: BacktraceMarker::FromIns };
-                        p_from_ins
+                    // Insertion (gap in the query)
+                    let p_from_ins = {
+                        let p_from_dp = dp_mat[i-1][j].penalty + gap_open_penalty + gap_extend_penalty;
+                        let p_from_ins = ins_mats[piece][i-1][j].penalty + gap_extend_penalty;
+
+                        if p_from_dp < p_from_ins {
+                            ins_mats[piece][i][j] = Cell { penalty: p_from_dp, btm: BacktraceMarker::FromDiag };
+                            p_from_dp
+                        } else {
+                            ins_mats[piece][i][j] = Cell { penalty: p_from_ins, btm: BacktraceMarker::FromIns(piece) };
+                            p_from_ins
+                        }
+                    };
+                    if p_from_ins < min_p_from_ins.0 {
+                        min_p_from_ins = (p_from_ins, piece);
                     }
-                };
+                }
                 // DP
                 let (p_from_diag, is_match) = {
                     let p_from_dp = dp_mat[i-1][j-1].penalty;
                     if query[i-1] == target[j-1] {
                         (p_from_dp, true)
                     } else {
-                        (p_from_dp + mismatch_penalty, false)
+                        (p_from_dp + penalties.penalty_of_pair(query[i-1], target[j-1]), false)
                     }
                 };
-                let min_p = p_from_diag.min(p_from_ins.min(p_from_del));
+                let min_p = p_from_diag.min(min_p_from_ins.0.min(min_p_from_del.0));
                 let btm = if (min_p == p_from_diag) && is_match {
                     BacktraceMarker::FromDiag
-                } else if min_p == p_from_del {
-                    BacktraceMarker::FromDel
-                } else if min_p == p_from_ins {
-                    BacktraceMarker::FromIns
+                } else if min_p == min_p_from_ins.0 {
+                    BacktraceMarker::FromIns(min_p_from_ins.1)
+                } else if min_p == min_p_from_del.0 {
+                    BacktraceMarker::FromDel(min_p_from_del.1)
                 } else {
                     BacktraceMarker::FromDiag
                 };
@@ -87,8 +110,8 @@ impl DpMatrix {
             target,
             query,
             dp_mat,
-            del_mat,
-            ins_mat,
+            del_mats,
+            ins_mats,
         }
     }
 }
@@ -1,8 +1,6 @@
-use sigalign::results::{
-    AlignmentOperations,
-    AlignmentOperation, AnchorAlignmentResult,
-};
-use std::cmp;
+use sigalign::SubstitutionMatrix;
+#[cfg(test)]
+use sigalign::results::{AlignmentOperation, AnchorAlignmentResult};
 
 mod generate;
 mod backtrace;
@@ -10,15 +8,75 @@ mod alignment;
 pub use alignment::{
     local_all_substring_with_dpm_only_to_pattern_matched_targets,
     semi_global_with_dpm,
+    semi_global_with_dpm_only_to_pattern_matched_targets,
 };
 
+/// Penalties of the DP matrix
+///  - The gap penalty is the minimum of the pieces: `min(open + extend * length)`.
+#[derive(Debug, Clone)]
+pub struct DpPenalties {
+    mismatch_penalty: u32,
+    substitution_matrix: Option<SubstitutionMatrix>,
+    gap_penalties: Vec<(u32, u32)>,
+}
+impl DpPenalties {
+    pub fn new(mismatch_penalty: u32, gap_open_penalty: u32, gap_extend_penalty: u32) -> Self {
+        Self {
+            mismatch_penalty,
+            substitution_matrix: None,
+            gap_penalties: vec![(gap_open_penalty, gap_extend_penalty)],
+        }
+    }
+    pub fn new_with_substitution_matrix(
+        substitution_matrix: SubstitutionMatrix,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+    ) -> Self {
+        Self {
+            mismatch_penalty: 0,
+            substitution_matrix: Some(substitution_matrix),
+            gap_penalties: vec![(gap_open_penalty, gap_extend_penalty)],
+        }
+    }
+    pub fn new_with_two_piece_gap(
+        mismatch_penalty: u32,
+        gap_open_penalty: u32,
+        gap_extend_penalty: u32,
+        second_gap_open_penalty: u32,
+        second_gap_extend_penalty: u32,
+    ) -> Self {
+        Self {
+            mismatch_penalty,
+            substitution_matrix: None,
+            gap_penalties: vec![
+                (gap_open_penalty, gap_extend_penalty),
+                (second_gap_open_penalty, second_gap_extend_penalty),
+            ],
+        }
+    }
+    fn penalty_of_pair(&self, query_base: u8, target_base: u8) -> u32 {
+        if query_base == target_base {
+            0
+        } else if let Some(substitution_matrix) = &self.substitution_matrix {
+            substitution_matrix.penalty_of_pair(target_base, query_base)
+        } else {
+            self.mismatch_penalty
+        }
+    }
+    #[cfg(test)]
+    fn penalty_of_gap(&self, length: u32) -> u32 {
+        self.gap_penalties.iter().map(|(open, extend)| open + extend * length).min().unwrap()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DpMatrix {
     target: Vec<u8>,
     query: Vec<u8>,
     dp_mat: Vec<Vec<Cell>>,
-    del_mat: Vec<Vec<Cell>>,
-    ins_mat: Vec<Vec<Cell>>,
+    // One matrix for each piece of the gap penalty
+    del_mats: Vec<Vec<Vec<Cell>>>,
+    ins_mats: Vec<Vec<Vec<Cell>>>,
 }
 #[derive(Debug, Clone)]
 struct Cell {
@@ -26,18 +84,20 @@ struct Cell {
     btm: BacktraceMarker,
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(clippy::enum_variant_names)]
 enum BacktraceMarker {
     FromDiag,
-    FromDel,
-    FromIns,
+    // With the index of the gap piece
+    FromDel(usize),
+    FromIns(usize),
 }
 
 #[test]
 fn calculation_of_penalty_is_accurate() {
-    use crate::test_data_path::{get_qry_for_val_path, get_ref_for_val_path};
-    use sigalign::utils::FastaReader;
-    use crate::init_logger;
-    use log::info;
+    use crate::common::test_data_path::{get_qry_for_val_path, get_ref_for_val_path};
+    use sigalign::{ReferenceBuilder, Aligner};
+    use sigalign_utils::sequence_reader::{fasta::FastaReader, SeqRecord};
+    use crate::common::init_logger;
 
     init_logger();
 
@@ -45,58 +105,79 @@ fn calculation_of_penalty_is_accurate() {
 
     let qry_file = get_qry_for_val_path();
     let ref_file = get_ref_for_val_path();
+    let reference = ReferenceBuilder::new().add_fasta_file(&ref_file).unwrap().build().unwrap();
 
-    let px = 4;
-    let po = 6;
-    let pe = 2;
     let ml = 100;
     let mppl = 0.1;
-
-    for (qry_idx, (label, query)) in FastaReader::from_path(&qry_file).unwrap().enumerate() {
-        if qry_idx == qry_count {
-            break;
-        }
-        info!("Query label: {}", label);
-        let results = semi_global_with_dpm(
-            &query,
-            &ref_file,
-            px,
-            po,
-            pe,
-            ml,
-            mppl,
-        );
-
-        results.0.iter().for_each(|x| {
-            x.alignments.iter().for_each(|y| {
-                let p1 = cal_penalty(&y.operations, px, po, pe);
-                let p2 = y.penalty;
-
-                if p1 != p2 {
-                    println!("query: {}", String::from_utf8(query.clone()).unwrap());
-                    println!("AnchorAlignmentResult:\n{:#?}", y);
-                    panic!("")
-                }
+    let all_penalties = [
+        DpPenalties::new(4, 6, 2),
+        DpPenalties::new_with_substitution_matrix(SubstitutionMatrix::new_transition_transversion(2, 6).unwrap(), 6, 2),
+        DpPenalties::new_with_two_piece_gap(4, 6, 2, 12, 1),
+    ];
+    let pattern_size = Aligner::new(2, 6, 1, ml, mppl).unwrap().get_pattern_size();
+
+    for penalties in all_penalties {
+        let mut qry_reader = FastaReader::from_path(&qry_file).unwrap();
+        let mut qry_idx = 0;
+        while let Some(mut record) = qry_reader.next() {
+            if qry_idx == qry_count {
+                break;
+            }
+            qry_idx += 1;
+            let mut query = Vec::new();
+            record.extend_seq_buf(&mut query);
+            let results = semi_global_with_dpm_only_to_pattern_matched_targets(
+                &query,
+                &reference,
+                pattern_size,
+                &penalties,
+                ml,
+                mppl,
+            );
+
+            results.0.iter().for_each(|x| {
+                let target = reference.get_sequence(x.index).unwrap();
+                x.alignments.iter().for_each(|y| {
+                    let p1 = cal_penalty(y, &query, &target, &penalties);
+                    let p2 = y.penalty;
+
+                    if p1 != p2 {
+                        println!("query: {}", String::from_utf8(query.clone()).unwrap());
+                        println!("AnchorAlignmentResult:\n{:#?}", y);
+                        panic!("")
+                    }
+                });
             });
-        });
+        }
     }
 }
+#[cfg(test)]
 fn cal_penalty(
-    operations: &Vec<AlignmentOperations>,
-    px: u32,
-    po: u32,
-    pe: u32,
+    alignment: &AnchorAlignmentResult,
+    query: &[u8],
+    target: &[u8],
+    penalties: &DpPenalties,
 ) -> u32 {
-    operations.iter().map(|x| {
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.0 as usize;
+    alignment.operations.iter().map(|x| {
+        let count = x.count as usize;
         match x.operation {
-            AlignmentOperation::Match => {
-                0
+            AlignmentOperation::Match | AlignmentOperation::Subst | AlignmentOperation::AmbiguousMatch => {
+                let penalty = (0..count).map(|offset| {
+                    penalties.penalty_of_pair(query[query_index + offset], target[target_index + offset])
+                }).sum();
+                query_index += count;
+                target_index += count;
+                penalty
             },
-            AlignmentOperation::Subst => {
-                px * x.count
+            AlignmentOperation::Deletion => {
+                target_index += count;
+                penalties.penalty_of_gap(x.count)
             },
-            AlignmentOperation::Insertion | AlignmentOperation::Deletion => {
-                pe * x.count + po
+            AlignmentOperation::Insertion => {
+                query_index += count;
+                penalties.penalty_of_gap(x.count)
             },
         }
     }).sum()
@@ -8,6 +8,13 @@ pub use logger::init_logger;
 pub mod test_data_path;
 pub mod random_text_and_pattern;
 
+// Queries and results shared by the tests
+mod queries_and_results;
+pub use queries_and_results::{
+    get_set_of_alignment_result,
+    get_queries,
+};
+
 // Result of stable version of sigalign
 mod result_of_stable_version;
 pub use result_of_stable_version::{
@@ -16,4 +23,7 @@ pub use result_of_stable_version::{
 };
 
 // DP matrix to generate the answer result
-// pub mod dynamic_programming_matrix;
\ No newline at end of file
+pub mod dynamic_programming_matrix;
+
+// Peak memory measured by the counting allocator (only in the tests)
+pub mod peak_memory;
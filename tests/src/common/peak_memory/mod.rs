@@ -0,0 +1,51 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Counts the bytes allocated by each thread, so that the tests running in parallel are not mixed.
+struct ThreadCountingAllocator;
+
+thread_local! {
+    static ALLOCATED: Cell<i64> = const { Cell::new(0) };
+    static PEAK: Cell<i64> = const { Cell::new(0) };
+}
+
+fn add_allocated(size: i64) {
+    let _ = ALLOCATED.try_with(|allocated| {
+        let new_allocated = allocated.get() + size;
+        allocated.set(new_allocated);
+        let _ = PEAK.try_with(|peak| peak.set(peak.get().max(new_allocated)));
+    });
+}
+
+unsafe impl GlobalAlloc for ThreadCountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        add_allocated(layout.size() as i64);
+        System.alloc(layout)
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        add_allocated(layout.size() as i64);
+        System.alloc_zeroed(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        add_allocated(-(layout.size() as i64));
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        add_allocated(new_size as i64 - layout.size() as i64);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// Registered only in the tests, not to slow down the benchmarks.
+#[cfg_attr(test, global_allocator)]
+static ALLOCATOR: ThreadCountingAllocator = ThreadCountingAllocator;
+
+/// Peak bytes allocated by the current thread while running `f`, more than at the start.
+///  - The memory allocated in the other threads (e.g., spawned by `f`) is not counted.
+pub fn measure_peak_memory<T, F: FnOnce() -> T>(f: F) -> (T, u64) {
+    let start = ALLOCATED.with(|allocated| allocated.get());
+    PEAK.with(|peak| peak.set(start));
+    let output = f();
+    let peak = PEAK.with(|peak| peak.get());
+    (output, (peak - start) as u64)
+}
@@ -0,0 +1,30 @@
+use ahash::AHashSet;
+use sigalign::results::{AlignmentResult, AnchorAlignmentResult};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
+};
+
+use super::test_data_path::get_qry_for_val_path;
+
+/// Alignments of the result as a set of (target index, alignment), to compare the results regardless of the order.
+pub fn get_set_of_alignment_result(alignment_result: &AlignmentResult) -> AHashSet<(u32, AnchorAlignmentResult)> {
+    alignment_result.0.iter().flat_map(|target_result| {
+        target_result.alignments.iter().map(|alignment| (target_result.index, alignment.clone()))
+    }).collect()
+}
+
+/// First `num_queries` queries of the validation data.
+pub fn get_queries(num_queries: usize) -> Vec<Vec<u8>> {
+    let mut queries = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    while let Some(mut record) = fasta_reader.next() {
+        if queries.len() == num_queries {
+            break;
+        }
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+    }
+    queries
+}
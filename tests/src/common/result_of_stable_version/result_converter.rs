@@ -18,6 +18,7 @@ use sigalign_core::{
         AnchorAlignmentResult,
         AlignmentPosition,
         AlignmentOperations,
+        Strand,
         AlignmentOperation,
     }
 };
@@ -35,7 +36,8 @@ fn aln_res_to_aln_res(stable_res: &StableAlignmentResult) -> AlignmentResult {
     let inner = stable_res.0.iter().map(|rec_aln_res| {
         TargetAlignmentResult {
             index: rec_aln_res.index as u32,
-            alignments: anc_res_to_anc_res(&rec_aln_res.alignments)
+            alignments: anc_res_to_anc_res(&rec_aln_res.alignments),
+            cutoff_tier: 0,
         }
     }).collect();
     AlignmentResult(inner)
@@ -50,6 +52,7 @@ fn anc_res_to_anc_res(stable_res: &Vec<StableAnchorAlignmentResult>) -> Vec<Anch
                 query: (anc_res.position.query.0 as u32, anc_res.position.query.1 as u32),
             },
             operations: ops_to_ops(&anc_res.operations),
+            strand: Strand::Forward,
         }
     }).collect()
 }
@@ -0,0 +1,46 @@
+use crate::common::{
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    results::AlignmentResult,
+};
+use sigalign_core::aligner::use_scalar_match_counting;
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn vectorized_match_counting_gives_same_result_as_scalar() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for (mode, change_to_semi_global) in [("local", false), ("semi-global", true)] {
+        info!("Mode: {}", mode);
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        for query in &queries {
+            use_scalar_match_counting(true);
+            let scalar_result = aligner.align_query(&reference, query);
+            use_scalar_match_counting(false);
+            let vectorized_result = aligner.align_query(&reference, query);
+            assert_eq!(
+                to_sorted_json(scalar_result),
+                to_sorted_json(vectorized_result),
+            );
+        }
+    }
+}
+
+fn to_sorted_json(mut alignment_result: AlignmentResult) -> String {
+    // The order of targets is not fixed
+    alignment_result.0.sort_by_key(|target_result| target_result.index);
+    alignment_result.to_json()
+}
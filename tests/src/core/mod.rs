@@ -0,0 +1 @@
+mod match_counter;
@@ -0,0 +1,144 @@
+use crate::common::{
+    init_logger,
+    test_data_path::get_ref_for_val_path,
+};
+use sigalign_core::reference::{
+    Reference,
+    SequenceStorage,
+    SequenceBuffer,
+    extensions::{Serialize, EstimateSize, LabelStorage},
+};
+use sigalign_impl::{
+    pattern_index::lfi::{Lfi32B5V64, LfiOption},
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        block_compressed::BlockCompressedStorage,
+    },
+};
+
+#[test]
+fn block_compressed_storage_restores_the_sequences() {
+    init_logger();
+
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    in_memory_storage.add_target("empty", b"");
+    in_memory_storage.add_target("short", b"ACGTN");
+
+    // Blocks shorter and longer than the targets
+    for block_size in [7, 100, 1000, 1 << 16] {
+        let mut block_compressed_storage = BlockCompressedStorage::with_block_size(block_size);
+        block_compressed_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+        block_compressed_storage.add_target("empty", b"");
+        block_compressed_storage.add_target("short", b"ACGTN");
+
+        assert_eq!(block_compressed_storage.num_targets(), in_memory_storage.num_targets());
+        assert_eq!(block_compressed_storage.get_total_length(), in_memory_storage.get_total_length());
+        // The same buffer is reused in the reverse order to use the cache
+        let mut buffer = block_compressed_storage.get_buffer();
+        let mut in_memory_buffer = in_memory_storage.get_buffer();
+        for target_index in (0..in_memory_storage.num_targets()).rev() {
+            block_compressed_storage.fill_buffer(target_index, &mut buffer);
+            in_memory_storage.fill_buffer(target_index, &mut in_memory_buffer);
+            assert_eq!(buffer.buffered_sequence(), in_memory_buffer.buffered_sequence());
+            assert_eq!(
+                block_compressed_storage.label_of_target_unchecked(target_index),
+                in_memory_storage.label_of_target_unchecked(target_index),
+            );
+        }
+        assert_eq!(block_compressed_storage.get_sequence_safely(in_memory_storage.num_targets()), None);
+        assert_eq!(
+            block_compressed_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+            in_memory_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+        );
+
+        // Save and load
+        let mut saved = Vec::new();
+        block_compressed_storage.save_to(&mut saved).unwrap();
+        assert_eq!(saved.len(), block_compressed_storage.serialized_size());
+        let loaded_storage = BlockCompressedStorage::load_from(&saved[..]).unwrap();
+        assert_eq!(block_compressed_storage, loaded_storage);
+    }
+}
+
+#[test]
+fn block_compressed_storage_is_smaller_for_repeats() {
+    let sequence: Vec<u8> = b"ACGGTCATGCANNNNGGCAT".iter().cycle().take(200_000).copied().collect();
+    let mut block_compressed_storage = BlockCompressedStorage::new();
+    block_compressed_storage.add_target("target", &sequence);
+    assert!(block_compressed_storage.get_compressed_length() * 10 < sequence.len() as u64);
+    assert_eq!(block_compressed_storage.get_sequence_safely(0).unwrap(), sequence);
+}
+
+#[test]
+fn reference_with_block_compressed_storage_locates_same_positions() {
+    init_logger();
+
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    let mut block_compressed_storage = BlockCompressedStorage::with_block_size(4096);
+    block_compressed_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    let lfi_option = LfiOption::new(2, 1024 * 1024, true);
+
+    let in_memory_reference = Reference::<Lfi32B5V64, _>::new(in_memory_storage.clone(), lfi_option.clone()).unwrap();
+    let block_compressed_reference = Reference::<Lfi32B5V64, _>::new(block_compressed_storage, lfi_option).unwrap();
+
+    let sorted_target_indices: Vec<u32> = (0..in_memory_reference.num_targets()).collect();
+    let sequence = in_memory_storage.get_sequence_safely(0).unwrap();
+    for pattern in sequence.chunks_exact(20).take(50) {
+        let mut expected: Vec<_> = in_memory_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        let mut located: Vec<_> = block_compressed_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        expected.sort();
+        located.sort();
+        assert_eq!(expected, located);
+    }
+}
+
+#[test]
+fn buffer_is_shared_by_block_compressed_storages() {
+    let mut first_storage = BlockCompressedStorage::with_block_size(8);
+    first_storage.add_target("first", b"AAAAAAAACCCCCCCC");
+    let mut second_storage = BlockCompressedStorage::with_block_size(8);
+    second_storage.add_target("second", b"GGGGGGGGTTTTTTTT");
+    // The cloned storage diverges after the targets are added.
+    let mut cloned_storage = first_storage.clone();
+    cloned_storage.add_target("third", b"NNNNNNNNNNNNNNNN");
+    first_storage.add_target("third", b"ACGTACGTACGTACGT");
+
+    let mut buffer = first_storage.get_buffer();
+    for _ in 0..2 {
+        for (storage, expected) in [
+            (&first_storage, b"ACGTACGTACGTACGT"),
+            (&second_storage, b"GGGGGGGGTTTTTTTT"),
+            (&cloned_storage, b"NNNNNNNNNNNNNNNN"),
+        ] {
+            let target_index = storage.num_targets() - 1;
+            storage.fill_buffer(target_index, &mut buffer);
+            assert_eq!(buffer.buffered_sequence(), expected);
+        }
+    }
+}
+
+#[test]
+fn corrupted_block_compressed_storage_is_error() {
+    let sequence: Vec<u8> = b"ACGGTCATGCANNNNGGCAT".iter().cycle().take(10_000).copied().collect();
+    let mut block_compressed_storage = BlockCompressedStorage::with_block_size(1000);
+    block_compressed_storage.add_target("target", &sequence);
+    let mut saved = Vec::new();
+    block_compressed_storage.save_to(&mut saved).unwrap();
+    assert!(BlockCompressedStorage::load_from(&saved[..]).is_ok());
+
+    // block_size (8 bytes), target_count (8 bytes), length of the compressed blocks (8 bytes), and the first block
+    let first_block = 3 * std::mem::size_of::<u64>();
+    for position in [0, first_block, first_block + 1] {
+        let mut corrupted = saved.clone();
+        corrupted[position] ^= 0xFF;
+        assert!(BlockCompressedStorage::load_from(&corrupted[..]).is_err());
+    }
+    // Zero block size
+    let mut corrupted = saved.clone();
+    corrupted[..8].fill(0);
+    assert!(BlockCompressedStorage::load_from(&corrupted[..]).is_err());
+}
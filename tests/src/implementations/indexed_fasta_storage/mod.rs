@@ -0,0 +1,122 @@
+use std::path::Path;
+use crate::common::{
+    init_logger,
+    test_data_path::{
+        get_lf_fa_path,
+        get_crlf_fa_path,
+        get_two_line_fa_path,
+        get_dir_on_tmp_dir,
+    },
+};
+use sigalign_core::reference::{
+    Reference,
+    SequenceStorage,
+    extensions::{Serialize, EstimateSize},
+};
+use sigalign_impl::{
+    pattern_index::lfi::{Lfi32B5V64, LfiOption},
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        indexed_fasta::IndexedFastaStorage,
+    },
+};
+
+#[test]
+fn indexed_fasta_storage_reads_same_targets() {
+    init_logger();
+
+    for fasta_file_path in [get_lf_fa_path(), get_crlf_fa_path(), get_two_line_fa_path()] {
+        // With the `.fai` file
+        let indexed_fasta_storage = IndexedFastaStorage::new(&fasta_file_path).unwrap();
+        assert_same_targets(&indexed_fasta_storage, &fasta_file_path);
+
+        // Without the `.fai` file
+        let mut copied_fasta_file_path = get_dir_on_tmp_dir("indexed_fasta_storage").unwrap();
+        copied_fasta_file_path.push(fasta_file_path.file_name().unwrap());
+        std::fs::copy(&fasta_file_path, &copied_fasta_file_path).unwrap();
+        let indexed_without_fai = IndexedFastaStorage::new(&copied_fasta_file_path).unwrap();
+        assert_same_targets(&indexed_without_fai, &fasta_file_path);
+
+        // Save and load
+        let mut buffer = Vec::new();
+        indexed_fasta_storage.save_to(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), indexed_fasta_storage.serialized_size());
+        let loaded_storage = IndexedFastaStorage::load_from(&buffer[..]).unwrap();
+        assert_eq!(indexed_fasta_storage, loaded_storage);
+        assert!(loaded_storage.get_fasta_file_path().is_absolute());
+    }
+}
+
+#[test]
+fn invalid_fai_is_error() {
+    let mut fasta_file_path = get_dir_on_tmp_dir("indexed_fasta_storage_with_invalid_fai").unwrap();
+    fasta_file_path.push("target.fa");
+    std::fs::write(&fasta_file_path, b">target\nACGT\nACGT\n").unwrap();
+    let mut fai_file_path = fasta_file_path.clone().into_os_string();
+    fai_file_path.push(".fai");
+    // Zero bases in each line
+    std::fs::write(&fai_file_path, b"target\t8\t8\t0\t1\n").unwrap();
+    assert!(IndexedFastaStorage::new(&fasta_file_path).is_err());
+    // Longer than the FASTA file
+    std::fs::write(&fai_file_path, b"target\t80\t8\t4\t5\n").unwrap();
+    assert!(IndexedFastaStorage::new(&fasta_file_path).is_err());
+    // Valid
+    std::fs::write(&fai_file_path, b"target\t8\t8\t4\t5\n").unwrap();
+    let indexed_fasta_storage = IndexedFastaStorage::new(&fasta_file_path).unwrap();
+    assert_eq!(indexed_fasta_storage.get_sequence_safely(0), Some(b"ACGTACGT".to_vec()));
+
+    // Loading fails after the FASTA file is removed
+    let mut buffer = Vec::new();
+    indexed_fasta_storage.save_to(&mut buffer).unwrap();
+    std::fs::remove_file(&fasta_file_path).unwrap();
+    assert!(IndexedFastaStorage::load_from(&buffer[..]).is_err());
+}
+
+#[test]
+fn reference_with_indexed_fasta_storage_locates_same_positions() {
+    init_logger();
+
+    let fasta_file_path = get_lf_fa_path();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(&fasta_file_path).unwrap()).unwrap();
+    let indexed_fasta_storage = IndexedFastaStorage::new(&fasta_file_path).unwrap();
+    let lfi_option = LfiOption::new(2, 1024 * 1024, true);
+
+    let in_memory_reference = Reference::<Lfi32B5V64, _>::new(in_memory_storage.clone(), lfi_option.clone()).unwrap();
+    let indexed_fasta_reference = Reference::<Lfi32B5V64, _>::new(indexed_fasta_storage, lfi_option).unwrap();
+    assert_eq!(
+        in_memory_reference.label_of_target(1),
+        indexed_fasta_reference.label_of_target(1),
+    );
+
+    let sorted_target_indices: Vec<u32> = (0..in_memory_reference.num_targets()).collect();
+    let sequence = in_memory_storage.get_sequence_safely(0).unwrap();
+    for pattern in sequence.chunks_exact(20).take(50) {
+        let mut expected: Vec<_> = in_memory_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        let mut located: Vec<_> = indexed_fasta_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        expected.sort();
+        located.sort();
+        assert_eq!(expected, located);
+    }
+}
+
+fn assert_same_targets(indexed_fasta_storage: &IndexedFastaStorage, fasta_file_path: &Path) {
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(fasta_file_path).unwrap()).unwrap();
+
+    assert_eq!(indexed_fasta_storage.num_targets(), in_memory_storage.num_targets());
+    assert_eq!(indexed_fasta_storage.get_total_length(), in_memory_storage.get_total_length());
+    for target_index in 0..in_memory_storage.num_targets() {
+        assert_eq!(
+            indexed_fasta_storage.get_sequence_safely(target_index),
+            in_memory_storage.get_sequence_safely(target_index),
+        );
+        assert_eq!(
+            indexed_fasta_storage.get_label_safely(target_index),
+            in_memory_storage.get_label_safely(target_index),
+        );
+    }
+    assert_eq!(indexed_fasta_storage.get_sequence_safely(in_memory_storage.num_targets()), None);
+}
@@ -1,2 +1,6 @@
 // mod sequence_storage;
-// mod pattern_index;
\ No newline at end of file
+// mod pattern_index;
+mod position_width;
+mod packed_nucleotide_storage;
+mod indexed_fasta_storage;
+mod block_compressed_storage;
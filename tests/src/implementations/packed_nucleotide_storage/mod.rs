@@ -0,0 +1,108 @@
+use crate::common::{
+    init_logger,
+    test_data_path::get_ref_for_val_path,
+};
+use sigalign_core::reference::{
+    Reference,
+    SequenceStorage,
+    extensions::{Serialize, EstimateSize, LabelStorage},
+};
+use sigalign_impl::{
+    pattern_index::lfi::{Lfi32B5V64, LfiOption},
+    sequence_storage::{
+        in_memory::InMemoryStorage,
+        packed_nucleotide::PackedNucleotideStorage,
+    },
+};
+
+#[test]
+fn packed_storage_restores_the_sequences() {
+    init_logger();
+
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    let mut packed_storage = PackedNucleotideStorage::new();
+    packed_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    // Bases that are not packed
+    let sequence_with_exceptions = b"ACGTNNNNacgtRYKM?ACGTACGTA";
+    in_memory_storage.add_target("exceptions", sequence_with_exceptions);
+    packed_storage.add_target("exceptions", sequence_with_exceptions);
+    // The lowercase bases are packed, and their case is kept as the runs.
+    assert!(packed_storage.get_exception_count() >= 9);
+    assert!(packed_storage.get_lowercase_run_count() >= 1);
+
+    assert_eq!(packed_storage.num_targets(), in_memory_storage.num_targets());
+    assert_eq!(packed_storage.get_total_length(), in_memory_storage.get_total_length());
+    for target_index in 0..in_memory_storage.num_targets() {
+        assert_eq!(
+            packed_storage.get_sequence_safely(target_index),
+            in_memory_storage.get_sequence_safely(target_index),
+        );
+        assert_eq!(
+            packed_storage.label_of_target_unchecked(target_index),
+            in_memory_storage.label_of_target_unchecked(target_index),
+        );
+    }
+    assert_eq!(packed_storage.get_sequence_safely(in_memory_storage.num_targets()), None);
+    assert_eq!(
+        packed_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+        in_memory_storage.get_concatenated_sequence_with_boundaries_of_targets(),
+    );
+
+    // Save and load
+    let mut buffer = Vec::new();
+    packed_storage.save_to(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), packed_storage.serialized_size());
+    let loaded_storage = PackedNucleotideStorage::load_from(&buffer[..]).unwrap();
+    assert_eq!(packed_storage, loaded_storage);
+}
+
+#[test]
+fn packed_storage_is_smaller_for_nucleotides() {
+    let sequence: Vec<u8> = b"ACGGTCATGCA".iter().cycle().take(10_000).copied().collect();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_target("target", &sequence);
+    let mut packed_storage = PackedNucleotideStorage::new();
+    packed_storage.add_target("target", &sequence);
+    assert_eq!(packed_storage.get_exception_count(), 0);
+    assert!(packed_storage.serialized_size() * 3 < in_memory_storage.serialized_size());
+}
+
+#[test]
+fn packed_storage_is_smaller_for_soft_masked_nucleotides() {
+    let sequence: Vec<u8> = b"acggtcatgca".iter().cycle().take(10_000).copied().collect();
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_target("target", &sequence);
+    let mut packed_storage = PackedNucleotideStorage::new();
+    packed_storage.add_target("target", &sequence);
+    assert_eq!(packed_storage.get_exception_count(), 0);
+    assert_eq!(packed_storage.get_lowercase_run_count(), 1);
+    assert_eq!(packed_storage.get_sequence_safely(0), Some(sequence));
+    assert!(packed_storage.serialized_size() * 3 < in_memory_storage.serialized_size());
+}
+
+#[test]
+fn reference_with_packed_storage_locates_same_positions() {
+    init_logger();
+
+    let mut in_memory_storage = InMemoryStorage::new();
+    in_memory_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    let mut packed_storage = PackedNucleotideStorage::new();
+    packed_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    let lfi_option = LfiOption::new(2, 1024 * 1024, true);
+
+    let in_memory_reference = Reference::<Lfi32B5V64, _>::new(in_memory_storage.clone(), lfi_option.clone()).unwrap();
+    let packed_reference = Reference::<Lfi32B5V64, _>::new(packed_storage, lfi_option).unwrap();
+
+    let sorted_target_indices: Vec<u32> = (0..in_memory_reference.num_targets()).collect();
+    let sequence = in_memory_storage.get_sequence_safely(0).unwrap();
+    for pattern in sequence.chunks_exact(20).take(50) {
+        let mut expected: Vec<_> = in_memory_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        let mut located: Vec<_> = packed_reference.locate_pattern(pattern, &sorted_target_indices).into_iter()
+            .map(|v| (v.target_index, v.sorted_positions)).collect();
+        expected.sort();
+        located.sort();
+        assert_eq!(expected, located);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::common::{
+    get_queries,
+    init_logger,
+    test_data_path::get_ref_for_val_path,
+};
+use ahash::AHashMap;
+use log::info;
+use sigalign_core::reference::{
+    Reference,
+    PatternIndex,
+    extensions::Serialize,
+};
+use sigalign_impl::{
+    pattern_index::lfi::{Lfi32B5V64, Lfi64B5V64, LfiOption},
+    sequence_storage::in_memory::InMemoryStorage,
+};
+
+const PATTERN_SIZE: usize = 20;
+const NUM_QUERIES: usize = 20;
+
+#[test]
+fn lfi_64_locates_same_positions_as_lfi_32() {
+    init_logger();
+
+    let mut sequence_storage = InMemoryStorage::new();
+    sequence_storage.add_fasta(std::fs::File::open(get_ref_for_val_path()).unwrap()).unwrap();
+    sequence_storage.set_sequences_to_uppercase();
+    let lfi_option = LfiOption::new(2, 1024 * 1024, true);
+
+    let reference_32 = Reference::<Lfi32B5V64, _>::new(sequence_storage.clone(), lfi_option.clone()).unwrap();
+    let reference_64 = Reference::<Lfi64B5V64, _>::new(sequence_storage, lfi_option).unwrap();
+    // Save and load
+    let mut buffer = Vec::new();
+    reference_64.save_to(&mut buffer).unwrap();
+    let loaded_reference_64 = Reference::<Lfi64B5V64, InMemoryStorage>::load_from(&buffer[..]).unwrap();
+
+    let sorted_target_indices: Vec<u32> = (0..reference_32.num_targets()).collect();
+    let mut pattern_count = 0;
+    for query in get_queries(NUM_QUERIES) {
+        for pattern in query.chunks_exact(PATTERN_SIZE) {
+            let locations_32 = get_map_of_locations(&reference_32, pattern, &sorted_target_indices);
+            assert_eq!(locations_32, get_map_of_locations(&reference_64, pattern, &sorted_target_indices));
+            assert_eq!(locations_32, get_map_of_locations(&loaded_reference_64, pattern, &sorted_target_indices));
+            pattern_count += 1;
+        }
+    }
+    info!("Pattern count: {}", pattern_count);
+}
+
+fn get_map_of_locations<I: PatternIndex>(
+    reference: &Reference<I, InMemoryStorage>,
+    pattern: &[u8],
+    sorted_target_indices: &[u32],
+) -> AHashMap<u32, Vec<u32>> {
+    reference.locate_pattern(pattern, sorted_target_indices).into_iter().map(|pattern_location| {
+        (pattern_location.target_index, pattern_location.sorted_positions)
+    }).collect()
+}
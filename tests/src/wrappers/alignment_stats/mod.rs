@@ -0,0 +1,49 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 50;
+
+#[test]
+fn alignment_with_stats_gives_same_result() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for (mode, change_to_semi_global) in [("local", false), ("semi-global", true)] {
+        info!("Mode: {}", mode);
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        let pattern_size = aligner.get_pattern_size() as u64;
+        for query in &queries {
+            let result = aligner.align_query(&reference, query);
+            let (result_with_stats, stats) = aligner.align_query_with_stats(&reference, query);
+            assert_eq!(
+                get_set_of_alignment_result(&result),
+                get_set_of_alignment_result(&result_with_stats),
+            );
+
+            assert_eq!(stats.pattern_count, query.len() as u64 / pattern_size);
+            assert!(stats.located_pattern_count <= stats.pattern_count);
+            assert!(stats.anchor_count_before_merge <= stats.pattern_occurrence_count);
+            assert!(stats.anchor_count_after_merge <= stats.anchor_count_before_merge);
+            assert!(stats.extended_anchor_count <= stats.anchor_count_after_merge);
+            assert!(stats.skipped_anchor_count <= stats.anchor_count_after_merge);
+            if result.count_alignments() != 0 {
+                assert!(stats.extended_anchor_count > 0);
+                assert!(stats.filled_wave_front_cell_count >= stats.extended_anchor_count);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_dir_on_tmp_dir,
+    },
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn block_compressed_storage_results_are_same_as_in_memory_storage() {
+    init_logger();
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let in_memory_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let block_compressed_reference = ReferenceBuilder::new()
+        .use_block_compressed_storage()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    // Save and load
+    let mut buffer = Vec::new();
+    block_compressed_reference.save_to(&mut buffer).unwrap();
+    let mut in_memory_buffer = Vec::new();
+    in_memory_reference.save_to(&mut in_memory_buffer).unwrap();
+    assert!(buffer.len() < in_memory_buffer.len());
+    let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+    // Memory-mapped: the blocks are loaded to the memory
+    let mut path = get_dir_on_tmp_dir("block_compressed_storage").unwrap();
+    path.push("block_compressed_reference.sigref");
+    block_compressed_reference.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+    let mapped_reference = Reference::open_mmap(&path).unwrap();
+    assert!(!mapped_reference.is_memory_mapped());
+
+    for target_index in 0..in_memory_reference.get_num_targets() {
+        assert_eq!(in_memory_reference.get_sequence(target_index), block_compressed_reference.get_sequence(target_index));
+        assert_eq!(in_memory_reference.get_sequence(target_index), loaded_reference.get_sequence(target_index));
+        assert_eq!(in_memory_reference.get_label(target_index), loaded_reference.get_label(target_index));
+    }
+    for query in get_queries(NUM_QUERIES) {
+        let answer = get_set_of_alignment_result(&aligner.align_query(&in_memory_reference, &query));
+        assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&block_compressed_reference, &query)));
+        assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&loaded_reference, &query)));
+        assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&mapped_reference, &query)));
+    }
+}
+
+#[test]
+fn sequence_buffer_is_replaced_for_block_compressed_storage() {
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let in_memory_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let block_compressed_reference = ReferenceBuilder::new()
+        .use_block_compressed_storage()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    // The same buffer is used for both storages
+    let mut sequence_buffer = Reference::get_sequence_buffer();
+    for query in get_queries(10) {
+        let answer = get_set_of_alignment_result(
+            &aligner.align_query_with_sequence_buffer(&in_memory_reference, &mut sequence_buffer, &query)
+        );
+        let result = get_set_of_alignment_result(
+            &aligner.align_query_with_sequence_buffer(&block_compressed_reference, &mut sequence_buffer, &query)
+        );
+        assert_eq!(answer, result);
+    }
+}
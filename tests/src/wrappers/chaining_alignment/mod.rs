@@ -0,0 +1,107 @@
+use crate::common::{
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use ahash::{AHashMap, AHashSet};
+use log::info;
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+use sigalign_core::results::{AlignmentResult, AnchorAlignmentResult};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
+};
+
+const NUM_QUERIES: usize = 100;
+const PENALTIES: (u32, u32, u32) = (4, 6, 2);
+const CUTOFFS: [(u32, f32); 3] = [(50, 0.2), (150, 0.05), (100, 0.1)];
+
+#[test]
+fn each_target_is_aligned_with_the_strictest_cutoff() {
+    init_logger();
+
+    let ref_file = get_ref_for_val_path();
+    let qry_file = get_qry_for_val_path();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(&ref_file).unwrap().build().unwrap();
+    let queries = get_queries(&qry_file);
+
+    let mut chaining_aligner = Aligner::new_chaining(PENALTIES.0, PENALTIES.1, PENALTIES.2, &CUTOFFS).unwrap();
+    let sorted_cutoffs = chaining_aligner.get_cutoffs();
+    assert_eq!(sorted_cutoffs, vec![(150, 0.05), (100, 0.1), (50, 0.2)]);
+    let mut aligners: Vec<Aligner> = sorted_cutoffs.iter().map(|(min_length, max_ppl)| {
+        Aligner::new(PENALTIES.0, PENALTIES.1, PENALTIES.2, *min_length, *max_ppl).unwrap()
+    }).collect();
+
+    info!("Local chaining");
+    assert_chaining_result(&mut chaining_aligner, &mut aligners, &reference, &queries);
+
+    info!("Semi-global chaining");
+    assert!(chaining_aligner.change_to_semi_global());
+    aligners.iter_mut().for_each(|aligner| { aligner.change_to_semi_global(); });
+    assert_chaining_result(&mut chaining_aligner, &mut aligners, &reference, &queries);
+}
+
+#[test]
+fn unordered_cutoffs_are_rejected() {
+    let result = Aligner::new_chaining(4, 6, 2, &[(100, 0.2), (50, 0.1)]);
+    assert!(result.is_err());
+    let result = Aligner::new_chaining(4, 6, 2, &[]);
+    assert!(result.is_err());
+}
+
+fn assert_chaining_result(
+    chaining_aligner: &mut Aligner,
+    aligners: &mut [Aligner],
+    reference: &Reference,
+    queries: &[Vec<u8>],
+) {
+    for query in queries {
+        let chaining_result = chaining_aligner.align_query(reference, query);
+        let results_by_tier: Vec<_> = aligners.iter_mut().map(|aligner| {
+            get_map_of_alignment_result(&aligner.align_query(reference, query))
+        }).collect();
+
+        let mut expected_targets = AHashSet::new();
+        results_by_tier.iter().for_each(|v| expected_targets.extend(v.keys().cloned()));
+        let mut chaining_targets = AHashSet::new();
+
+        for target_result in chaining_result.0 {
+            let tier = target_result.cutoff_tier as usize;
+            // Not aligned by stricter cutoffs
+            for stricter_result in &results_by_tier[..tier] {
+                assert!(!stricter_result.contains_key(&target_result.index));
+            }
+            let alignments: AHashSet<AnchorAlignmentResult> = target_result.alignments.into_iter().collect();
+            assert_eq!(results_by_tier[tier].get(&target_result.index), Some(&alignments));
+            chaining_targets.insert(target_result.index);
+        }
+        assert_eq!(chaining_targets, expected_targets);
+    }
+}
+
+fn get_map_of_alignment_result(alignment_result: &AlignmentResult) -> AHashMap<u32, AHashSet<AnchorAlignmentResult>> {
+    alignment_result.0.iter().map(|target_result| {
+        (target_result.index, target_result.alignments.iter().cloned().collect())
+    }).collect()
+}
+
+fn get_queries(qry_file: &std::path::Path) -> Vec<Vec<u8>> {
+    let mut queries = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(qry_file).unwrap();
+    while let Some(mut record) = fasta_reader.next() {
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        queries.push(query);
+        if queries.len() == NUM_QUERIES {
+            break;
+        }
+    }
+    queries
+}
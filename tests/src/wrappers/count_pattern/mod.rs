@@ -0,0 +1,56 @@
+use crate::common::{
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
+};
+
+const NUM_QUERIES: usize = 20;
+
+#[test]
+fn count_is_same_as_occurrences_in_concatenated_sequence() {
+    init_logger();
+
+    let fm_index_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let kmer_index_reference = ReferenceBuilder::new()
+        .use_kmer_index(6)
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    let concatenated_sequence = get_concatenated_sequence(&fm_index_reference);
+
+    let mut fasta_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    let mut query = Vec::new();
+    let mut query_count = 0;
+    while let Some(mut record) = fasta_reader.next() {
+        query.clear();
+        record.extend_seq_buf(&mut query);
+        for pattern_size in [3, 6, 10, 20] {
+            for pattern in query.chunks_exact(pattern_size).take(5) {
+                let answer = concatenated_sequence.windows(pattern.len()).filter(|window| {
+                    *window == pattern
+                }).count() as u64;
+                assert_eq!(answer, fm_index_reference.count_pattern(pattern));
+                assert_eq!(answer, kmer_index_reference.count_pattern(pattern));
+            }
+        }
+        query_count += 1;
+        if query_count >= NUM_QUERIES {
+            break;
+        }
+    }
+}
+
+fn get_concatenated_sequence(reference: &Reference) -> Vec<u8> {
+    (0..reference.get_num_targets()).flat_map(|target_index| {
+        reference.get_sequence(target_index).unwrap()
+    }).collect()
+}
@@ -0,0 +1,136 @@
+use crate::common::{
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use ahash::AHashSet;
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    results::{FastaAlignmentResult, ReadAlignmentResult, LabeledTargetAlignmentResult},
+};
+use sigalign_core::results::AnchorAlignmentResult;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord, IdRefRecord,
+};
+
+const NUM_QUERIES: usize = 50;
+
+#[test]
+fn fastq_results_are_identical_to_fasta_with_high_quality() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+    let (fasta_bytes, fastq_bytes) = get_fasta_and_fastq_bytes(b'I');
+
+    info!("Forward only");
+    let fasta_result = aligner.align_fasta(&reference, &fasta_bytes[..]);
+    assert!(!fasta_result.0.is_empty());
+    for min_quality in [None, Some(20)] {
+        let fastq_result = aligner.align_fastq(&reference, &fastq_bytes[..], min_quality);
+        assert_eq!(
+            get_set_of_fasta_alignment_result(&fasta_result),
+            get_set_of_fasta_alignment_result(&fastq_result),
+        );
+        for read_alignment_result in &fastq_result.0 {
+            assert!(read_alignment_result.quality.as_ref().unwrap().bytes().all(|x| x == b'I'));
+        }
+    }
+
+    info!("With reverse complementary");
+    let fasta_result = aligner.align_fasta_with_reverse_complementary(&reference, &fasta_bytes[..]);
+    let fastq_result = aligner.align_fastq_with_reverse_complementary(&reference, &fastq_bytes[..], Some(20));
+    assert_eq!(
+        get_set_of_fasta_alignment_result(&fasta_result),
+        get_set_of_fasta_alignment_result(&fastq_result),
+    );
+}
+
+#[test]
+fn low_quality_bases_are_masked() {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+    // '#' is the Phred score of 2
+    let (_, fastq_bytes) = get_fasta_and_fastq_bytes(b'#');
+
+    let unmasked_result = aligner.align_fastq(&reference, &fastq_bytes[..], None);
+    assert!(!unmasked_result.0.is_empty());
+    assert!(unmasked_result.0[0].quality.as_ref().unwrap().bytes().all(|x| x == b'#'));
+    let masked_result = aligner.align_fastq(&reference, &fastq_bytes[..], Some(2));
+    assert_eq!(
+        get_set_of_fasta_alignment_result(&unmasked_result),
+        get_set_of_fasta_alignment_result(&masked_result),
+    );
+    let masked_result = aligner.align_fastq(&reference, &fastq_bytes[..], Some(3));
+    assert!(masked_result.0.is_empty());
+}
+
+#[test]
+fn quality_is_reversed_for_reverse_complementary() {
+    let reference = ReferenceBuilder::new().add_fasta(&b">target\nACGTTGCAGTCGATCGTAGCTAGCTAGGATCGATGCACGTAGCTAGGGATCTAGCTTACG"[..]).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+    // Reverse complementary of the target
+    let query = b"CGTAAGCTAGATCCCTAGCTACGTGCATCGATCCTAGCTAGCTACGATCGACTGCAACGT";
+    let quality = format!("#{}5", "I".repeat(query.len() - 2));
+    let fastq_bytes = format!("@read\n{}\n+\n{}\n", String::from_utf8_lossy(query), quality).into_bytes();
+    let result = aligner.align_fastq_with_reverse_complementary(&reference, &fastq_bytes[..], None);
+    assert_eq!(result.0.len(), 1);
+    let ReadAlignmentResult { is_forward, quality, .. } = &result.0[0];
+    assert!(!is_forward);
+    let quality = quality.as_ref().unwrap();
+    assert!(quality.starts_with('5'));
+    assert!(quality.ends_with('#'));
+}
+
+fn get_fasta_and_fastq_bytes(quality_char: u8) -> (Vec<u8>, Vec<u8>) {
+    let mut fasta_bytes = Vec::new();
+    let mut fastq_bytes = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    let mut count = 0;
+    while let Some(mut record) = fasta_reader.next() {
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        let id = record.id_str().unwrap();
+        fasta_bytes.extend_from_slice(format!(">{}\n", id).as_bytes());
+        fasta_bytes.extend_from_slice(&query);
+        fasta_bytes.push(b'\n');
+        fastq_bytes.extend_from_slice(format!("@{}\n", id).as_bytes());
+        fastq_bytes.extend_from_slice(&query);
+        fastq_bytes.extend_from_slice(b"\n+\n");
+        fastq_bytes.extend(std::iter::repeat(quality_char).take(query.len()));
+        fastq_bytes.push(b'\n');
+        count += 1;
+        if count == NUM_QUERIES {
+            break;
+        }
+    }
+    (fasta_bytes, fastq_bytes)
+}
+
+fn get_set_of_fasta_alignment_result(fasta_alignment_result: &FastaAlignmentResult) -> AHashSet<(String, bool, u32, AnchorAlignmentResult)> {
+    let mut result_set = AHashSet::new();
+    for ReadAlignmentResult {
+        read,
+        is_forward,
+        result,
+        quality: _,
+    } in &fasta_alignment_result.0 {
+        for LabeledTargetAlignmentResult {
+            index,
+            label: _,
+            alignments,
+            cutoff_tier: _,
+            metadata: _,
+        } in &result.0 {
+            for alignment in alignments {
+                result_set.insert((read.clone(), *is_forward, *index, alignment.clone()));
+            }
+        }
+    }
+    result_set
+}
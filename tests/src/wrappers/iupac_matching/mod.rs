@@ -0,0 +1,173 @@
+use crate::common::{
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    results::{AnchorAlignmentResult, AlignmentOperation},
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
+};
+
+const NUM_QUERIES: usize = 50;
+
+#[test]
+fn ambiguous_match_is_separated_from_match_and_subst() {
+    init_logger();
+
+    let target = b"ACGTTGCAAGCTAGCTAGGATCCATGCAAGTCGTACGATCGATGCTAGCTAGTCGATCGTAGCTAGCATCGTAGCTAGCTG";
+    let mut ambiguous_target = target.to_vec();
+    ambiguous_target[40] = b'R'; // A/G
+    let fasta = format!(">ambiguous\n{}\n", String::from_utf8(ambiguous_target).unwrap());
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+
+    let mut aligner = Aligner::new_with_iupac_matching(4, 0, 6, 2, 50, 0.1).unwrap();
+    let result = aligner.align_query(&reference, target);
+    let alignment = &result.0[0].alignments[0];
+    assert_eq!(alignment.penalty, 0);
+    assert_eq!(
+        alignment.operations.iter().map(|x| (x.operation.clone(), x.count)).collect::<Vec<_>>(),
+        vec![
+            (AlignmentOperation::Match, 40),
+            (AlignmentOperation::AmbiguousMatch, 1),
+            (AlignmentOperation::Match, target.len() as u32 - 41),
+        ],
+    );
+
+    let mut aligner = Aligner::new_with_iupac_matching(4, 1, 6, 2, 50, 0.1).unwrap();
+    assert_eq!(aligner.get_ambiguous_match_penalty(), Some(1));
+    let result = aligner.align_query(&reference, target);
+    assert_eq!(result.0[0].alignments[0].penalty, 1);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let result = aligner.align_query(&reference, target);
+    assert_eq!(result.0[0].alignments[0].penalty, 4);
+
+    assert!(Aligner::new_with_iupac_matching(4, 4, 6, 2, 50, 0.1).is_err());
+}
+
+#[test]
+fn penalties_are_calculated_with_ambiguous_matches() {
+    init_logger();
+
+    // Every 37th base of the targets is changed to the IUPAC code including it.
+    let mut fasta = String::new();
+    let mut fasta_reader = FastaReader::from_path(get_ref_for_val_path()).unwrap();
+    let mut sequence = Vec::new();
+    let mut target_index = 0;
+    while let Some(mut record) = fasta_reader.next() {
+        sequence.clear();
+        record.extend_seq_buf(&mut sequence);
+        sequence.iter_mut().step_by(37).for_each(|base| {
+            *base = match base.to_ascii_uppercase() {
+                b'A' => b'R',
+                b'C' => b'Y',
+                b'G' => b'K',
+                b'T' => b'W',
+                other => other,
+            };
+        });
+        fasta.push_str(&format!(">{}\n{}\n", target_index, String::from_utf8(sequence.clone()).unwrap()));
+        target_index += 1;
+    }
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let (mismatch_penalty, gap_open_penalty, gap_extend_penalty) = (4, 6, 2);
+    for ambiguous_match_penalty in [0, 2] {
+        let mut aligner = Aligner::new_with_iupac_matching(
+            mismatch_penalty, ambiguous_match_penalty, gap_open_penalty, gap_extend_penalty, 50, 0.1,
+        ).unwrap();
+        let mut ambiguous_match_count = 0;
+        for change_to_semi_global in [false, true] {
+            info!("Ambiguous match penalty: {}, semi-global: {}", ambiguous_match_penalty, change_to_semi_global);
+            if change_to_semi_global {
+                aligner.change_to_semi_global();
+            }
+            for query in &queries {
+                let result = aligner.align_query(&reference, query);
+                for target_result in &result.0 {
+                    let target = reference.get_sequence(target_result.index).unwrap();
+                    for alignment in &target_result.alignments {
+                        let (penalty, count) = calculate_penalty(
+                            alignment, &target, query,
+                            mismatch_penalty, ambiguous_match_penalty, gap_open_penalty, gap_extend_penalty,
+                        );
+                        assert_eq!(penalty, alignment.penalty);
+                        ambiguous_match_count += count;
+                    }
+                }
+            }
+        }
+        assert!(ambiguous_match_count > 0);
+    }
+}
+
+// (penalty, number of ambiguous matches)
+fn calculate_penalty(
+    alignment: &AnchorAlignmentResult,
+    target: &[u8],
+    query: &[u8],
+    mismatch_penalty: u32,
+    ambiguous_match_penalty: u32,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+) -> (u32, u32) {
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.0 as usize;
+    let mut penalty = 0;
+    let mut ambiguous_match_count = 0;
+    for operations in &alignment.operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match => {
+                assert_eq!(query[query_index..query_index + count], target[target_index..target_index + count]);
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::AmbiguousMatch => {
+                for _ in 0..count {
+                    assert_ne!(query[query_index], target[target_index]);
+                    assert!(is_ambiguous_code_of(target[target_index], query[query_index]));
+                    query_index += 1;
+                    target_index += 1;
+                }
+                penalty += ambiguous_match_penalty * count as u32;
+                ambiguous_match_count += count as u32;
+            },
+            AlignmentOperation::Subst => {
+                for _ in 0..count {
+                    assert!(!is_ambiguous_code_of(target[target_index], query[query_index]));
+                    query_index += 1;
+                    target_index += 1;
+                }
+                penalty += mismatch_penalty * count as u32;
+            },
+            AlignmentOperation::Deletion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                target_index += count;
+            },
+            AlignmentOperation::Insertion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                query_index += count;
+            },
+        }
+    }
+    assert_eq!(query_index, alignment.position.query.1 as usize);
+    assert_eq!(target_index, alignment.position.target.1 as usize);
+    (penalty, ambiguous_match_count)
+}
+
+// Only the codes used in the test
+fn is_ambiguous_code_of(code: u8, base: u8) -> bool {
+    matches!(
+        (code, base),
+        (b'R', b'A') | (b'R', b'G') | (b'Y', b'C') | (b'Y', b'T')
+        | (b'K', b'G') | (b'K', b'T') | (b'W', b'A') | (b'W', b'T')
+    )
+}
@@ -0,0 +1,62 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn kmer_index_results_are_same_as_fm_index() {
+    init_logger();
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let pattern_size = aligner.get_pattern_size();
+    info!("Pattern size: {}", pattern_size);
+
+    let fm_index_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    for kmer_size in [pattern_size / 2, pattern_size, pattern_size + 1] {
+        info!("K-mer size: {}", kmer_size);
+        let kmer_index_reference = ReferenceBuilder::new()
+            .use_kmer_index(kmer_size)
+            .add_fasta_file(get_ref_for_val_path()).unwrap()
+            .build().unwrap();
+        // Save and load
+        let mut buffer = Vec::new();
+        kmer_index_reference.save_to(&mut buffer).unwrap();
+        let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+
+        for change_to_semi_global in [false, true] {
+            if change_to_semi_global {
+                aligner.change_to_semi_global();
+            } else {
+                aligner.change_to_local();
+            }
+            for query in &queries {
+                let answer = get_set_of_alignment_result(&aligner.align_query(&fm_index_reference, query));
+                assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&kmer_index_reference, query)));
+                assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&loaded_reference, query)));
+            }
+        }
+    }
+}
+
+#[test]
+fn invalid_kmer_size_is_error() {
+    for kmer_size in [0, 65] {
+        let result = ReferenceBuilder::new()
+            .use_kmer_index(kmer_size)
+            .add_fasta_file(get_ref_for_val_path()).unwrap()
+            .build();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    random_text_and_pattern::gen_rand_text,
+    peak_memory::measure_peak_memory,
+    init_logger,
+};
+use sigalign::{
+    ReferenceBuilder,
+    ReferenceBuildError,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 30;
+
+#[test]
+fn build_is_bounded_by_memory_budget() {
+    init_logger();
+
+    let builder = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap();
+    let estimated = builder.estimate_peak_memory();
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap()
+        .memory_budget(estimated)
+        .build().unwrap();
+    assert!(estimated > 17 * reference.get_total_length());
+
+    let result = builder.memory_budget(estimated - 1).build();
+    assert!(matches!(
+        result,
+        Err(ReferenceBuildError::MemoryBudgetExceeded { estimated: e, budget: b }) if e == estimated && b == estimated - 1
+    ));
+}
+
+#[test]
+fn estimated_peak_memory_is_not_less_than_measured() {
+    init_logger();
+
+    // FM-index
+    let builder = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap();
+    let estimated = builder.estimate_peak_memory();
+    let (_, measured) = measure_peak_memory(|| builder.build().unwrap());
+    assert!(measured <= estimated, "measured: {}, estimated: {}", measured, estimated);
+
+    // K-mer index of which all k-mers are distinct
+    let sequence = gen_rand_text(b"ACGT", 1_000_000, 1_000_000);
+    let fasta = format!(">random\n{}\n", String::from_utf8(sequence).unwrap());
+    let builder = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().use_kmer_index(16);
+    let estimated = builder.estimate_peak_memory();
+    let (_, measured) = measure_peak_memory(|| builder.build().unwrap());
+    assert!(measured <= estimated, "measured: {}, estimated: {}", measured, estimated);
+    assert!(estimated < 2 * measured, "measured: {}, estimated: {}", measured, estimated);
+}
+
+#[test]
+fn built_shards_are_counted_in_memory_budget() {
+    init_logger();
+
+    // The k-mer indices kept after building take more than the working memory of a shard.
+    let builder = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().use_kmer_index(12);
+    let memory_budget = builder.estimate_peak_memory() / 2;
+    let result = builder.memory_budget(memory_budget).build_collection();
+    assert!(matches!(
+        result,
+        Err(ReferenceBuildError::MemoryBudgetExceeded { estimated, budget }) if estimated > budget && budget == memory_budget
+    ));
+}
+
+#[test]
+fn collection_built_in_parallel_is_aligned_as_one_reference() {
+    init_logger();
+
+    let whole_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let builder = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().num_threads(2);
+    // Half of the memory to build at once
+    let memory_budget = builder.estimate_peak_memory() / 2;
+    let collection = builder.memory_budget(memory_budget).build_collection().unwrap();
+    assert!(collection.get_num_shards() > 2);
+    assert_eq!(collection.get_num_targets(), whole_reference.get_num_targets());
+    for target_index in 0..whole_reference.get_num_targets() {
+        assert_eq!(collection.get_label(target_index).unwrap(), whole_reference.get_label(target_index));
+    }
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let queries = get_queries(NUM_QUERIES);
+    let results = aligner.align_queries_to_collection(&collection, &queries).unwrap();
+    for (query, result) in queries.iter().zip(&results) {
+        assert_eq!(
+            get_set_of_alignment_result(&aligner.align_query(&whole_reference, query)),
+            get_set_of_alignment_result(result),
+        );
+    }
+
+    // A target is too long to fit in the budget
+    let result = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap()
+        .memory_budget(memory_budget / 100)
+        .build_collection();
+    assert!(matches!(result, Err(ReferenceBuildError::MemoryBudgetExceeded { .. })));
+}
@@ -0,0 +1,99 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_dir_on_tmp_dir,
+    },
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 30;
+
+#[test]
+fn memory_mapped_reference_is_same_as_loaded() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    assert!(!reference.is_memory_mapped());
+    let mut path = get_dir_on_tmp_dir("memory_mapped_reference").unwrap();
+    path.push("reference.sigref");
+    reference.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+
+    let mapped_reference = Reference::open_mmap(&path).unwrap();
+    assert!(mapped_reference.is_memory_mapped());
+    assert_eq!(mapped_reference.get_num_targets(), reference.get_num_targets());
+    assert_eq!(mapped_reference.get_total_length(), reference.get_total_length());
+    for target_index in 0..reference.get_num_targets() {
+        assert_eq!(mapped_reference.get_sequence(target_index), reference.get_sequence(target_index));
+        assert_eq!(mapped_reference.get_label(target_index), reference.get_label(target_index));
+    }
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for query in get_queries(NUM_QUERIES) {
+        assert_eq!(
+            get_set_of_alignment_result(&aligner.align_query(&reference, &query)),
+            get_set_of_alignment_result(&aligner.align_query(&mapped_reference, &query)),
+        );
+    }
+
+    // Saved again from the mapped reference
+    let mut saved = Vec::new();
+    reference.save_to(&mut saved).unwrap();
+    let mut saved_from_mapped = Vec::new();
+    mapped_reference.save_to(&mut saved_from_mapped).unwrap();
+    assert_eq!(saved, saved_from_mapped);
+}
+
+#[test]
+fn kmer_index_is_loaded_to_memory() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .use_kmer_index(16)
+        .build().unwrap();
+    let mut path = get_dir_on_tmp_dir("memory_mapped_reference").unwrap();
+    path.push("kmer_index_reference.sigref");
+    reference.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+
+    let mapped_reference = Reference::open_mmap(&path).unwrap();
+    assert!(mapped_reference.is_memory_mapped());
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for query in get_queries(NUM_QUERIES) {
+        assert_eq!(
+            get_set_of_alignment_result(&aligner.align_query(&reference, &query)),
+            get_set_of_alignment_result(&aligner.align_query(&mapped_reference, &query)),
+        );
+    }
+}
+
+#[test]
+fn packed_sequences_are_loaded_to_memory() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .use_packed_nucleotide_storage()
+        .build().unwrap();
+    let mut path = get_dir_on_tmp_dir("memory_mapped_reference").unwrap();
+    path.push("packed_storage_reference.sigref");
+    reference.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+
+    let mapped_reference = Reference::open_mmap(&path).unwrap();
+    assert!(!mapped_reference.is_memory_mapped());
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for query in get_queries(NUM_QUERIES) {
+        assert_eq!(
+            get_set_of_alignment_result(&aligner.align_query(&reference, &query)),
+            get_set_of_alignment_result(&aligner.align_query(&mapped_reference, &query)),
+        );
+    }
+}
@@ -1,4 +1,27 @@
 mod validate_result_with_stable_version;
 mod validate_result_with_limit;
+mod validate_result_with_dp_matrix;
 
-mod serialize_reference;
\ No newline at end of file
+mod serialize_reference;
+mod parallel_alignments;
+mod chaining_alignment;
+mod substitution_matrix;
+mod two_piece_gap;
+mod alignment_stats;
+mod streaming_alignments;
+mod fastq_alignment;
+mod strand_alignment;
+mod quality_aware_mismatch;
+mod kmer_index;
+mod packed_nucleotide_storage;
+mod block_compressed_storage;
+mod pattern_occurrence_cap;
+mod count_pattern;
+mod soft_mask_lowercase;
+mod iupac_matching;
+mod translated_search;
+mod reference_collection;
+mod memory_bounded_build;
+mod pattern_location_cache;
+mod memory_mapped_reference;
+mod target_metadata;
@@ -0,0 +1,63 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn packed_storage_results_are_same_as_in_memory_storage() {
+    init_logger();
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let in_memory_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let packed_reference = ReferenceBuilder::new()
+        .use_packed_nucleotide_storage()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    // Save and load
+    let mut buffer = Vec::new();
+    packed_reference.save_to(&mut buffer).unwrap();
+    let mut in_memory_buffer = Vec::new();
+    in_memory_reference.save_to(&mut in_memory_buffer).unwrap();
+    assert!(buffer.len() < in_memory_buffer.len());
+    let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+
+    for target_index in 0..in_memory_reference.get_num_targets() {
+        assert_eq!(in_memory_reference.get_sequence(target_index), packed_reference.get_sequence(target_index));
+        assert_eq!(in_memory_reference.get_label(target_index), loaded_reference.get_label(target_index));
+    }
+    for query in get_queries(NUM_QUERIES) {
+        let answer = get_set_of_alignment_result(&aligner.align_query(&in_memory_reference, &query));
+        assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&packed_reference, &query)));
+        assert_eq!(answer, get_set_of_alignment_result(&aligner.align_query(&loaded_reference, &query)));
+    }
+}
+
+#[test]
+fn sequence_buffer_is_replaced_for_packed_storage() {
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let in_memory_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let packed_reference = ReferenceBuilder::new()
+        .use_packed_nucleotide_storage()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    // The same buffer is used for both storages
+    let mut sequence_buffer = Reference::get_sequence_buffer();
+    for query in get_queries(10) {
+        let answer = get_set_of_alignment_result(
+            &aligner.align_query_with_sequence_buffer(&in_memory_reference, &mut sequence_buffer, &query)
+        );
+        let result = get_set_of_alignment_result(
+            &aligner.align_query_with_sequence_buffer(&packed_reference, &mut sequence_buffer, &query)
+        );
+        assert_eq!(answer, result);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use ahash::AHashSet;
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    results::{FastaAlignmentResult, ReadAlignmentResult, LabeledTargetAlignmentResult},
+};
+use sigalign_core::results::{AnchorAlignmentResult};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord, IdRefRecord,
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn results_of_threaded_alignment_are_identical_to_serial() {
+    init_logger();
+
+    let ref_file = get_ref_for_val_path();
+    let qry_file = get_qry_for_val_path();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(&ref_file).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+
+    let mut queries = Vec::new();
+    let mut fasta_bytes = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(&qry_file).unwrap();
+    while let Some(mut record) = fasta_reader.next() {
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        fasta_bytes.extend_from_slice(format!(">{}\n", record.id_str().unwrap()).as_bytes());
+        fasta_bytes.extend_from_slice(&query);
+        fasta_bytes.push(b'\n');
+        queries.push(query);
+        if queries.len() == NUM_QUERIES {
+            break;
+        }
+    }
+
+    info!("Align queries");
+    let serial_results = aligner.align_queries(&reference, &queries);
+    for num_threads in [1, 3, 0] {
+        let threaded_results = aligner.align_queries_with_threads(&reference, &queries, num_threads);
+        assert_eq!(serial_results.len(), threaded_results.len());
+        for (serial, threaded) in serial_results.iter().zip(threaded_results.iter()) {
+            assert_eq!(
+                get_set_of_alignment_result(serial),
+                get_set_of_alignment_result(threaded),
+            );
+        }
+    }
+
+    info!("Align FASTA");
+    let serial_result = aligner.align_fasta(&reference, &fasta_bytes[..]);
+    for num_threads in [1, 4] {
+        let threaded_result = aligner.align_fasta_with_threads(&reference, &fasta_bytes[..], num_threads);
+        assert_eq!(serial_result.0.len(), threaded_result.0.len());
+        for (serial, threaded) in serial_result.0.iter().zip(threaded_result.0.iter()) {
+            assert_eq!(serial.read, threaded.read);
+        }
+        assert_eq!(
+            get_set_of_fasta_alignment_result(&serial_result),
+            get_set_of_fasta_alignment_result(&threaded_result),
+        );
+    }
+}
+
+fn get_set_of_fasta_alignment_result(fasta_alignment_result: &FastaAlignmentResult) -> AHashSet<(String, u32, AnchorAlignmentResult)> {
+    let mut result_set = AHashSet::new();
+    for ReadAlignmentResult {
+        read,
+        is_forward: _,
+        result,
+        quality: _,
+    } in &fasta_alignment_result.0 {
+        for LabeledTargetAlignmentResult {
+            index,
+            label: _,
+            alignments,
+            cutoff_tier: _,
+            metadata: _,
+        } in &result.0 {
+            for alignment in alignments {
+                result_set.insert((read.clone(), *index, alignment.clone()));
+            }
+        }
+    }
+    result_set
+}
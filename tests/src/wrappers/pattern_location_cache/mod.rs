@@ -0,0 +1,77 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 30;
+
+#[test]
+fn cached_reference_gives_same_result() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut cached_reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    assert_eq!(cached_reference.get_pattern_location_cache_stats(), None);
+    // Small capacity to test the eviction
+    let capacity = 16 * 1024;
+    cached_reference.set_pattern_location_cache_capacity(Some(capacity));
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        // Each query is aligned twice to hit the cache
+        for query in queries.iter().chain(queries.iter()) {
+            assert_eq!(
+                get_set_of_alignment_result(&aligner.align_query(&reference, query)),
+                get_set_of_alignment_result(&aligner.align_query(&cached_reference, query)),
+            );
+        }
+    }
+    let cache_stats = cached_reference.get_pattern_location_cache_stats().unwrap();
+    assert!(cache_stats.hit_count > 0);
+    assert!(cache_stats.miss_count > 0);
+    assert!(cache_stats.entry_count > 0);
+    assert!(cache_stats.size <= capacity);
+}
+
+#[test]
+fn cache_counts_are_reported_in_stats() {
+    init_logger();
+
+    let mut reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let query = &get_queries(NUM_QUERIES)[0];
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+
+    let (_, stats) = aligner.align_query_with_stats(&reference, query);
+    assert_eq!(stats.pattern_cache_hit_count, 0);
+    assert_eq!(stats.pattern_cache_miss_count, 0);
+
+    reference.set_pattern_location_cache_capacity(Some(1024 * 1024));
+    let (first_result, first_stats) = aligner.align_query_with_stats(&reference, query);
+    // The same pattern can be repeated in the query.
+    assert!(first_stats.pattern_cache_miss_count > 0);
+    assert_eq!(
+        first_stats.pattern_cache_hit_count + first_stats.pattern_cache_miss_count,
+        first_stats.pattern_count,
+    );
+    let (second_result, second_stats) = aligner.align_query_with_stats(&reference, query);
+    assert_eq!(second_stats.pattern_cache_hit_count, second_stats.pattern_count);
+    assert_eq!(second_stats.pattern_cache_miss_count, 0);
+    assert_eq!(first_stats.pattern_occurrence_count, second_stats.pattern_occurrence_count);
+    assert_eq!(
+        get_set_of_alignment_result(&first_result),
+        get_set_of_alignment_result(&second_result),
+    );
+
+    reference.set_pattern_location_cache_capacity(None);
+    assert_eq!(reference.get_pattern_location_cache_stats(), None);
+}
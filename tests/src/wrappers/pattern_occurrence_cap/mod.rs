@@ -0,0 +1,112 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+};
+
+const NUM_QUERIES: usize = 50;
+
+#[test]
+fn large_cap_is_same_as_exact_mode() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut exact_aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    assert_eq!(exact_aligner.get_pattern_occurrence_cap(), None);
+    let mut capped_aligner = exact_aligner.clone();
+    capped_aligner.set_pattern_occurrence_cap(Some(u32::MAX));
+
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            exact_aligner.change_to_semi_global();
+            capped_aligner.change_to_semi_global();
+        }
+        assert_eq!(capped_aligner.get_pattern_occurrence_cap(), Some(u32::MAX));
+        for query in &queries {
+            let (result, stats) = capped_aligner.align_query_with_stats(&reference, query);
+            assert_eq!(
+                get_set_of_alignment_result(&exact_aligner.align_query(&reference, query)),
+                get_set_of_alignment_result(&result),
+            );
+            assert_eq!(stats.over_cap_pattern_count, 0);
+        }
+    }
+}
+
+#[test]
+fn patterns_over_cap_are_dropped() {
+    init_logger();
+
+    // A unit repeated in the target
+    let repeat_unit = b"ACGTTGCAAGCTAGCTAGGATCCATGCAAGTCGTACGATCGATGCTAGCTAGTCGATCGTAGCTAGCATCGTAGCTAGCTG";
+    let repeat_count = 10;
+    let target: Vec<u8> = repeat_unit.iter().cycle().take(repeat_unit.len() * repeat_count).copied().collect();
+    let fasta = format!(">repeat\n{}\n", String::from_utf8(target.clone()).unwrap());
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+    let query = &target[..repeat_unit.len() * 2];
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let (result, stats) = aligner.align_query_with_stats(&reference, query);
+    assert!(result.count_alignments() > 0);
+    assert_eq!(stats.over_cap_pattern_count, 0);
+
+    // Every pattern of the query occurs at least `repeat_count - 1` times.
+    aligner.set_pattern_occurrence_cap(Some(repeat_count as u32 - 2));
+    let (result, stats) = aligner.align_query_with_stats(&reference, query);
+    assert_eq!(result.count_alignments(), 0);
+    assert_eq!(stats.over_cap_pattern_count, stats.pattern_count);
+    assert_eq!(stats.located_pattern_count, 0);
+
+    aligner.set_pattern_occurrence_cap(None);
+    let (result, _) = aligner.align_query_with_stats(&reference, query);
+    assert!(result.count_alignments() > 0);
+}
+
+#[test]
+fn extension_over_patterns_over_cap() {
+    init_logger();
+
+    // Target: unique flanks around a segment repeated in the other targets
+    let sequence = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap()
+        .get_sequence(0).unwrap();
+    let (left_flank, repeat, right_flank) = (&sequence[..300], &sequence[300..400], &sequence[400..700]);
+    let mut fasta = format!(
+        ">flanked\n{}{}{}\n",
+        String::from_utf8(left_flank.to_vec()).unwrap(),
+        String::from_utf8(repeat.to_vec()).unwrap(),
+        String::from_utf8(right_flank.to_vec()).unwrap(),
+    );
+    for copy_index in 0..5 {
+        fasta.push_str(&format!(">copy_{}\n{}\n", copy_index, String::from_utf8(repeat.to_vec()).unwrap()));
+    }
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+
+    // The repeated segment is traversed by the extensions from both flanks,
+    // and the matches in the middle of the segment have only the patterns over the cap.
+    let mut query = [&left_flank[150..], repeat, &right_flank[..150]].concat();
+    for position in [20, 170, 230, query.len() - 20] {
+        query[position] = if query[position] == b'A' { b'C' } else { b'A' };
+    }
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    aligner.set_pattern_occurrence_cap(Some(2));
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        let (result, stats) = aligner.align_query_with_stats(&reference, &query);
+        assert!(stats.over_cap_pattern_count > 0);
+        let result = get_set_of_alignment_result(&result);
+        assert!(result.iter().any(|(target_index, alignment)| {
+            *target_index == 0 && alignment.position.query == (0, query.len() as u32)
+        }));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    QueryQualitiesError,
+    SubstitutionMatrix,
+    results::{AnchorAlignmentResult, AlignmentOperation},
+};
+
+const NUM_QUERIES: usize = 100;
+const PHRED_OFFSET: u8 = 33;
+const QUALITY_CAP: u8 = 40;
+
+#[test]
+fn highest_qualities_are_same_as_no_qualities() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut aligner = Aligner::new_with_quality_aware_mismatch(6, 2, 6, 2, 50, 0.15).unwrap();
+    assert_eq!(aligner.get_mismatch_penalty(), 6);
+    assert_eq!(aligner.get_minimum_mismatch_penalty(), Some(2));
+
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        for query in &queries {
+            let qualities = vec![PHRED_OFFSET + QUALITY_CAP; query.len()];
+            assert_eq!(
+                get_set_of_alignment_result(&aligner.align_query(&reference, query)),
+                get_set_of_alignment_result(&aligner.align_query_with_qualities(&reference, query, &qualities).unwrap()),
+            );
+        }
+    }
+}
+
+#[test]
+fn penalties_are_calculated_with_qualities() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let (mismatch_penalty, min_mismatch_penalty) = (6, 2);
+    let (gap_open_penalty, gap_extend_penalty) = (6, 2);
+    let (min_length, max_penalty_per_length) = (50, 0.15);
+    let mut aligner = Aligner::new_with_quality_aware_mismatch(
+        mismatch_penalty,
+        min_mismatch_penalty,
+        gap_open_penalty,
+        gap_extend_penalty,
+        min_length,
+        max_penalty_per_length,
+    ).unwrap();
+
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        let mut alignment_count = 0;
+        for query in &queries {
+            let qualities: Vec<u8> = (0..query.len()).map(|i| PHRED_OFFSET + (i * 7 % 41) as u8).collect();
+            let result = aligner.align_query_with_qualities(&reference, query, &qualities).unwrap();
+            for target_result in result.0 {
+                let target = reference.get_sequence(target_result.index).unwrap();
+                for alignment in target_result.alignments {
+                    let penalty = calculate_penalty(
+                        &alignment, query, &qualities, &target,
+                        mismatch_penalty, min_mismatch_penalty, gap_open_penalty, gap_extend_penalty,
+                    );
+                    assert_eq!(alignment.penalty, penalty);
+                    assert!(alignment.length >= min_length);
+                    assert!(alignment.penalty as f32 / alignment.length as f32 <= max_penalty_per_length);
+                    alignment_count += 1;
+                }
+            }
+        }
+        info!("Alignment count: {}", alignment_count);
+        assert!(alignment_count > 0);
+    }
+}
+
+#[test]
+fn invalid_qualities_are_rejected() {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let query = get_queries(NUM_QUERIES).remove(0);
+
+    let mut aligner = Aligner::new_with_quality_aware_mismatch(6, 2, 6, 2, 50, 0.15).unwrap();
+    let shorter_qualities = vec![PHRED_OFFSET + QUALITY_CAP; query.len() - 1];
+    assert!(matches!(
+        aligner.align_query_with_qualities(&reference, &query, &shorter_qualities),
+        Err(QueryQualitiesError::LengthMismatch { .. }),
+    ));
+
+    let substitution_matrix = SubstitutionMatrix::new_transition_transversion(2, 6).unwrap();
+    let mut aligner = Aligner::new_with_substitution_matrix(substitution_matrix, 6, 2, 50, 0.15).unwrap();
+    let qualities = vec![PHRED_OFFSET + QUALITY_CAP; query.len()];
+    assert!(matches!(
+        aligner.align_query_with_qualities(&reference, &query, &qualities),
+        Err(QueryQualitiesError::WithSubstitutionMatrix),
+    ));
+}
+
+fn mismatch_penalty_of_quality(quality: u8, mismatch_penalty: u32, min_mismatch_penalty: u32) -> u32 {
+    let phred = quality.saturating_sub(PHRED_OFFSET).min(QUALITY_CAP) as u32;
+    let cap = QUALITY_CAP as u32;
+    min_mismatch_penalty + ((mismatch_penalty - min_mismatch_penalty) * phred + cap / 2) / cap
+}
+
+#[allow(clippy::too_many_arguments)]
+fn calculate_penalty(
+    alignment: &AnchorAlignmentResult,
+    query: &[u8],
+    qualities: &[u8],
+    target: &[u8],
+    mismatch_penalty: u32,
+    min_mismatch_penalty: u32,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+) -> u32 {
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.0 as usize;
+    let mut penalty = 0;
+    for operations in &alignment.operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match => {
+                assert_eq!(query[query_index..query_index + count], target[target_index..target_index + count]);
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::Subst => {
+                for _ in 0..count {
+                    penalty += mismatch_penalty_of_quality(qualities[query_index], mismatch_penalty, min_mismatch_penalty);
+                    query_index += 1;
+                    target_index += 1;
+                }
+            },
+            AlignmentOperation::Deletion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                target_index += count;
+            },
+            AlignmentOperation::Insertion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                query_index += count;
+            },
+            AlignmentOperation::AmbiguousMatch => unreachable!(),
+        }
+    }
+    assert_eq!(query_index, alignment.position.query.1 as usize);
+    assert_eq!(target_index, alignment.position.target.1 as usize);
+    penalty
+}
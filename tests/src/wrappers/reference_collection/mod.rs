@@ -0,0 +1,131 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_dir_on_tmp_dir,
+    },
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    ReferenceCollection,
+    Aligner,
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord, IdRefRecord,
+};
+
+const NUM_QUERIES: usize = 30;
+const NUM_SHARDS: usize = 3;
+
+#[test]
+fn collection_is_aligned_as_one_reference() {
+    init_logger();
+
+    // Split the FASTA records into shards
+    let mut fasta_of_shards = vec![String::new(); NUM_SHARDS];
+    let mut fasta_reader = FastaReader::from_path(get_ref_for_val_path()).unwrap();
+    let mut record_count = 0;
+    let mut sequence = Vec::new();
+    while let Some(mut record) = fasta_reader.next() {
+        sequence.clear();
+        record.extend_seq_buf(&mut sequence);
+        let fasta_record = format!(">{}\n{}\n", record.id_str().unwrap(), String::from_utf8(sequence.clone()).unwrap());
+        // Shards of different sizes: 1/6, 2/6, 3/6
+        let shard_index = match record_count % 6 { 0 => 0, 1 | 2 => 1, _ => 2 };
+        fasta_of_shards[shard_index].push_str(&fasta_record);
+        record_count += 1;
+    }
+    // Whole reference with the targets in the order of shards
+    let whole_reference = ReferenceBuilder::new().add_fasta(fasta_of_shards.concat().as_bytes()).unwrap().build().unwrap();
+    let shards: Vec<Reference> = fasta_of_shards.iter().map(|fasta| {
+        ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap()
+    }).collect();
+
+    // In memory, loaded from files, and lazy
+    let tmp_dir = get_dir_on_tmp_dir("reference_collection").unwrap();
+    let shard_paths: Vec<_> = shards.iter().enumerate().map(|(shard_index, shard)| {
+        let mut path = tmp_dir.clone();
+        path.push(format!("shard_{}.sigref", shard_index));
+        shard.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+        path
+    }).collect();
+    let mut in_memory_collection = ReferenceCollection::new();
+    shards.into_iter().for_each(|shard| in_memory_collection.add_reference(shard));
+    let loaded_collection = ReferenceCollection::from_reference_files(&shard_paths, false).unwrap();
+    let lazy_collection = ReferenceCollection::from_reference_files(&shard_paths, true).unwrap();
+
+    let queries = get_queries(NUM_QUERIES);
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let expected_results: Vec<_> = queries.iter().map(|query| aligner.align_query(&whole_reference, query)).collect();
+    for collection in [&in_memory_collection, &loaded_collection, &lazy_collection] {
+        assert_eq!(collection.get_num_shards(), NUM_SHARDS);
+        assert_eq!(collection.get_num_targets(), whole_reference.get_num_targets());
+        for target_index in [0, record_count / 3, record_count - 1] {
+            assert_eq!(collection.get_sequence(target_index).unwrap(), whole_reference.get_sequence(target_index));
+            assert_eq!(collection.get_label(target_index).unwrap(), whole_reference.get_label(target_index));
+        }
+        assert_eq!(collection.get_sequence(record_count).unwrap(), None);
+
+        let results = aligner.align_queries_to_collection(collection, &queries).unwrap();
+        for ((query, expected_result), result) in queries.iter().zip(&expected_results).zip(&results) {
+            assert_eq!(get_set_of_alignment_result(expected_result), get_set_of_alignment_result(result));
+            assert_eq!(
+                get_set_of_alignment_result(expected_result),
+                get_set_of_alignment_result(&aligner.align_query_to_collection(collection, query).unwrap()),
+            );
+            let labeled_result = aligner.align_query_labeled_to_collection(collection, query).unwrap();
+            for target_result in labeled_result.0 {
+                assert_eq!(Some(target_result.label), whole_reference.get_label(target_result.index));
+            }
+        }
+    }
+}
+
+#[test]
+fn limit_is_applied_to_the_merged_shards() {
+    let (collection, _) = get_lazy_collection_of_shards("reference_collection_limit");
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let limit = 2;
+    aligner.set_limit(Some(limit));
+    let results = aligner.align_queries_to_collection(&collection, &get_queries(NUM_QUERIES)).unwrap();
+    assert!(results.iter().any(|result| result.count_alignments() == limit as usize));
+    assert!(results.iter().all(|result| result.count_alignments() <= limit as usize));
+}
+
+#[test]
+fn last_lazy_shard_is_not_loaded_again() {
+    let (collection, shard_paths) = get_lazy_collection_of_shards("reference_collection_lazy");
+    let last_target_index = collection.get_num_targets() - 1;
+    let label = collection.get_label(last_target_index).unwrap();
+    assert!(label.is_some());
+
+    // The last shard is kept after the use
+    std::fs::remove_file(shard_paths.last().unwrap()).unwrap();
+    assert_eq!(collection.get_label(last_target_index).unwrap(), label);
+    collection.release_loaded_shard();
+    assert!(collection.get_label(last_target_index).is_err());
+}
+
+// Each target of the reference for validation in its own shard
+fn get_lazy_collection_of_shards(dir_name: &str) -> (ReferenceCollection, Vec<std::path::PathBuf>) {
+    let tmp_dir = get_dir_on_tmp_dir(dir_name).unwrap();
+    let mut fasta_reader = FastaReader::from_path(get_ref_for_val_path()).unwrap();
+    let mut sequence = Vec::new();
+    let mut shard_paths = Vec::new();
+    while let Some(mut record) = fasta_reader.next() {
+        sequence.clear();
+        record.extend_seq_buf(&mut sequence);
+        let fasta_record = format!(">{}\n{}\n", record.id_str().unwrap(), String::from_utf8(sequence.clone()).unwrap());
+        let shard = ReferenceBuilder::new().add_fasta(fasta_record.as_bytes()).unwrap().build().unwrap();
+        let mut path = tmp_dir.clone();
+        path.push(format!("shard_{}.sigref", shard_paths.len()));
+        shard.save_to(std::fs::File::create(&path).unwrap()).unwrap();
+        shard_paths.push(path);
+    }
+    let collection = ReferenceCollection::from_reference_files(&shard_paths, true).unwrap();
+    (collection, shard_paths)
+}
@@ -0,0 +1,94 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+};
+
+#[test]
+fn soft_masked_regions_are_not_seeded_but_extended() {
+    init_logger();
+
+    // Target: 300 unmasked bases followed by 300 soft-masked bases
+    let sequence = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap()
+        .get_sequence(0).unwrap();
+    let sequence = &sequence[..600];
+    let fasta = format!(
+        ">soft_masked\n{}{}\n",
+        String::from_utf8(sequence[..300].to_vec()).unwrap(),
+        String::from_utf8(sequence[300..].to_ascii_lowercase()).unwrap(),
+    );
+    let unmasked_reference = ReferenceBuilder::new()
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+    let soft_masked_reference = ReferenceBuilder::new()
+        .soft_mask_lowercase(true)
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+    let mut buffer = Vec::new();
+    soft_masked_reference.save_to(&mut buffer).unwrap();
+    let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+
+    // Soft-masked bases are uppercased
+    assert_eq!(soft_masked_reference.get_sequence(0).unwrap(), sequence);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for reference in [&soft_masked_reference, &loaded_reference] {
+        // Seeded in the unmasked region and extended to the masked region
+        let query = &sequence[100..500];
+        let result = aligner.align_query(reference, query);
+        assert!(result.count_alignments() > 0);
+        assert_eq!(
+            get_set_of_alignment_result(&aligner.align_query(&unmasked_reference, query)),
+            get_set_of_alignment_result(&result),
+        );
+        // Never seeded in the masked region
+        let query = &sequence[350..550];
+        assert!(aligner.align_query(&unmasked_reference, query).count_alignments() > 0);
+        assert_eq!(aligner.align_query(reference, query).count_alignments(), 0);
+    }
+}
+
+#[test]
+fn extension_over_soft_masked_region_to_the_left() {
+    init_logger();
+
+    // Target: 300 soft-masked bases followed by 300 unmasked bases
+    let sequence = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap()
+        .get_sequence(0).unwrap();
+    let sequence = &sequence[..600];
+    let fasta = format!(
+        ">soft_masked\n{}{}\n",
+        String::from_utf8(sequence[..300].to_ascii_lowercase()).unwrap(),
+        String::from_utf8(sequence[300..].to_vec()).unwrap(),
+    );
+    let unmasked_reference = ReferenceBuilder::new()
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+    let soft_masked_reference = ReferenceBuilder::new()
+        .soft_mask_lowercase(true)
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+
+    // The exact match run in the masked region is traversed by the left extension
+    let mut query = sequence[150..550].to_vec();
+    query[20] = if query[20] == b'A' { b'C' } else { b'A' };
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        // Alignments seeded in the masked region are excluded
+        let result = get_set_of_alignment_result(&aligner.align_query(&soft_masked_reference, &query));
+        assert!(result.iter().any(|(_, alignment)| alignment.position.query == (0, query.len() as u32)));
+        assert!(result.is_subset(&get_set_of_alignment_result(&aligner.align_query(&unmasked_reference, &query))));
+    }
+}
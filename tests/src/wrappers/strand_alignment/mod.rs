@@ -0,0 +1,166 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    StrandMode,
+    results::{AnchorAlignmentResult, AlignmentOperation, Strand},
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord,
+};
+
+const NUM_QUERIES: usize = 30;
+
+#[test]
+fn both_strands_are_aligned_in_one_call() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let mut reverse_aligned_count = 0;
+    for query in get_queries_of_both_strands() {
+        aligner.set_strand_mode(StrandMode::Forward);
+        let forward_result = aligner.align_query(&reference, &query);
+        let reverse_result = aligner.align_query(&reference, reverse_complementary(&query));
+        let mut expected = get_set_of_alignment_result(&forward_result);
+        expected.extend(
+            get_set_of_alignment_result(&reverse_result).into_iter().map(|(index, mut alignment)| {
+                alignment.strand = Strand::Reverse;
+                (index, alignment)
+            })
+        );
+        reverse_aligned_count += reverse_result.count_alignments();
+
+        aligner.set_strand_mode(StrandMode::Both);
+        let both_strands_result = aligner.align_query(&reference, &query);
+        assert_eq!(get_set_of_alignment_result(&both_strands_result), expected);
+
+        aligner.set_strand_mode(StrandMode::BothNormalized);
+        let mut normalized_result = aligner.align_query(&reference, &query);
+        for target_result in &normalized_result.0 {
+            let target = reference.get_sequence(target_result.index).unwrap();
+            for alignment in &target_result.alignments {
+                if alignment.strand == Strand::Reverse {
+                    assert_normalized_alignment_is_valid(alignment, &query, &target);
+                }
+            }
+        }
+        normalized_result.flip_query_orientation_of_reverse_strand(query.len() as u32);
+        assert_eq!(get_set_of_alignment_result(&normalized_result), expected);
+    }
+    info!("Reverse aligned: {}", reverse_aligned_count);
+    assert!(reverse_aligned_count > 0);
+}
+
+#[test]
+fn strand_is_forward_by_default() {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    assert_eq!(aligner.get_strand_mode(), StrandMode::Forward);
+    for query in get_queries_of_both_strands() {
+        let result = aligner.align_query(&reference, &query);
+        assert!(result.0.iter().all(|x| x.alignments.iter().all(|y| y.strand == Strand::Forward)));
+    }
+}
+
+#[test]
+fn limit_is_applied_to_the_merged_strands() {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    aligner.set_strand_mode(StrandMode::Both);
+    let limit = 2;
+    aligner.set_limit(Some(limit));
+    assert_eq!(aligner.get_limit(), Some(limit));
+    for query in get_queries_of_both_strands() {
+        let result = aligner.align_query(&reference, &query);
+        assert!(result.count_alignments() <= limit as usize);
+    }
+}
+
+#[test]
+fn pattern_locations_are_shared_between_strands_by_cache() {
+    let mut reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    aligner.set_strand_mode(StrandMode::Both);
+    for query in get_queries_of_both_strands() {
+        // The reverse complementary of the palindrome has the same patterns.
+        let palindrome = [&query[..], &reverse_complementary(&query)].concat();
+
+        reference.set_pattern_location_cache_capacity(None);
+        let (answer, stats) = aligner.align_query_with_stats(&reference, &palindrome);
+        assert_eq!(stats.pattern_cache_hit_count, 0);
+
+        reference.set_pattern_location_cache_capacity(Some(1 << 24));
+        let (result, stats) = aligner.align_query_with_stats(&reference, &palindrome);
+        assert!(stats.pattern_cache_hit_count * 2 >= stats.pattern_count);
+        assert_eq!(get_set_of_alignment_result(&answer), get_set_of_alignment_result(&result));
+    }
+}
+
+// Walk the original query forward and the target backward.
+fn assert_normalized_alignment_is_valid(
+    alignment: &AnchorAlignmentResult,
+    query: &[u8],
+    target: &[u8],
+) {
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.1 as usize;
+    for operations in &alignment.operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match | AlignmentOperation::Subst => {
+                for _ in 0..count {
+                    let is_match = complementary_base(query[query_index]) == target[target_index - 1];
+                    assert_eq!(is_match, operations.operation == AlignmentOperation::Match);
+                    query_index += 1;
+                    target_index -= 1;
+                }
+            },
+            AlignmentOperation::Insertion => query_index += count,
+            AlignmentOperation::Deletion => target_index -= count,
+            AlignmentOperation::AmbiguousMatch => unreachable!(),
+        }
+    }
+    assert_eq!(query_index, alignment.position.query.1 as usize);
+    assert_eq!(target_index, alignment.position.target.0 as usize);
+}
+
+// Half of the queries are reverse complementary
+fn get_queries_of_both_strands() -> Vec<Vec<u8>> {
+    let mut queries = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    while let Some(mut record) = fasta_reader.next() {
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        if queries.len() % 2 == 1 {
+            query = reverse_complementary(&query);
+        }
+        queries.push(query);
+        if queries.len() == NUM_QUERIES {
+            break;
+        }
+    }
+    queries
+}
+
+fn reverse_complementary(query: &[u8]) -> Vec<u8> {
+    query.iter().rev().map(|x| complementary_base(*x)).collect()
+}
+fn complementary_base(base: u8) -> u8 {
+    match base {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'G' => b'C',
+        b'C' => b'G',
+        _ => base,
+    }
+}
@@ -0,0 +1,119 @@
+use crate::common::{
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    init_logger,
+};
+use ahash::AHashSet;
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    StrandMode,
+    results::{ReadAlignmentResult, LabeledTargetAlignmentResult},
+};
+use sigalign_core::results::AnchorAlignmentResult;
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    fastq::FastqReader,
+    SeqRecord, IdRefRecord,
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn streamed_results_are_identical_to_align_fasta_and_fastq() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+
+    let mut fasta_bytes = Vec::new();
+    let mut fastq_bytes = Vec::new();
+    let mut fasta_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    let mut count = 0;
+    while let Some(mut record) = fasta_reader.next() {
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        let id = record.id_str().unwrap();
+        fasta_bytes.extend_from_slice(format!(">{}\n", id).as_bytes());
+        fasta_bytes.extend_from_slice(&query);
+        fasta_bytes.push(b'\n');
+        fastq_bytes.extend_from_slice(format!("@{}\n", id).as_bytes());
+        fastq_bytes.extend_from_slice(&query);
+        fastq_bytes.extend_from_slice(b"\n+\n");
+        fastq_bytes.extend(b"#5?I".iter().cycle().take(query.len()));
+        fastq_bytes.push(b'\n');
+        count += 1;
+        if count == NUM_QUERIES {
+            break;
+        }
+    }
+
+    for strand_mode in [StrandMode::Forward, StrandMode::Both] {
+        info!("Strand mode: {:?}", strand_mode);
+        aligner.set_strand_mode(strand_mode);
+
+        info!("Stream FASTA");
+        let collected_result = aligner.align_fasta(&reference, &fasta_bytes[..]).0;
+        assert!(!collected_result.is_empty());
+        let streamed_result: Vec<ReadAlignmentResult> = aligner.align_reads(
+            &reference,
+            FastaReader::new(&fasta_bytes[..]),
+        ).map(|x| x.unwrap()).collect();
+        assert_same_results(&collected_result, &streamed_result);
+
+        info!("Stream FASTQ");
+        let collected_result = aligner.align_fastq(&reference, &fastq_bytes[..], None).0;
+        assert!(collected_result.iter().all(|x| x.quality.is_some()));
+        let streamed_result: Vec<ReadAlignmentResult> = aligner.align_reads(
+            &reference,
+            FastqReader::new(&fastq_bytes[..]),
+        ).map(|x| x.unwrap()).collect();
+        assert_same_results(&collected_result, &streamed_result);
+    }
+}
+
+#[test]
+fn error_in_record_is_yielded() {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.2).unwrap();
+
+    // Invalid FASTQ: the record does not start with '@'
+    let fastq_bytes = b"read_1\nACGT\n+\nIIII\n";
+    let mut streamed = aligner.align_reads(&reference, FastqReader::new(&fastq_bytes[..]));
+    assert!(matches!(streamed.next(), Some(Err(_))));
+}
+
+fn assert_same_results(
+    collected_result: &[ReadAlignmentResult],
+    streamed_result: &[ReadAlignmentResult],
+) {
+    assert_eq!(collected_result.len(), streamed_result.len());
+    for (collected, streamed) in collected_result.iter().zip(streamed_result.iter()) {
+        assert_eq!(collected.read, streamed.read);
+        assert_eq!(collected.is_forward, streamed.is_forward);
+        assert_eq!(collected.quality, streamed.quality);
+        assert_eq!(
+            get_set_of_read_alignment_result(collected),
+            get_set_of_read_alignment_result(streamed),
+        );
+    }
+}
+
+fn get_set_of_read_alignment_result(read_alignment_result: &ReadAlignmentResult) -> AHashSet<(u32, AnchorAlignmentResult)> {
+    let mut result_set = AHashSet::new();
+    for LabeledTargetAlignmentResult {
+        index,
+        label: _,
+        alignments,
+        cutoff_tier: _,
+        metadata: _,
+    } in &read_alignment_result.result.0 {
+        for alignment in alignments {
+            result_set.insert((*index, alignment.clone()));
+        }
+    }
+    result_set
+}
@@ -0,0 +1,128 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    SubstitutionMatrix,
+    results::{AnchorAlignmentResult, AlignmentOperation},
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn uniform_substitution_matrix_is_same_as_mismatch_penalty() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let substitution_matrix = SubstitutionMatrix::new_transition_transversion(4, 4).unwrap();
+    let mut aligner_with_matrix = Aligner::new_with_substitution_matrix(substitution_matrix, 6, 2, 50, 0.1).unwrap();
+    assert_eq!(aligner.get_pattern_size(), aligner_with_matrix.get_pattern_size());
+
+    for (mode, change_to_semi_global) in [("local", false), ("semi-global", true)] {
+        info!("Mode: {}", mode);
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+            aligner_with_matrix.change_to_semi_global();
+        }
+        for query in &queries {
+            assert_eq!(
+                get_set_of_alignment_result(&aligner.align_query(&reference, query)),
+                get_set_of_alignment_result(&aligner_with_matrix.align_query(&reference, query)),
+            );
+        }
+    }
+}
+
+#[test]
+fn penalties_are_calculated_with_substitution_matrix() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let (gap_open_penalty, gap_extend_penalty) = (6, 2);
+    let (min_length, max_penalty_per_length) = (50, 0.15);
+    let substitution_matrix = SubstitutionMatrix::new_transition_transversion(2, 6).unwrap();
+    let mut aligner = Aligner::new_with_substitution_matrix(
+        substitution_matrix.clone(),
+        gap_open_penalty,
+        gap_extend_penalty,
+        min_length,
+        max_penalty_per_length,
+    ).unwrap();
+    assert_eq!(aligner.get_mismatch_penalty(), 2);
+
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        let mut alignment_count = 0;
+        for query in &queries {
+            let result = aligner.align_query(&reference, query);
+            for target_result in result.0 {
+                let target = reference.get_sequence(target_result.index).unwrap();
+                for alignment in target_result.alignments {
+                    let penalty = calculate_penalty(
+                        &alignment, query, &target, &substitution_matrix, gap_open_penalty, gap_extend_penalty,
+                    );
+                    assert_eq!(alignment.penalty, penalty);
+                    assert!(alignment.length >= min_length);
+                    assert!(alignment.penalty as f32 / alignment.length as f32 <= max_penalty_per_length);
+                    alignment_count += 1;
+                }
+            }
+        }
+        info!("Alignment count: {}", alignment_count);
+        assert!(alignment_count > 0);
+    }
+}
+
+fn calculate_penalty(
+    alignment: &AnchorAlignmentResult,
+    query: &[u8],
+    target: &[u8],
+    substitution_matrix: &SubstitutionMatrix,
+    gap_open_penalty: u32,
+    gap_extend_penalty: u32,
+) -> u32 {
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.0 as usize;
+    let mut penalty = 0;
+    for operations in &alignment.operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match => {
+                assert_eq!(query[query_index..query_index + count], target[target_index..target_index + count]);
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::Subst => {
+                for _ in 0..count {
+                    penalty += substitution_matrix.penalty_of_pair(target[target_index], query[query_index]);
+                    query_index += 1;
+                    target_index += 1;
+                }
+            },
+            AlignmentOperation::Deletion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                target_index += count;
+            },
+            AlignmentOperation::Insertion => {
+                penalty += gap_open_penalty + gap_extend_penalty * count as u32;
+                query_index += count;
+            },
+            AlignmentOperation::AmbiguousMatch => unreachable!(),
+        }
+    }
+    assert_eq!(query_index, alignment.position.query.1 as usize);
+    assert_eq!(target_index, alignment.position.target.1 as usize);
+    penalty
+}
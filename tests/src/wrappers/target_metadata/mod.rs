@@ -0,0 +1,151 @@
+#[cfg(test)]
+use crate::common::{
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+#[cfg(test)]
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+    TargetMetadata,
+};
+
+#[cfg(test)]
+const METADATA_TSV: &str = "\
+#label\ttaxonomy_id\tsource\tnote
+target_1\t9606\tgenome\t
+target_3\t\tplasmid\tcircular
+unknown\t1\tignored\tignored
+";
+
+#[test]
+fn metadata_is_kept_and_serialized() {
+    init_logger();
+
+    let (fasta, sequence) = get_fasta_and_sequence();
+    let reference = ReferenceBuilder::new()
+        .keep_descriptions(true)
+        .add_metadata_tsv(METADATA_TSV.as_bytes()).unwrap()
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+
+    let expected_metadata = [
+        TargetMetadata {
+            description: "first target  with spaces".to_string(),
+            length: 400,
+            taxonomy_id: Some(9606),
+            attributes: vec![("source".to_string(), "genome".to_string())],
+        },
+        TargetMetadata {
+            description: String::new(),
+            length: 300,
+            taxonomy_id: None,
+            attributes: Vec::new(),
+        },
+        TargetMetadata {
+            description: "third".to_string(),
+            length: 200,
+            taxonomy_id: None,
+            attributes: vec![
+                ("source".to_string(), "plasmid".to_string()),
+                ("note".to_string(), "circular".to_string()),
+            ],
+        },
+    ];
+    for (target_index, metadata) in expected_metadata.iter().enumerate() {
+        assert_eq!(reference.get_metadata(target_index as u32).as_ref(), Some(metadata));
+    }
+    assert_eq!(reference.get_metadata(3), None);
+    assert_eq!(reference.get_sequence(0).unwrap(), &sequence[..400]);
+
+    // Serialized with the reference
+    let mut buffer = Vec::new();
+    reference.save_to(&mut buffer).unwrap();
+    let loaded_reference = Reference::load_from(&buffer[..]).unwrap();
+    for (target_index, metadata) in expected_metadata.iter().enumerate() {
+        assert_eq!(loaded_reference.get_metadata(target_index as u32).as_ref(), Some(metadata));
+    }
+
+    // Split into shards
+    let collection = ReferenceBuilder::new()
+        .keep_descriptions(true)
+        .num_threads(3)
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .add_metadata_tsv(METADATA_TSV.as_bytes()).unwrap()
+        .build_collection().unwrap();
+    assert!(collection.get_num_shards() > 1);
+    for (target_index, metadata) in expected_metadata.iter().enumerate() {
+        assert_eq!(collection.get_metadata(target_index as u32).unwrap().as_ref(), Some(metadata));
+    }
+}
+
+#[test]
+fn only_length_without_metadata() {
+    init_logger();
+
+    let (fasta, _) = get_fasta_and_sequence();
+    let reference = ReferenceBuilder::new()
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .build().unwrap();
+    for (target_index, length) in [400, 300, 200].into_iter().enumerate() {
+        assert_eq!(
+            reference.get_metadata(target_index as u32),
+            Some(TargetMetadata { length, ..Default::default() }),
+        );
+    }
+}
+
+#[test]
+fn metadata_is_copied_into_results_optionally() {
+    init_logger();
+
+    let (fasta, sequence) = get_fasta_and_sequence();
+    let reference = ReferenceBuilder::new()
+        .keep_descriptions(true)
+        .add_fasta(fasta.as_bytes()).unwrap()
+        .add_metadata_tsv(METADATA_TSV.as_bytes()).unwrap()
+        .build().unwrap();
+    let query = &sequence[450..650];
+
+    let mut aligner = Aligner::new(4, 6, 2, 100, 0.1).unwrap();
+    assert!(!aligner.get_metadata_in_results());
+    let result = aligner.align_query_labeled(&reference, query);
+    assert!(!result.0.is_empty());
+    assert!(result.0.iter().all(|target_result| target_result.metadata.is_none()));
+
+    aligner.set_metadata_in_results(true);
+    let result = aligner.align_query_labeled(&reference, query);
+    assert!(!result.0.is_empty());
+    for target_result in &result.0 {
+        assert_eq!(target_result.metadata, reference.get_metadata(target_result.index));
+    }
+}
+
+#[test]
+fn invalid_metadata_tsv_is_rejected() {
+    for tsv in [
+        "label\ttaxonomy_id\ntarget_1\tnot_a_number\n",
+        "label\tsource\ntarget_1\tgenome\textra\n",
+        "label\tsource\n\tgenome\n",
+    ] {
+        assert!(ReferenceBuilder::new().add_metadata_tsv(tsv.as_bytes()).is_err());
+    }
+}
+
+// Three targets (400, 300, and 200 bp) from the sequence for validation
+#[cfg(test)]
+fn get_fasta_and_sequence() -> (String, Vec<u8>) {
+    let sequence = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap()
+        .get_sequence(0).unwrap();
+    let sequence = sequence[..900].to_vec();
+    let fasta = format!(
+        ">target_1 first target  with spaces\n{}\n>target_2\n{}\n>target_3 third\n{}\n",
+        String::from_utf8(sequence[..400].to_vec()).unwrap(),
+        String::from_utf8(sequence[400..700].to_vec()).unwrap(),
+        String::from_utf8(sequence[700..].to_vec()).unwrap(),
+    );
+    (fasta, sequence)
+}
@@ -0,0 +1,81 @@
+use crate::common::{
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    SubstitutionMatrix,
+    GeneticCode,
+};
+use sigalign_utils::sequence_manipulation::{
+    reverse_complementary::reverse_complement_of_dna_sequence,
+    translation::{translate_dna_sequence, six_frame_translation_of_dna_sequence},
+};
+
+#[test]
+fn codons_are_translated_by_genetic_code() {
+    let dna = b"ATGTGGTGAAGAnnnTAA";
+    assert_eq!(translate_dna_sequence(dna, GeneticCode::Standard), b"MW*RX*");
+    let vertebrate_mitochondrial = GeneticCode::from_ncbi_table_id(2).unwrap();
+    assert_eq!(translate_dna_sequence(dna, vertebrate_mitochondrial), b"MWW*X*");
+    assert!(GeneticCode::from_ncbi_table_id(7).is_none());
+
+    let frames = six_frame_translation_of_dna_sequence(b"ATGGCCTAAC", GeneticCode::Standard);
+    assert_eq!(
+        frames,
+        vec![
+            (1, b"MA*".to_vec()), (2, b"WPN".to_vec()), (3, b"GL".to_vec()),
+            (-1, b"VRP".to_vec()), (-2, b"LGH".to_vec()), (-3, b"*A".to_vec()),
+        ],
+    );
+}
+
+#[test]
+fn translated_query_is_aligned_with_nucleotide_position() {
+    init_logger();
+
+    // Protein reference translated from the DNA in the first frame
+    let dna_reference = ReferenceBuilder::new()
+        .add_fasta_file(get_ref_for_val_path()).unwrap()
+        .build().unwrap();
+    let dna = (0..dna_reference.get_num_targets()).map(|target_index| {
+        dna_reference.get_sequence(target_index).unwrap()
+    }).find(|sequence| sequence.len() >= 900).unwrap();
+    let dna = &dna[..900];
+    let protein = translate_dna_sequence(dna, GeneticCode::Standard);
+    let fasta = format!(">protein\n{}\n", String::from_utf8(protein.clone()).unwrap());
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+
+    let mut aligner = Aligner::new_with_substitution_matrix(SubstitutionMatrix::blosum62(), 12, 2, 30, 0.1).unwrap();
+    for query_start in [100, 201, 302] {
+        let forward_query = dna[query_start..query_start + 450].to_vec();
+        let reverse_query = reverse_complement_of_dna_sequence(&forward_query);
+        // Frame of the query that is in the same frame as the reference
+        let frame = ((3 - query_start % 3) % 3 + 1) as i8;
+        for (query, expected_frame) in [(forward_query, frame), (reverse_query, -frame)] {
+            let result = aligner.align_query_translated(&reference, &query, GeneticCode::Standard);
+            assert!(result.0.iter().flat_map(|x| x.alignments.iter()).any(|x| {
+                x.frame == expected_frame && x.alignment.penalty == 0 && x.alignment.position.query.1 - x.alignment.position.query.0 >= 140
+            }));
+            for translated_alignment in result.0.iter().flat_map(|x| x.alignments.iter()) {
+                let (start, end) = translated_alignment.nucleotide_query_position;
+                assert_eq!((end - start) % 3, 0);
+                let aligned_bases = if translated_alignment.frame > 0 {
+                    query[start as usize..end as usize].to_vec()
+                } else {
+                    reverse_complement_of_dna_sequence(&query[start as usize..end as usize])
+                };
+                let (query_start, query_end) = translated_alignment.alignment.position.query;
+                assert_eq!(aligned_bases.len() as u32, (query_end - query_start) * 3);
+                if translated_alignment.alignment.penalty == 0 {
+                    let (target_start, target_end) = translated_alignment.alignment.position.target;
+                    assert_eq!(
+                        translate_dna_sequence(&aligned_bases, GeneticCode::Standard),
+                        protein[target_start as usize..target_end as usize],
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::common::{
+    get_set_of_alignment_result,
+    get_queries,
+    test_data_path::get_ref_for_val_path,
+    init_logger,
+};
+use log::info;
+use sigalign::{
+    ReferenceBuilder,
+    Aligner,
+    results::{AnchorAlignmentResult, AlignmentOperation},
+};
+
+const NUM_QUERIES: usize = 100;
+
+#[test]
+fn unreachable_second_gap_is_same_as_single_gap() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    // The second piece is cheaper only for gaps longer than any gap allowed by the cutoff.
+    let mut aligner = Aligner::new(4, 6, 2, 50, 0.1).unwrap();
+    let mut two_piece_aligner = Aligner::new_with_two_piece_gap(4, 6, 2, 10_000, 1, 50, 0.1).unwrap();
+    assert_eq!(two_piece_aligner.get_second_gap_penalties(), Some((10_000, 1)));
+
+    for (mode, change_to_semi_global) in [("local", false), ("semi-global", true)] {
+        info!("Mode: {}", mode);
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+            two_piece_aligner.change_to_semi_global();
+        }
+        for query in &queries {
+            assert_eq!(
+                get_set_of_alignment_result(&aligner.align_query(&reference, query)),
+                get_set_of_alignment_result(&two_piece_aligner.align_query(&reference, query)),
+            );
+        }
+    }
+}
+
+#[test]
+fn penalties_are_calculated_with_two_piece_gap() {
+    init_logger();
+
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    let queries = get_queries(NUM_QUERIES);
+
+    let gap_penalties = [(6, 2), (12, 1)];
+    let (min_length, max_penalty_per_length) = (50, 0.15);
+    let mut aligner = Aligner::new_with_two_piece_gap(
+        4,
+        gap_penalties[0].0,
+        gap_penalties[0].1,
+        gap_penalties[1].0,
+        gap_penalties[1].1,
+        min_length,
+        max_penalty_per_length,
+    ).unwrap();
+
+    for change_to_semi_global in [false, true] {
+        if change_to_semi_global {
+            aligner.change_to_semi_global();
+        }
+        let mut alignment_count = 0;
+        for query in &queries {
+            let result = aligner.align_query(&reference, query);
+            for target_result in result.0 {
+                let target = reference.get_sequence(target_result.index).unwrap();
+                for alignment in target_result.alignments {
+                    let penalty = calculate_penalty(&alignment, query, &target, 4, &gap_penalties);
+                    assert_eq!(alignment.penalty, penalty);
+                    assert!(alignment.length >= min_length);
+                    assert!(alignment.penalty as f32 / alignment.length as f32 <= max_penalty_per_length);
+                    alignment_count += 1;
+                }
+            }
+        }
+        info!("Alignment count: {}", alignment_count);
+        assert!(alignment_count > 0);
+    }
+}
+
+#[test]
+fn long_gap_is_aligned_with_second_gap() {
+    init_logger();
+
+    // Pseudo-random target and the query with a long deletion
+    let mut seed: u32 = 7;
+    let target: Vec<u8> = (0..200).map(|_| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        b"ACGT"[((seed >> 16) % 4) as usize]
+    }).collect();
+    let query: Vec<u8> = [&target[..100], &target[130..]].concat();
+    let fasta = [b">target\n".as_slice(), &target, b"\n"].concat();
+    let reference = ReferenceBuilder::new().add_fasta(&fasta[..]).unwrap().build().unwrap();
+
+    // Single gap: 6 + 2*30 = 66 (> 0.3 * 200)
+    // Two-piece gap: 12 + 1*30 = 42 (<= 0.3 * 200)
+    let mut aligner = Aligner::new(4, 6, 2, 100, 0.3).unwrap();
+    let mut two_piece_aligner = Aligner::new_with_two_piece_gap(4, 6, 2, 12, 1, 100, 0.3).unwrap();
+    aligner.change_to_semi_global();
+    two_piece_aligner.change_to_semi_global();
+
+    assert_eq!(aligner.align_query(&reference, &query).count_alignments(), 0);
+    let result = two_piece_aligner.align_query(&reference, &query);
+    let alignments: Vec<_> = result.0.iter().flat_map(|target_result| target_result.alignments.iter()).collect();
+    assert_eq!(alignments.len(), 1);
+    assert_eq!(alignments[0].penalty, 42);
+    assert_eq!(calculate_penalty(alignments[0], &query, &target, 4, &[(6, 2), (12, 1)]), 42);
+}
+
+#[test]
+fn invalid_second_gap_is_rejected() {
+    // Second gap-open penalty is not larger than the first
+    assert!(Aligner::new_with_two_piece_gap(4, 6, 2, 6, 1, 50, 0.1).is_err());
+    // Second gap-extend penalty is not smaller than the first
+    assert!(Aligner::new_with_two_piece_gap(4, 6, 2, 12, 2, 50, 0.1).is_err());
+}
+
+fn calculate_penalty(
+    alignment: &AnchorAlignmentResult,
+    query: &[u8],
+    target: &[u8],
+    mismatch_penalty: u32,
+    gap_penalties: &[(u32, u32)],
+) -> u32 {
+    let gap_penalty = |count: u32| {
+        gap_penalties.iter().map(|(o, e)| o + e * count).min().unwrap()
+    };
+    let mut query_index = alignment.position.query.0 as usize;
+    let mut target_index = alignment.position.target.0 as usize;
+    let mut penalty = 0;
+    for operations in &alignment.operations {
+        let count = operations.count as usize;
+        match operations.operation {
+            AlignmentOperation::Match => {
+                assert_eq!(query[query_index..query_index + count], target[target_index..target_index + count]);
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::Subst => {
+                penalty += mismatch_penalty * count as u32;
+                query_index += count;
+                target_index += count;
+            },
+            AlignmentOperation::Deletion => {
+                penalty += gap_penalty(count as u32);
+                target_index += count;
+            },
+            AlignmentOperation::Insertion => {
+                penalty += gap_penalty(count as u32);
+                query_index += count;
+            },
+            AlignmentOperation::AmbiguousMatch => unreachable!(),
+        }
+    }
+    assert_eq!(query_index, alignment.position.query.1 as usize);
+    assert_eq!(target_index, alignment.position.target.1 as usize);
+    penalty
+}
@@ -0,0 +1,244 @@
+use std::ops::Sub;
+
+use crate::common::{
+    init_logger,
+    test_data_path::{
+        get_ref_for_val_path,
+        get_qry_for_val_path,
+    },
+    dynamic_programming_matrix::{
+        DpPenalties,
+        semi_global_with_dpm_only_to_pattern_matched_targets,
+        local_all_substring_with_dpm_only_to_pattern_matched_targets,
+    },
+};
+use ahash::AHashSet;
+use sigalign::{
+    Reference,
+    ReferenceBuilder,
+    Aligner,
+    SubstitutionMatrix,
+    results::{AlignmentResult, AlignmentPosition},
+};
+use sigalign_utils::sequence_reader::{
+    fasta::FastaReader,
+    SeqRecord, IdRecord,
+};
+use log::info;
+
+const NUM_QUERIES: usize = 20;
+const MIN_LENGTH: u32 = 100;
+const MAX_PENALTY_PER_LENGTH: f32 = 0.1;
+
+#[test]
+fn validate_semi_global_mode_with_dp_matrix() {
+    init_logger();
+    info!("Start to validate semi-global result with DP matrix");
+
+    let mut aligner = Aligner::new(4, 6, 2, MIN_LENGTH, MAX_PENALTY_PER_LENGTH).unwrap();
+    let penalties = DpPenalties::new(4, 6, 2);
+    validate_semi_global_result_with_dp_matrix(&mut aligner, &penalties);
+}
+#[test]
+fn validate_semi_global_mode_with_substitution_matrix() {
+    init_logger();
+    info!("Start to validate semi-global result with substitution matrix");
+
+    let substitution_matrix = SubstitutionMatrix::new_transition_transversion(2, 6).unwrap();
+    let mut aligner = Aligner::new_with_substitution_matrix(
+        substitution_matrix.clone(), 6, 2, MIN_LENGTH, MAX_PENALTY_PER_LENGTH,
+    ).unwrap();
+    let penalties = DpPenalties::new_with_substitution_matrix(substitution_matrix, 6, 2);
+    validate_semi_global_result_with_dp_matrix(&mut aligner, &penalties);
+}
+#[test]
+fn validate_semi_global_mode_with_two_piece_gap() {
+    init_logger();
+    info!("Start to validate semi-global result with two-piece gap");
+
+    let mut aligner = Aligner::new_with_two_piece_gap(
+        4, 6, 2, 12, 1, MIN_LENGTH, MAX_PENALTY_PER_LENGTH,
+    ).unwrap();
+    let penalties = DpPenalties::new_with_two_piece_gap(4, 6, 2, 12, 1);
+    validate_semi_global_result_with_dp_matrix(&mut aligner, &penalties);
+}
+
+fn validate_semi_global_result_with_dp_matrix(
+    aligner: &mut Aligner,
+    penalties: &DpPenalties,
+) {
+    let reference = ReferenceBuilder::new().add_fasta_file(get_ref_for_val_path()).unwrap().build().unwrap();
+    aligner.change_to_semi_global();
+
+    let mut qry_reader = FastaReader::from_path(get_qry_for_val_path()).unwrap();
+    let mut qry_count = 0;
+    let mut dpm_alignment_count = 0;
+    while let Some(mut record) = qry_reader.next() {
+        if qry_count == NUM_QUERIES { break };
+        qry_count += 1;
+        let mut query = Vec::new();
+        record.extend_seq_buf(&mut query);
+        let mut label = String::new();
+        record.extend_id_string(&mut label).unwrap();
+        info!(" - query label: {}", label);
+
+        let dpm_result = get_semi_global_result_with_dp_matrix(&query, &reference, aligner, penalties);
+        let sigalign_result = aligner.align_query(&reference, &query);
+
+        assert_sigalign_result_includes_the_dpm_result(&sigalign_result, &dpm_result);
+        dpm_alignment_count += dpm_result.count_alignments();
+    }
+    info!("DPM alignment count: {}", dpm_alignment_count);
+    assert!(dpm_alignment_count > 0);
+}
+
+fn get_semi_global_result_with_dp_matrix(
+    query: &[u8],
+    reference: &Reference,
+    aligner: &Aligner,
+    penalties: &DpPenalties,
+) -> AlignmentResult {
+    semi_global_with_dpm_only_to_pattern_matched_targets(
+        query,
+        reference,
+        aligner.get_pattern_size(),
+        penalties,
+        MIN_LENGTH,
+        MAX_PENALTY_PER_LENGTH,
+    )
+}
+
+// The DP matrices of all substrings of the query are too slow for the validation data,
+// so the local mode is validated with the shorter targets and queries.
+const LOCAL_NUM_TARGETS: usize = 30;
+const LOCAL_TARGET_LENGTH: usize = 120;
+const LOCAL_NUM_QUERIES: usize = 3;
+const LOCAL_MIN_LENGTH: u32 = 40;
+const LOCAL_MAX_PENALTY_PER_LENGTH: f32 = 0.2;
+
+#[test]
+fn validate_local_mode_with_dp_matrix() {
+    init_logger();
+    info!("Start to validate local result with DP matrix");
+
+    let mut aligner = Aligner::new(4, 6, 2, LOCAL_MIN_LENGTH, LOCAL_MAX_PENALTY_PER_LENGTH).unwrap();
+    let penalties = DpPenalties::new(4, 6, 2);
+    validate_local_result_with_dp_matrix(&mut aligner, &penalties);
+}
+#[test]
+fn validate_local_mode_with_substitution_matrix() {
+    init_logger();
+    info!("Start to validate local result with substitution matrix");
+
+    let substitution_matrix = SubstitutionMatrix::new_transition_transversion(2, 6).unwrap();
+    let mut aligner = Aligner::new_with_substitution_matrix(
+        substitution_matrix.clone(), 6, 2, LOCAL_MIN_LENGTH, LOCAL_MAX_PENALTY_PER_LENGTH,
+    ).unwrap();
+    let penalties = DpPenalties::new_with_substitution_matrix(substitution_matrix, 6, 2);
+    validate_local_result_with_dp_matrix(&mut aligner, &penalties);
+}
+#[test]
+fn validate_local_mode_with_two_piece_gap() {
+    init_logger();
+    info!("Start to validate local result with two-piece gap");
+
+    let mut aligner = Aligner::new_with_two_piece_gap(
+        4, 6, 2, 12, 1, LOCAL_MIN_LENGTH, LOCAL_MAX_PENALTY_PER_LENGTH,
+    ).unwrap();
+    let penalties = DpPenalties::new_with_two_piece_gap(4, 6, 2, 12, 1);
+    validate_local_result_with_dp_matrix(&mut aligner, &penalties);
+}
+
+fn validate_local_result_with_dp_matrix(
+    aligner: &mut Aligner,
+    penalties: &DpPenalties,
+) {
+    let (reference, queries) = get_short_reference_and_queries_for_local_mode();
+    aligner.change_to_local();
+
+    let mut dpm_alignment_count = 0;
+    for query in queries {
+        let dpm_result = local_all_substring_with_dpm_only_to_pattern_matched_targets(
+            &query,
+            &reference,
+            aligner.get_pattern_size(),
+            penalties,
+            LOCAL_MIN_LENGTH,
+            LOCAL_MAX_PENALTY_PER_LENGTH,
+        );
+        let sigalign_result = aligner.align_query(&reference, &query);
+
+        assert_sigalign_result_includes_the_dpm_result(&sigalign_result, &dpm_result);
+        dpm_alignment_count += dpm_result.count_alignments();
+    }
+    info!("DPM alignment count: {}", dpm_alignment_count);
+    assert!(dpm_alignment_count > 0);
+}
+
+// The targets are the prefixes of the validation targets.
+// Each query is a mutated segment of a target, flanked by the bases of the other target,
+// so that the alignments do not cover the whole query.
+fn get_short_reference_and_queries_for_local_mode() -> (Reference, Vec<Vec<u8>>) {
+    let mut targets = Vec::new();
+    let mut ref_reader = FastaReader::from_path(get_ref_for_val_path()).unwrap();
+    while let Some(mut record) = ref_reader.next() {
+        let mut target = Vec::new();
+        record.extend_seq_buf(&mut target);
+        target.truncate(LOCAL_TARGET_LENGTH);
+        targets.push(target);
+        if targets.len() == LOCAL_NUM_TARGETS { break };
+    }
+    let fasta: String = targets.iter().enumerate().map(|(index, target)| {
+        format!(">target_{}\n{}\n", index, String::from_utf8(target.clone()).unwrap())
+    }).collect();
+    let reference = ReferenceBuilder::new().add_fasta(fasta.as_bytes()).unwrap().build().unwrap();
+
+    let queries = (0..LOCAL_NUM_QUERIES).map(|query_index| {
+        let target = &targets[query_index * 3 % LOCAL_NUM_TARGETS];
+        let flank = &targets[(query_index * 3 + 7) % LOCAL_NUM_TARGETS];
+        let mut segment = target[50..120].to_vec();
+        // A substitution and a deletion
+        segment[15] = if segment[15] == b'A' { b'C' } else { b'A' };
+        segment.remove(30 + query_index);
+        [&flank[..10], &segment, &flank[100..110]].concat()
+    }).collect();
+    (reference, queries)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct EquivalentResult {
+    target_index: u32,
+    penalty: u32,
+    length: u32,
+    position: AlignmentPosition,
+}
+
+fn assert_sigalign_result_includes_the_dpm_result(
+    sigalign_result: &AlignmentResult,
+    dpm_result: &AlignmentResult,
+) {
+    let sigalign_result_set: AHashSet<EquivalentResult> = alignment_result_to_hashset(sigalign_result);
+
+    let dpm_result_set: AHashSet<EquivalentResult> = alignment_result_to_hashset(dpm_result);
+
+    if !sigalign_result_set.is_superset(&dpm_result_set) {
+        println!("#dpm_result:\n{:#?}", &dpm_result);
+        println!("#sigalign_result:\n{:#?}", &sigalign_result);
+        println!("#only in sigalign:\n{:#?}", &sigalign_result_set.sub(&dpm_result_set));
+        println!("#only in dpm:\n{:#?}", &dpm_result_set.sub(&sigalign_result_set));
+        panic!("SigAlign result does not contain the DPM result");
+    }
+}
+
+fn alignment_result_to_hashset(result: &AlignmentResult) -> AHashSet<EquivalentResult> {
+    result.0.iter().flat_map(|x| {
+        x.alignments.iter().map(|y| {
+            EquivalentResult {
+                target_index: x.index,
+                penalty: y.penalty,
+                length: y.length,
+                position: y.position.clone(),
+            }
+        })
+    }).collect()
+}
@@ -146,11 +146,14 @@ fn get_set_of_fasta_alignment_result(fasta_alignment_result: &FastaAlignmentResu
         read,
         is_forward: _,
         result,
+        quality: _,
     } in &fasta_alignment_result.0 {
         for LabeledTargetAlignmentResult {
             index,
             label: _,
             alignments,
+            cutoff_tier: _,
+            metadata: _,
         } in &result.0 {
             for alignment in alignments {
                 result_set.insert((read.clone(), *index, alignment.clone()));
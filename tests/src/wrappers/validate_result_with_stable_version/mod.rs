@@ -122,11 +122,13 @@ fn fasta_alignment_result_to_result_map(fasta_alignment_result: FastaAlignmentRe
         read,
         is_forward: _,
         result,
+        quality: _,
     } in fasta_alignment_result.0 {
         let target_results = result.0.into_iter().map(|x| {
             TargetAlignmentResult {
                 index: x.index,
-                alignments: x.alignments
+                alignments: x.alignments,
+                cutoff_tier: x.cutoff_tier,
             }
         }).collect();
         result_map.insert(read, AlignmentResult(target_results));